@@ -10,6 +10,24 @@ use std::path::Path;
 pub struct TranscriptionResult {
     pub segments: Vec<TranscriptionSegment>,
     pub full_text: String,
+    /// Language the audio was transcribed in (None = not known to the backend)
+    pub language: Option<String>,
+    /// How sure language detection was of it (None = the language was set, not detected)
+    pub language_probability: Option<f32>,
+}
+
+impl TranscriptionResult {
+    /// Shift all timestamps by `offset_ms` (position of the audio in a longer recording)
+    pub fn offset(&mut self, offset_ms: i64) {
+        for segment in &mut self.segments {
+            segment.start_ms += offset_ms;
+            segment.end_ms += offset_ms;
+            for word in &mut segment.words {
+                word.start_ms += offset_ms;
+                word.end_ms += offset_ms;
+            }
+        }
+    }
 }
 
 /// A segment of transcribed text with timestamps
@@ -18,6 +36,116 @@ pub struct TranscriptionSegment {
     pub text: String,
     pub start_ms: i64,
     pub end_ms: i64,
+    /// Word timings and confidences, when the backend provides them
+    pub words: Vec<WordTiming>,
+}
+
+/// A word with its timing and how sure the model was of it
+#[derive(Debug, Clone, PartialEq)]
+pub struct WordTiming {
+    pub text: String,
+    pub start_ms: i64,
+    pub end_ms: i64,
+    /// Mean probability of the word's tokens (0.0 to 1.0)
+    pub probability: f32,
+}
+
+/// A decoded token: its text as the model spells it (a leading space or SentencePiece
+/// marker starts a new word), timing and probability
+#[derive(Debug, Clone)]
+pub struct TokenTiming {
+    pub text: String,
+    pub start_ms: i64,
+    pub end_ms: i64,
+    pub probability: f32,
+}
+
+/// Join sub-word tokens into words
+pub fn words_from_tokens(tokens: impl IntoIterator<Item = TokenTiming>) -> Vec<WordTiming> {
+    let mut words: Vec<WordTiming> = Vec::new();
+    // Token count of the word being built, for the mean probability
+    let mut pieces = 0usize;
+    let mut word_break = true;
+    for token in tokens {
+        let text = token.text.replace('\u{2581}', " ");
+        let starts_word = word_break || text.starts_with(' ');
+        word_break = text.ends_with(' ');
+        let text = text.trim();
+        if text.is_empty() {
+            continue;
+        }
+        match words.last_mut() {
+            Some(word) if !starts_word => {
+                word.text.push_str(text);
+                word.end_ms = word.end_ms.max(token.end_ms);
+                word.probability =
+                    (word.probability * pieces as f32 + token.probability) / (pieces + 1) as f32;
+                pieces += 1;
+            }
+            _ => {
+                words.push(WordTiming {
+                    text: text.to_string(),
+                    start_ms: token.start_ms,
+                    end_ms: token.end_ms,
+                    probability: token.probability,
+                });
+                pieces = 1;
+            }
+        }
+    }
+    words
+}
+
+/// Which language to transcribe one piece of audio in
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LanguageOptions {
+    /// Language code or "auto" (None = the backend's language setting)
+    pub language: Option<String>,
+    /// Languages auto-detection may pick from (empty = any)
+    pub candidates: Vec<String>,
+}
+
+/// The most probable of the detected languages, among `candidates` when there are any.
+/// `probabilities` pairs each language code with its detection probability.
+pub fn pick_language<'a>(
+    probabilities: impl IntoIterator<Item = (&'a str, f32)>,
+    candidates: &[String],
+) -> Option<(&'a str, f32)> {
+    probabilities
+        .into_iter()
+        .filter(|(code, _)| candidates.is_empty() || candidates.iter().any(|c| c == code))
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+}
+
+/// How backends that decode frame-level outputs themselves (CTC) search for the text
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct DecoderOptions {
+    /// Hypotheses kept per frame; 1 = greedy decoding
+    pub beam_width: usize,
+    /// ARPA n-gram language model fused into the beam search (None = no LM)
+    pub lm_path: Option<String>,
+    /// Weight of the language model's log probability
+    pub lm_weight: f32,
+    /// Score added per word with a language model, offsetting its preference for fewer words
+    pub word_bonus: f32,
+    /// Words the beam search favours (names, product terms)
+    pub hotwords: Vec<String>,
+    /// Score added for each hotword
+    pub hotword_boost: f32,
+}
+
+impl Default for DecoderOptions {
+    fn default() -> Self {
+        Self {
+            beam_width: 1,
+            lm_path: None,
+            lm_weight: 0.5,
+            word_bonus: 1.0,
+            hotwords: Vec::new(),
+            hotword_boost: 10.0,
+        }
+    }
 }
 
 /// Available ASR backend types
@@ -67,9 +195,61 @@ pub trait AsrBackend: Send + Sync {
     /// Get the current language setting
     fn language(&self) -> &str;
 
+    /// Configure decoding (beam search, language model, hotwords).
+    /// Backends whose decoder is built in ignore it.
+    fn set_decoder_options(&mut self, _options: &DecoderOptions) -> Result<()> {
+        Ok(())
+    }
+
+    /// Swap in a decoder built beforehand (`CtcDecoder::new` may load a large language model).
+    /// Backends whose decoder is built in ignore it.
+    #[cfg(feature = "parakeet")]
+    fn set_decoder(&mut self, _decoder: super::ctc_decoder::CtcDecoder) {}
+
+    /// Set the CPU threads one transcription may use (0 = the backend's default).
+    /// Backends that don't run transcriptions in parallel ignore it.
+    fn set_threads(&mut self, _threads: usize) {}
+
+    /// Whether transcriptions can run in parallel, each on decoder state of its own.
+    /// Backends that say no take one transcription at a time.
+    fn parallel(&self) -> bool {
+        false
+    }
+
     /// Transcribe audio samples (must be 16kHz mono f32)
     async fn transcribe(&self, samples: &[f32]) -> Result<TranscriptionResult>;
 
+    /// Transcribe with an initial prompt biasing the decoder toward its words and spellings.
+    /// Backends that can't be prompted ignore it.
+    async fn transcribe_with_prompt(
+        &self,
+        samples: &[f32],
+        _prompt: &str,
+    ) -> Result<TranscriptionResult> {
+        self.transcribe(samples).await
+    }
+
+    /// Transcribe with a prompt, in the given language or restricted to candidate languages.
+    /// Backends that can't detect or switch language per call ignore it.
+    async fn transcribe_with_language(
+        &self,
+        samples: &[f32],
+        prompt: &str,
+        _language: &LanguageOptions,
+    ) -> Result<TranscriptionResult> {
+        self.transcribe_with_prompt(samples, prompt).await
+    }
+
+    /// Transcribe speech in any language straight into English text.
+    /// None = the backend can't translate.
+    async fn translate_to_english(
+        &self,
+        _samples: &[f32],
+        _language: &LanguageOptions,
+    ) -> Result<Option<TranscriptionResult>> {
+        Ok(None)
+    }
+
     /// Transcribe with a time offset (for streaming)
     async fn transcribe_with_offset(
         &self,
@@ -77,14 +257,17 @@ pub trait AsrBackend: Send + Sync {
         offset_ms: i64,
     ) -> Result<TranscriptionResult> {
         let mut result = self.transcribe(samples).await?;
-        for segment in &mut result.segments {
-            segment.start_ms += offset_ms;
-            segment.end_ms += offset_ms;
-        }
+        result.offset(offset_ms);
         Ok(result)
     }
 }
 
+/// Languages of the multilingual Parakeet model (TDT 0.6B v3)
+pub const PARAKEET_MULTILINGUAL_LANGUAGES: &[&str] = &[
+    "bg", "cs", "da", "de", "el", "en", "es", "et", "fi", "fr", "hr", "hu", "it", "lt", "lv", "mt",
+    "nl", "pl", "pt", "ro", "ru", "sk", "sl", "sv", "uk",
+];
+
 /// Backend info for display in UI
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct BackendInfo {
@@ -119,12 +302,63 @@ impl BackendInfo {
         }
     }
 
-    pub fn parakeet() -> Self {
+    /// `languages` are those of the Parakeet model in use; "auto" is offered only when there
+    /// is more than one to detect from
+    pub fn parakeet(languages: Vec<String>) -> Self {
+        let auto = (languages.len() > 1).then(|| "auto".to_string());
         Self {
             backend_type: "parakeet".to_string(),
             name: "Parakeet".to_string(),
-            description: "NVIDIA Parakeet - fast English transcription".to_string(),
-            supported_languages: vec!["en".to_string()],
+            description: "NVIDIA Parakeet - fast English and European-language transcription"
+                .to_string(),
+            supported_languages: auto.into_iter().chain(languages).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token(text: &str, start_ms: i64, end_ms: i64, probability: f32) -> TokenTiming {
+        TokenTiming {
+            text: text.to_string(),
+            start_ms,
+            end_ms,
+            probability,
         }
     }
+
+    #[test]
+    fn test_words_from_tokens() {
+        let words = words_from_tokens([
+            token(" Hel", 0, 200, 0.9),
+            token("lo", 200, 400, 0.5),
+            token(" ", 400, 400, 0.1),
+            token("world", 450, 900, 0.8),
+            token("\u{2581}again", 950, 1300, 0.6),
+        ]);
+        let texts: Vec<&str> = words.iter().map(|w| w.text.as_str()).collect();
+        assert_eq!(texts, vec!["Hello", "world", "again"]);
+        assert_eq!((words[0].start_ms, words[0].end_ms), (0, 400));
+        assert!((words[0].probability - 0.7).abs() < 1e-6);
+        assert_eq!((words[2].start_ms, words[2].end_ms), (950, 1300));
+    }
+
+    #[test]
+    fn test_pick_language() {
+        let probabilities = [("en", 0.5), ("es", 0.3), ("pt", 0.15), ("de", 0.05)];
+        assert_eq!(pick_language(probabilities, &[]), Some(("en", 0.5)));
+        let candidates = ["es".to_string(), "de".to_string()];
+        assert_eq!(pick_language(probabilities, &candidates), Some(("es", 0.3)));
+        assert_eq!(pick_language(probabilities, &["ja".to_string()]), None);
+    }
+
+    #[test]
+    fn test_parakeet_languages() {
+        let english = BackendInfo::parakeet(vec!["en".to_string()]);
+        assert_eq!(english.supported_languages, vec!["en"]);
+        let multilingual = BackendInfo::parakeet(vec!["en".to_string(), "de".to_string()]);
+        assert_eq!(multilingual.supported_languages, vec!["auto", "en", "de"]);
+    }
 }
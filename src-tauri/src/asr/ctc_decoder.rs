@@ -0,0 +1,535 @@
+// CTC decoding
+// Greedy (argmax) decoding, and a prefix beam search that can fuse in an ARPA n-gram
+// language model and boost hotwords. The beam search keeps the hypotheses that are most
+// likely over the whole utterance instead of committing to the best token of each frame,
+// which fixes many spelling slips; the language model and hotwords score every word a
+// hypothesis completes.
+
+use anyhow::{anyhow, Context, Result};
+use std::collections::HashMap;
+use std::path::Path;
+
+use super::backend::DecoderOptions;
+
+/// Tokens this unlikely in a frame are not tried as extensions (natural log)
+const TOKEN_MIN_LOGP: f32 = -5.0;
+/// Log10 probability of a word the language model has never seen (when it has no <unk>)
+const UNKNOWN_WORD_LOG10: f32 = -10.0;
+
+/// A decoded token with the output frames it spans
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlignedToken {
+    pub id: usize,
+    pub first_frame: usize,
+    pub last_frame: usize,
+    /// Mean softmax probability of the token over its frames
+    pub probability: f32,
+}
+
+/// CTC greedy decoding over raw flat logits.
+/// Data layout: [batch=0, time, vocab] → index = t * vocab_size + v
+pub fn ctc_greedy_align(
+    logits: &[f32],
+    time_steps: usize,
+    vocab_size: usize,
+    blank_id: usize,
+) -> Vec<AlignedToken> {
+    let mut tokens: Vec<AlignedToken> = Vec::new();
+    let mut prev_token = blank_id;
+    // Frames the last token has been held for
+    let mut held = 0usize;
+
+    for t in 0..time_steps {
+        let frame = &logits[t * vocab_size..(t + 1) * vocab_size];
+        let mut max_val = f32::NEG_INFINITY;
+        let mut max_idx = 0usize;
+        for (v, &val) in frame.iter().enumerate() {
+            if val > max_val {
+                max_val = val;
+                max_idx = v;
+            }
+        }
+        // Softmax probability of the best token
+        let sum: f32 = frame.iter().map(|&val| (val - max_val).exp()).sum();
+        let probability = 1.0 / sum;
+
+        // CTC collapse: skip blanks and repeated tokens
+        if max_idx != blank_id {
+            match tokens.last_mut() {
+                Some(token) if max_idx == prev_token => {
+                    token.last_frame = t;
+                    token.probability =
+                        (token.probability * held as f32 + probability) / (held + 1) as f32;
+                    held += 1;
+                }
+                _ => {
+                    tokens.push(AlignedToken {
+                        id: max_idx,
+                        first_frame: t,
+                        last_frame: t,
+                        probability,
+                    });
+                    held = 1;
+                }
+            }
+        }
+        prev_token = max_idx;
+    }
+    tokens
+}
+
+/// ARPA-format n-gram language model over lowercase words
+pub struct NgramLm {
+    order: usize,
+    /// Space-joined n-gram → (log10 probability, log10 backoff weight)
+    entries: HashMap<String, (f32, f32)>,
+}
+
+impl NgramLm {
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read language model {:?}", path))?;
+        Self::parse(&text)
+    }
+
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut entries = HashMap::new();
+        let mut order = 0;
+        // N of the "\N-grams:" section being read (0 = header)
+        let mut section = 0;
+        for line in text.lines().map(str::trim) {
+            if line.is_empty() || line == "\\data\\" || line.starts_with("ngram ") {
+                continue;
+            }
+            if line == "\\end\\" {
+                break;
+            }
+            if let Some(n) = line
+                .strip_prefix('\\')
+                .and_then(|l| l.strip_suffix("-grams:"))
+            {
+                section = n
+                    .parse()
+                    .map_err(|_| anyhow!("Invalid ARPA section: {}", line))?;
+                order = order.max(section);
+                continue;
+            }
+            if section == 0 {
+                continue;
+            }
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() < section + 1 {
+                return Err(anyhow!("Invalid {}-gram line: {}", section, line));
+            }
+            let prob: f32 = fields[0]
+                .parse()
+                .map_err(|_| anyhow!("Invalid probability in: {}", line))?;
+            let backoff: f32 = match fields.get(section + 1) {
+                Some(b) => b
+                    .parse()
+                    .map_err(|_| anyhow!("Invalid backoff in: {}", line))?,
+                None => 0.0,
+            };
+            let ngram = fields[1..=section].join(" ").to_lowercase();
+            entries.insert(ngram, (prob, backoff));
+        }
+        if entries.is_empty() {
+            return Err(anyhow!("Language model has no n-grams"));
+        }
+        Ok(Self { order, entries })
+    }
+
+    pub fn order(&self) -> usize {
+        self.order
+    }
+
+    /// Natural log probability of `word` after `history` (oldest first), with Katz backoff
+    pub fn log_prob(&self, history: &[String], word: &str) -> f32 {
+        let mut backoff = 0.0;
+        let context = self.order.saturating_sub(1).min(history.len());
+        for n in (0..=context).rev() {
+            let context = &history[history.len() - n..];
+            let mut key = context.join(" ");
+            if n > 0 {
+                key.push(' ');
+            }
+            key.push_str(word);
+            if let Some(&(prob, _)) = self.entries.get(&key) {
+                return (backoff + prob) * std::f32::consts::LN_10;
+            }
+            if n > 0 {
+                backoff += self
+                    .entries
+                    .get(&context.join(" "))
+                    .map_or(0.0, |&(_, b)| b);
+            }
+        }
+        let unknown = self
+            .entries
+            .get("<unk>")
+            .map_or(UNKNOWN_WORD_LOG10, |&(prob, _)| prob);
+        (backoff + unknown) * std::f32::consts::LN_10
+    }
+}
+
+/// Greedy or beam-search CTC decoder, configured from `DecoderOptions`
+#[derive(Default)]
+pub struct CtcDecoder {
+    options: DecoderOptions,
+    lm: Option<NgramLm>,
+    hotwords: Vec<String>,
+}
+
+/// A hypothesis of the beam search
+#[derive(Clone)]
+struct Beam {
+    ids: Vec<usize>,
+    tokens: Vec<AlignedToken>,
+    /// Log probability of the paths ending in a blank / in the last token
+    blank: f32,
+    non_blank: f32,
+    /// Last words completed, as context for the language model
+    history: Vec<String>,
+    /// Word being spelled
+    partial: String,
+    /// Language model, word bonus and hotword score of the completed words
+    word_score: f32,
+}
+
+impl Beam {
+    fn acoustic(&self) -> f32 {
+        log_add(self.blank, self.non_blank)
+    }
+}
+
+fn log_add(a: f32, b: f32) -> f32 {
+    if a == f32::NEG_INFINITY {
+        return b;
+    }
+    if b == f32::NEG_INFINITY {
+        return a;
+    }
+    let max = a.max(b);
+    max + ((a - max).exp() + (b - max).exp()).ln()
+}
+
+fn normalize_word(word: &str) -> String {
+    word.trim_matches(|c: char| !c.is_alphanumeric() && c != '\'')
+        .to_lowercase()
+}
+
+impl CtcDecoder {
+    /// Build a decoder, loading the language model if one is configured
+    pub fn new(options: &DecoderOptions) -> Result<Self> {
+        let lm = match options.lm_path.as_deref().filter(|p| !p.is_empty()) {
+            Some(path) if options.beam_width > 1 => {
+                let lm = NgramLm::load(Path::new(path))?;
+                log::info!(
+                    "Loaded {}-gram language model from {} ({} n-grams)",
+                    lm.order(),
+                    path,
+                    lm.entries.len()
+                );
+                Some(lm)
+            }
+            _ => None,
+        };
+        let mut hotwords: Vec<String> = options
+            .hotwords
+            .iter()
+            .flat_map(|h| h.split_whitespace())
+            .map(normalize_word)
+            .filter(|w| !w.is_empty())
+            .collect();
+        hotwords.sort();
+        hotwords.dedup();
+        Ok(Self {
+            options: options.clone(),
+            lm,
+            hotwords,
+        })
+    }
+
+    pub fn options(&self) -> &DecoderOptions {
+        &self.options
+    }
+
+    /// Decode flat logits ([time, vocab]) to tokens with their frame spans
+    pub fn decode(
+        &self,
+        logits: &[f32],
+        time_steps: usize,
+        vocab_size: usize,
+        blank_id: usize,
+        vocab: &[String],
+    ) -> Vec<AlignedToken> {
+        if self.options.beam_width <= 1 {
+            return ctc_greedy_align(logits, time_steps, vocab_size, blank_id);
+        }
+        self.beam_search(logits, time_steps, vocab_size, blank_id, vocab)
+    }
+
+    fn beam_search(
+        &self,
+        logits: &[f32],
+        time_steps: usize,
+        vocab_size: usize,
+        blank_id: usize,
+        vocab: &[String],
+    ) -> Vec<AlignedToken> {
+        let width = self.options.beam_width;
+        let mut beams = vec![Beam {
+            ids: Vec::new(),
+            tokens: Vec::new(),
+            blank: 0.0,
+            non_blank: f32::NEG_INFINITY,
+            history: Vec::new(),
+            partial: String::new(),
+            word_score: 0.0,
+        }];
+
+        for t in 0..time_steps {
+            let frame = &logits[t * vocab_size..(t + 1) * vocab_size];
+            let max = frame.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+            let log_sum = max + frame.iter().map(|&v| (v - max).exp()).sum::<f32>().ln();
+            let logp = |v: usize| frame[v] - log_sum;
+
+            // The likeliest tokens of this frame are the only extensions tried
+            let mut candidates: Vec<usize> = (0..vocab_size)
+                .filter(|&v| v != blank_id && logp(v) >= TOKEN_MIN_LOGP)
+                .collect();
+            candidates.sort_by(|&a, &b| frame[b].total_cmp(&frame[a]));
+            candidates.truncate(width);
+
+            let mut next: HashMap<Vec<usize>, Beam> = HashMap::new();
+            for beam in &beams {
+                let total = beam.acoustic();
+                let stay = || Beam {
+                    blank: f32::NEG_INFINITY,
+                    non_blank: f32::NEG_INFINITY,
+                    ..beam.clone()
+                };
+
+                let entry = next.entry(beam.ids.clone()).or_insert_with(stay);
+                entry.blank = log_add(entry.blank, total + logp(blank_id));
+
+                // Repeating the last token collapses into it
+                if let Some(&last) = beam.ids.last() {
+                    let entry = next.entry(beam.ids.clone()).or_insert_with(stay);
+                    entry.non_blank = log_add(entry.non_blank, beam.non_blank + logp(last));
+                    if let Some(token) = entry.tokens.last_mut() {
+                        if token.last_frame < t {
+                            let frames = (token.last_frame - token.first_frame + 1) as f32;
+                            token.probability =
+                                (token.probability * frames + logp(last).exp()) / (frames + 1.0);
+                            token.last_frame = t;
+                        }
+                    }
+                }
+
+                for &c in &candidates {
+                    // The same token again only counts as new after a blank
+                    let from = if beam.ids.last() == Some(&c) {
+                        beam.blank
+                    } else {
+                        total
+                    };
+                    if from == f32::NEG_INFINITY {
+                        continue;
+                    }
+                    let mut ids = beam.ids.clone();
+                    ids.push(c);
+                    let entry = next
+                        .entry(ids)
+                        .or_insert_with(|| self.extend(beam, c, t, logp(c).exp(), vocab));
+                    entry.non_blank = log_add(entry.non_blank, from + logp(c));
+                }
+            }
+
+            beams = next.into_values().collect();
+            beams.sort_by(|a, b| self.score(b).total_cmp(&self.score(a)));
+            beams.truncate(width);
+        }
+
+        // The last word of each hypothesis is complete once the audio ends
+        beams
+            .into_iter()
+            .map(|mut beam| {
+                self.finish_word(&mut beam);
+                beam
+            })
+            .max_by(|a, b| self.score(a).total_cmp(&self.score(b)))
+            .map(|beam| beam.tokens)
+            .unwrap_or_default()
+    }
+
+    /// `beam` followed by token `id`, first heard at frame `t`
+    fn extend(&self, beam: &Beam, id: usize, t: usize, probability: f32, vocab: &[String]) -> Beam {
+        let mut next = Beam {
+            blank: f32::NEG_INFINITY,
+            non_blank: f32::NEG_INFINITY,
+            ..beam.clone()
+        };
+        next.ids.push(id);
+        next.tokens.push(AlignedToken {
+            id,
+            first_frame: t,
+            last_frame: t,
+            probability,
+        });
+        let text = vocab
+            .get(id)
+            .map(|token| token.replace('\u{2581}', " "))
+            .unwrap_or_default();
+        if text.starts_with(' ') {
+            self.finish_word(&mut next);
+        }
+        next.partial.push_str(text.trim());
+        if text.ends_with(' ') {
+            self.finish_word(&mut next);
+        }
+        next
+    }
+
+    /// Score the word being spelled now that it is complete
+    fn finish_word(&self, beam: &mut Beam) {
+        let word = normalize_word(&std::mem::take(&mut beam.partial));
+        if word.is_empty() {
+            return;
+        }
+        if let Some(lm) = &self.lm {
+            beam.word_score += self.options.lm_weight * lm.log_prob(&beam.history, &word)
+                + self.options.word_bonus;
+            beam.history.push(word.clone());
+            let keep = lm.order().saturating_sub(1);
+            if beam.history.len() > keep {
+                beam.history.drain(..beam.history.len() - keep);
+            }
+        }
+        if self.hotwords.contains(&word) {
+            beam.word_score += self.options.hotword_boost;
+        }
+    }
+
+    /// Ranking score: acoustic, completed words, and a share of the hotword boost for a word
+    /// spelled part way, so hotwords survive pruning until they are complete
+    fn score(&self, beam: &Beam) -> f32 {
+        let mut score = beam.acoustic() + beam.word_score;
+        if !beam.partial.is_empty() && !self.hotwords.is_empty() {
+            let partial = normalize_word(&beam.partial);
+            let best = self
+                .hotwords
+                .iter()
+                .filter(|h| !partial.is_empty() && h.starts_with(&partial))
+                .map(|h| partial.len() as f32 / h.len() as f32)
+                .fold(0.0, f32::max);
+            score += self.options.hotword_boost * best;
+        }
+        score
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Logits from per-frame probabilities
+    fn logits(frames: &[&[f32]]) -> Vec<f32> {
+        frames
+            .iter()
+            .flat_map(|f| f.iter().map(|p| p.ln()))
+            .collect()
+    }
+
+    fn vocab(tokens: &[&str]) -> Vec<String> {
+        tokens.iter().map(|t| t.to_string()).collect()
+    }
+
+    #[test]
+    fn test_ctc_greedy_align() {
+        // Vocab: 0 = blank, 1 = "a", 2 = "b". Frames: a a _ a b b _
+        let best = [1, 1, 0, 1, 2, 2, 0];
+        let logits: Vec<f32> = best
+            .iter()
+            .flat_map(|&b| (0..3).map(move |v| if v == b { 4.0 } else { 0.0 }))
+            .collect();
+        let tokens = ctc_greedy_align(&logits, best.len(), 3, 0);
+        let spans: Vec<(usize, usize, usize)> = tokens
+            .iter()
+            .map(|t| (t.id, t.first_frame, t.last_frame))
+            .collect();
+        assert_eq!(spans, vec![(1, 0, 1), (1, 3, 3), (2, 4, 5)]);
+        let expected = 4f32.exp() / (4f32.exp() + 2.0);
+        assert!((tokens[0].probability - expected).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_beam_search_sums_paths_greedy_misses() {
+        // Blank wins every frame, but "a" over both frames (a_, _a, aa) is likelier overall
+        let logits = logits(&[&[0.4, 0.35, 0.25], &[0.4, 0.35, 0.25]]);
+        let vocab = vocab(&["<blank>", "a", "b"]);
+        assert!(ctc_greedy_align(&logits, 2, 3, 0).is_empty());
+
+        let decoder = CtcDecoder::new(&DecoderOptions {
+            beam_width: 4,
+            ..Default::default()
+        })
+        .unwrap();
+        let tokens = decoder.decode(&logits, 2, 3, 0, &vocab);
+        let ids: Vec<usize> = tokens.iter().map(|t| t.id).collect();
+        assert_eq!(ids, vec![1]);
+    }
+
+    #[test]
+    fn test_language_model_and_hotwords_pick_the_word() {
+        let lm = NgramLm::parse(
+            "\\data\\\nngram 1=3\nngram 2=1\n\n\\1-grams:\n-1.0\tthe\t-0.5\n-2.0\tcat\n\
+             -3.0\tcut\n\n\\2-grams:\n-0.2\tthe cat\n\n\\end\\\n",
+        )
+        .unwrap();
+        let the = vec!["the".to_string()];
+        assert!((lm.log_prob(&the, "cat") - -0.2 * std::f32::consts::LN_10).abs() < 1e-5);
+        // Backoff: weight of "the" plus the unigram
+        assert!((lm.log_prob(&the, "cut") - -3.5 * std::f32::consts::LN_10).abs() < 1e-5);
+
+        // Acoustically "cut" edges out "cat"
+        let vocab = vocab(&["<blank>", "\u{2581}c", "u", "a", "t"]);
+        let logits = logits(&[
+            &[0.01, 0.96, 0.01, 0.01, 0.01],
+            &[0.01, 0.01, 0.55, 0.42, 0.01],
+            &[0.01, 0.01, 0.01, 0.01, 0.96],
+        ]);
+        let text = |decoder: &CtcDecoder| -> String {
+            decoder
+                .decode(&logits, 3, 5, 0, &vocab)
+                .iter()
+                .map(|t| vocab[t.id].trim_start_matches('\u{2581}'))
+                .collect()
+        };
+        let plain = CtcDecoder::new(&DecoderOptions {
+            beam_width: 8,
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(text(&plain), "cut");
+
+        let with_lm = CtcDecoder {
+            lm: Some(lm),
+            ..CtcDecoder::new(&DecoderOptions {
+                beam_width: 8,
+                ..Default::default()
+            })
+            .unwrap()
+        };
+        assert_eq!(text(&with_lm), "cat");
+
+        let boosted = CtcDecoder::new(&DecoderOptions {
+            beam_width: 8,
+            hotwords: vec!["Cat".to_string()],
+            hotword_boost: 2.0,
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(text(&boosted), "cat");
+    }
+}
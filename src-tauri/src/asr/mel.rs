@@ -116,6 +116,20 @@ impl MelSpectrogram {
     }
 }
 
+/// NeMo "per_feature" normalization: each mel bin to zero mean and unit variance over time
+pub fn normalize_per_feature(features: &mut Array2<f32>) {
+    let n_frames = features.shape()[1];
+    if n_frames < 2 {
+        return;
+    }
+    for mut row in features.rows_mut() {
+        let mean = row.sum() / n_frames as f32;
+        let var = row.iter().map(|&x| (x - mean).powi(2)).sum::<f32>() / (n_frames - 1) as f32;
+        let std = var.sqrt() + 1e-5;
+        row.mapv_inplace(|x| (x - mean) / std);
+    }
+}
+
 /// Create a Hanning window
 fn hann_window(length: usize) -> Vec<f32> {
     (0..length)
@@ -204,6 +218,18 @@ mod tests {
         assert_eq!(fb.shape(), &[80, 257]); // 80 mels x (512/2 + 1) freq bins
     }
 
+    #[test]
+    fn test_normalize_per_feature() {
+        let mut features =
+            Array2::from_shape_vec((2, 4), vec![1.0, 2.0, 3.0, 4.0, 5.0, 5.0, 5.0, 5.0]).unwrap();
+        normalize_per_feature(&mut features);
+        let row: Vec<f32> = features.row(0).to_vec();
+        assert!(row.iter().sum::<f32>().abs() < 1e-5);
+        assert!((row[3] - row[0] - 3.0 / 1.2910).abs() < 1e-3);
+        // A constant bin becomes all zeros
+        assert!(features.row(1).iter().all(|x| x.abs() < 1e-5));
+    }
+
     #[test]
     fn test_hz_mel_roundtrip() {
         let hz = 1000.0;
@@ -4,9 +4,14 @@
 
 pub mod backend;
 #[cfg(feature = "parakeet")]
+pub mod ctc_decoder;
+#[cfg(feature = "parakeet")]
 pub mod mel;
 #[cfg(feature = "parakeet")]
 pub mod parakeet_backend;
+pub mod punctuation;
+#[cfg(feature = "parakeet")]
+pub mod transducer;
 pub mod whisper_backend;
 
 use anyhow::{anyhow, Result};
@@ -14,10 +19,12 @@ use std::path::{Path, PathBuf};
 
 // Re-export backend types
 pub use backend::{
-    AsrBackend, AsrBackendType, BackendInfo, TranscriptionResult, TranscriptionSegment,
+    AsrBackend, AsrBackendType, BackendInfo, DecoderOptions, LanguageOptions, TranscriptionResult,
+    TranscriptionSegment, WordTiming,
 };
 #[cfg(feature = "parakeet")]
 pub use parakeet_backend::{ParakeetBackend, ParakeetModel};
+pub use punctuation::PunctuationModel;
 pub use whisper_backend::WhisperBackend;
 
 /// Available Whisper model sizes
@@ -154,6 +161,27 @@ impl TranscriptionEngine {
         self.backend.language()
     }
 
+    /// Configure decoding for backends with their own decoder (Parakeet)
+    pub fn set_decoder_options(&mut self, options: &DecoderOptions) -> Result<()> {
+        self.backend.set_decoder_options(options)
+    }
+
+    /// Swap in a decoder built beforehand, for backends with their own decoder (Parakeet)
+    #[cfg(feature = "parakeet")]
+    pub fn set_decoder(&mut self, decoder: ctc_decoder::CtcDecoder) {
+        self.backend.set_decoder(decoder)
+    }
+
+    /// Set the CPU threads each transcription may use (0 = the backend's default)
+    pub fn set_threads(&mut self, threads: usize) {
+        self.backend.set_threads(threads);
+    }
+
+    /// Whether workers can transcribe in parallel on this engine
+    pub fn parallel(&self) -> bool {
+        self.backend.parallel()
+    }
+
     /// Load a model from a file path
     pub fn load_model(&mut self, model_path: &Path) -> Result<()> {
         self.backend.load_model(model_path)
@@ -179,6 +207,63 @@ impl TranscriptionEngine {
             .transcribe_with_offset(audio_samples, offset_ms)
             .await
     }
+
+    /// Transcribe with a time offset and an initial prompt (vocabulary, preceding text).
+    /// An empty prompt decodes the audio on its own.
+    pub async fn transcribe_with_prompt(
+        &self,
+        audio_samples: &[f32],
+        offset_ms: i64,
+        prompt: &str,
+    ) -> Result<TranscriptionResult> {
+        if prompt.is_empty() {
+            return self.transcribe_with_offset(audio_samples, offset_ms).await;
+        }
+        let mut result = self
+            .backend
+            .transcribe_with_prompt(audio_samples, prompt)
+            .await?;
+        result.offset(offset_ms);
+        Ok(result)
+    }
+
+    /// Translate speech into English text with a time offset (None = the backend can't)
+    pub async fn translate_to_english(
+        &self,
+        audio_samples: &[f32],
+        offset_ms: i64,
+        language: &LanguageOptions,
+    ) -> Result<Option<TranscriptionResult>> {
+        let mut result = self
+            .backend
+            .translate_to_english(audio_samples, language)
+            .await?;
+        if let Some(ref mut result) = result {
+            result.offset(offset_ms);
+        }
+        Ok(result)
+    }
+
+    /// Transcribe with a time offset, a prompt (may be empty) and a per-call language choice
+    pub async fn transcribe_with_language(
+        &self,
+        audio_samples: &[f32],
+        offset_ms: i64,
+        prompt: &str,
+        language: &LanguageOptions,
+    ) -> Result<TranscriptionResult> {
+        if *language == LanguageOptions::default() {
+            return self
+                .transcribe_with_prompt(audio_samples, offset_ms, prompt)
+                .await;
+        }
+        let mut result = self
+            .backend
+            .transcribe_with_language(audio_samples, prompt, language)
+            .await?;
+        result.offset(offset_ms);
+        Ok(result)
+    }
 }
 
 impl Default for TranscriptionEngine {
@@ -187,16 +272,29 @@ impl Default for TranscriptionEngine {
     }
 }
 
-/// Get available ASR backends info
-pub fn get_available_backends() -> Vec<BackendInfo> {
+/// Get available ASR backends info. `model_name` is the configured model: a Parakeet one
+/// ("parakeet-<model>") lists its own languages; otherwise Parakeet lists every language
+/// its models cover.
+pub fn get_available_backends(model_name: &str) -> Vec<BackendInfo> {
     #[cfg(not(feature = "parakeet"))]
     {
+        let _ = model_name;
         vec![BackendInfo::whisper()]
     }
     #[cfg(feature = "parakeet")]
     {
+        let languages = model_name
+            .strip_prefix("parakeet-")
+            .and_then(|name| name.parse::<ParakeetModel>().ok())
+            .map(|model| model.languages())
+            .unwrap_or_else(|| {
+                backend::PARAKEET_MULTILINGUAL_LANGUAGES
+                    .iter()
+                    .map(|l| l.to_string())
+                    .collect()
+            });
         let mut backends = vec![BackendInfo::whisper()];
-        backends.push(BackendInfo::parakeet());
+        backends.push(BackendInfo::parakeet(languages));
         backends
     }
 }
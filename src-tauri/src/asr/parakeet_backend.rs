@@ -1,21 +1,30 @@
 // Parakeet ASR Backend
-// NVIDIA Parakeet CTC via ONNX Runtime
+// NVIDIA Parakeet CTC, RNN-T and TDT via ONNX Runtime
 //
-// Supports CTC models (0.6B, 1.1B). RNNT models are listed but not yet supported.
-// English-only. Uses mel spectrogram preprocessing + ONNX inference + CTC greedy decoding.
+// CTC models are a single ONNX file, decoded greedily by default or by beam search with an
+// optional n-gram language model and hotwords. Transducer (RNN-T, TDT) exports are an
+// encoder, decoder and joiner file side by side (`*-encoder.onnx`, `*-decoder.onnx`,
+// `*-joiner.onnx`), decoded greedily. The encoder's metadata can carry the vocabulary,
+// the languages of multilingual models, the mel bin count, normalization and TDT durations.
 
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
 use ndarray::Array2;
 use ort::session::builder::GraphOptimizationLevel;
 use ort::session::Session;
-use ort::session::SessionOutputs;
+use ort::session::{SessionInputValue, SessionOutputs};
+use ort::tensor::TensorElementType;
 use ort::value::Tensor;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 
-use super::backend::{AsrBackend, TranscriptionResult, TranscriptionSegment};
-use super::mel::MelSpectrogram;
+use super::backend::{
+    words_from_tokens, AsrBackend, DecoderOptions, LanguageOptions, TokenTiming,
+    TranscriptionResult, TranscriptionSegment, PARAKEET_MULTILINGUAL_LANGUAGES,
+};
+use super::ctc_decoder::{AlignedToken, CtcDecoder};
+use super::mel::{normalize_per_feature, MelConfig, MelSpectrogram};
+use super::transducer::{self, TransducerNet};
 
 /// Parakeet model variants
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -24,29 +33,56 @@ pub enum ParakeetModel {
     Ctc06b,
     /// Parakeet CTC 1.1B - better accuracy
     Ctc11b,
-    /// Parakeet RNNT 0.6B - streaming capable (not yet supported)
+    /// Parakeet RNNT 0.6B - transducer, more accurate than CTC
     Rnnt06b,
-    /// Parakeet RNNT 1.1B - best accuracy (not yet supported)
+    /// Parakeet RNNT 1.1B - transducer, best English accuracy
     Rnnt11b,
+    /// Parakeet TDT 0.6B v2 - token-and-duration transducer, English
+    Tdt06bV2,
+    /// Parakeet TDT 0.6B v3 - token-and-duration transducer, 25 European languages
+    Tdt06bV3,
 }
 
 impl ParakeetModel {
-    pub fn filename(&self) -> &'static str {
+    /// Name used in model lists and commands (after the `parakeet-` prefix)
+    pub fn name(&self) -> &'static str {
         match self {
-            ParakeetModel::Ctc06b => "parakeet-ctc-0.6b.onnx",
-            ParakeetModel::Ctc11b => "parakeet-ctc-1.1b.onnx",
-            ParakeetModel::Rnnt06b => "parakeet-rnnt-0.6b.onnx",
-            ParakeetModel::Rnnt11b => "parakeet-rnnt-1.1b.onnx",
+            ParakeetModel::Ctc06b => "ctc-0.6b",
+            ParakeetModel::Ctc11b => "ctc-1.1b",
+            ParakeetModel::Rnnt06b => "rnnt-0.6b",
+            ParakeetModel::Rnnt11b => "rnnt-1.1b",
+            ParakeetModel::Tdt06bV2 => "tdt-0.6b-v2",
+            ParakeetModel::Tdt06bV3 => "tdt-0.6b-v3",
         }
     }
 
-    pub fn vocab_filename(&self) -> &'static str {
-        match self {
-            ParakeetModel::Ctc06b => "parakeet-ctc-0.6b-vocab.json",
-            ParakeetModel::Ctc11b => "parakeet-ctc-1.1b-vocab.json",
-            ParakeetModel::Rnnt06b => "parakeet-rnnt-0.6b-vocab.json",
-            ParakeetModel::Rnnt11b => "parakeet-rnnt-1.1b-vocab.json",
+    /// The model file to load: the whole model for CTC, the encoder for transducers
+    pub fn filename(&self) -> String {
+        if self.is_ctc() {
+            format!("parakeet-{}.onnx", self.name())
+        } else {
+            format!("parakeet-{}-encoder.onnx", self.name())
+        }
+    }
+
+    pub fn vocab_filename(&self) -> String {
+        format!("parakeet-{}-vocab.json", self.name())
+    }
+
+    /// Prediction and joint network files of a transducer: (filename, url)
+    pub fn transducer_files(&self) -> Vec<(String, String)> {
+        if self.is_ctc() {
+            return Vec::new();
         }
+        ["decoder", "joiner"]
+            .iter()
+            .map(|part| {
+                (
+                    format!("parakeet-{}-{}.onnx", self.name(), part),
+                    format!("{}/{}.onnx", self.repo_url(), part),
+                )
+            })
+            .collect()
     }
 
     pub fn size_mb(&self) -> u64 {
@@ -55,6 +91,8 @@ impl ParakeetModel {
             ParakeetModel::Ctc11b => 1100,
             ParakeetModel::Rnnt06b => 650,
             ParakeetModel::Rnnt11b => 1200,
+            ParakeetModel::Tdt06bV2 => 650,
+            ParakeetModel::Tdt06bV3 => 670,
         }
     }
 
@@ -62,8 +100,10 @@ impl ParakeetModel {
         match self {
             ParakeetModel::Ctc06b => "Fast inference, good accuracy",
             ParakeetModel::Ctc11b => "Better accuracy, larger model",
-            ParakeetModel::Rnnt06b => "Streaming capable, fast (not yet supported)",
-            ParakeetModel::Rnnt11b => "Best accuracy, streaming (not yet supported)",
+            ParakeetModel::Rnnt06b => "Transducer, more accurate than CTC",
+            ParakeetModel::Rnnt11b => "Transducer, best English accuracy",
+            ParakeetModel::Tdt06bV2 => "Transducer with durations, fast and accurate",
+            ParakeetModel::Tdt06bV3 => "Transducer with durations, 25 European languages",
         }
     }
 
@@ -71,39 +111,47 @@ impl ParakeetModel {
         matches!(self, ParakeetModel::Ctc06b | ParakeetModel::Ctc11b)
     }
 
-    pub fn download_url(&self) -> &'static str {
+    /// Languages the model transcribes
+    pub fn languages(&self) -> Vec<String> {
         match self {
-            ParakeetModel::Ctc06b => {
-                "https://huggingface.co/nvidia/parakeet-ctc-0.6b/resolve/main/model.onnx"
-            }
-            ParakeetModel::Ctc11b => {
-                "https://huggingface.co/nvidia/parakeet-ctc-1.1b/resolve/main/model.onnx"
-            }
-            ParakeetModel::Rnnt06b => {
-                "https://huggingface.co/nvidia/parakeet-rnnt-0.6b/resolve/main/model.onnx"
-            }
-            ParakeetModel::Rnnt11b => {
-                "https://huggingface.co/nvidia/parakeet-rnnt-1.1b/resolve/main/model.onnx"
-            }
+            ParakeetModel::Tdt06bV3 => PARAKEET_MULTILINGUAL_LANGUAGES
+                .iter()
+                .map(|l| l.to_string())
+                .collect(),
+            _ => vec!["en".to_string()],
         }
     }
 
-    pub fn vocab_url(&self) -> &'static str {
+    fn repo_url(&self) -> &'static str {
         match self {
-            ParakeetModel::Ctc06b => {
-                "https://huggingface.co/nvidia/parakeet-ctc-0.6b/resolve/main/vocab.json"
-            }
-            ParakeetModel::Ctc11b => {
-                "https://huggingface.co/nvidia/parakeet-ctc-1.1b/resolve/main/vocab.json"
-            }
+            ParakeetModel::Ctc06b => "https://huggingface.co/nvidia/parakeet-ctc-0.6b/resolve/main",
+            ParakeetModel::Ctc11b => "https://huggingface.co/nvidia/parakeet-ctc-1.1b/resolve/main",
             ParakeetModel::Rnnt06b => {
-                "https://huggingface.co/nvidia/parakeet-rnnt-0.6b/resolve/main/vocab.json"
+                "https://huggingface.co/nvidia/parakeet-rnnt-0.6b/resolve/main"
             }
             ParakeetModel::Rnnt11b => {
-                "https://huggingface.co/nvidia/parakeet-rnnt-1.1b/resolve/main/vocab.json"
+                "https://huggingface.co/nvidia/parakeet-rnnt-1.1b/resolve/main"
+            }
+            ParakeetModel::Tdt06bV2 => {
+                "https://huggingface.co/nvidia/parakeet-tdt-0.6b-v2/resolve/main"
             }
+            ParakeetModel::Tdt06bV3 => {
+                "https://huggingface.co/nvidia/parakeet-tdt-0.6b-v3/resolve/main"
+            }
+        }
+    }
+
+    pub fn download_url(&self) -> String {
+        if self.is_ctc() {
+            format!("{}/model.onnx", self.repo_url())
+        } else {
+            format!("{}/encoder.onnx", self.repo_url())
         }
     }
+
+    pub fn vocab_url(&self) -> String {
+        format!("{}/vocab.json", self.repo_url())
+    }
 }
 
 impl std::str::FromStr for ParakeetModel {
@@ -115,30 +163,158 @@ impl std::str::FromStr for ParakeetModel {
             "ctc-1.1b" | "ctc11b" | "parakeet-ctc-1.1b" => Ok(ParakeetModel::Ctc11b),
             "rnnt-0.6b" | "rnnt06b" | "parakeet-rnnt-0.6b" => Ok(ParakeetModel::Rnnt06b),
             "rnnt-1.1b" | "rnnt11b" | "parakeet-rnnt-1.1b" => Ok(ParakeetModel::Rnnt11b),
+            "tdt-0.6b-v2" | "tdt06bv2" | "parakeet-tdt-0.6b-v2" => Ok(ParakeetModel::Tdt06bV2),
+            "tdt-0.6b-v3" | "tdt06bv3" | "parakeet-tdt-0.6b-v3" => Ok(ParakeetModel::Tdt06bV3),
             _ => Err(anyhow!("Unknown Parakeet model: {}", s)),
         }
     }
 }
 
+/// ONNX sessions of a loaded model
+enum ParakeetSessions {
+    /// A CTC model: one network from features to token logits
+    Ctc(Session),
+    /// A transducer: encoder, prediction network and joint network
+    Transducer {
+        encoder: Session,
+        decoder: Session,
+        joiner: Session,
+    },
+}
+
+/// What a NeMo export declares about itself in its ONNX metadata
+#[derive(Debug, Clone, Default, PartialEq)]
+struct ExportMetadata {
+    /// Token strings in id order (`vocabulary`, a JSON array)
+    vocab: Option<Vec<String>>,
+    /// Languages the model transcribes (`languages`, comma-separated codes)
+    languages: Vec<String>,
+    /// Mel bins the model takes (`feat_dim`)
+    feat_dim: Option<usize>,
+    /// Each mel bin is normalized over the utterance (`normalize_type` = per_feature)
+    per_feature: bool,
+    /// Frame counts a TDT joiner predicts (`tdt_durations`, comma-separated)
+    durations: Vec<usize>,
+}
+
+impl ExportMetadata {
+    fn parse(custom: impl Fn(&str) -> Option<String>) -> Result<Self> {
+        let list = |key: &str| -> Vec<String> {
+            custom(key)
+                .map(|value| {
+                    value
+                        .split(|c: char| c == ',' || c.is_whitespace())
+                        .filter(|item| !item.is_empty())
+                        .map(|item| item.to_string())
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+        let vocab = custom("vocabulary")
+            .map(|json| {
+                serde_json::from_str::<Vec<String>>(&json)
+                    .map_err(|e| anyhow!("Invalid vocabulary in model metadata: {}", e))
+            })
+            .transpose()?;
+        let feat_dim = custom("feat_dim")
+            .map(|dim| {
+                dim.trim()
+                    .parse()
+                    .map_err(|_| anyhow!("Invalid feat_dim in model metadata: {}", dim))
+            })
+            .transpose()?;
+        let durations = list("tdt_durations")
+            .iter()
+            .map(|d| {
+                d.parse()
+                    .map_err(|_| anyhow!("Invalid TDT duration in model metadata: {}", d))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self {
+            vocab,
+            languages: list("languages")
+                .into_iter()
+                .map(|l| l.to_lowercase())
+                .collect(),
+            feat_dim,
+            per_feature: custom("normalize_type").as_deref() == Some("per_feature"),
+            durations,
+        })
+    }
+}
+
 pub struct ParakeetBackend {
     model_path: Option<PathBuf>,
+    /// Language asked for, kept until a model says whether it supports it
+    requested_language: String,
     language: String,
+    /// Languages the loaded model transcribes
+    languages: Vec<String>,
     is_loaded: bool,
-    session: Option<Arc<Mutex<Session>>>,
+    sessions: Option<Arc<Mutex<ParakeetSessions>>>,
     vocab: Option<Vec<String>>,
     mel: MelSpectrogram,
+    /// Normalize each mel bin over the utterance before inference
+    per_feature: bool,
+    /// Frame counts a TDT joiner predicts (empty for CTC and RNN-T)
+    durations: Vec<usize>,
+    decoder: CtcDecoder,
 }
 
 impl ParakeetBackend {
     pub fn new() -> Self {
         Self {
             model_path: None,
+            requested_language: "en".to_string(),
             language: "en".to_string(),
+            languages: vec!["en".to_string()],
             is_loaded: false,
-            session: None,
+            sessions: None,
             vocab: None,
             mel: MelSpectrogram::default(),
+            per_feature: false,
+            durations: Vec::new(),
+            decoder: CtcDecoder::default(),
+        }
+    }
+
+    /// Language to label a result with: the model's only language, or the requested one
+    /// (None = the backend's setting) when the model knows it. Multilingual models pick the
+    /// language themselves without reporting it, so "auto" gives None.
+    fn result_language(&self, requested: Option<&str>) -> Option<String> {
+        if let [only] = self.languages.as_slice() {
+            return Some(only.clone());
         }
+        let lang = requested.unwrap_or(&self.language);
+        self.languages.iter().find(|l| *l == lang).cloned()
+    }
+
+    /// Use the requested language if the model knows it, otherwise auto-detect
+    /// (multilingual models) or the model's only language
+    fn resolve_language(&mut self) {
+        let lang = self.requested_language.as_str();
+        self.language = if self.languages.iter().any(|l| l == lang)
+            || (lang == "auto" && self.languages.len() > 1)
+        {
+            lang.to_string()
+        } else {
+            let fallback = if self.languages.len() > 1 {
+                "auto".to_string()
+            } else {
+                self.languages
+                    .first()
+                    .cloned()
+                    .unwrap_or_else(|| "en".to_string())
+            };
+            if lang != "auto" {
+                log::warn!(
+                    "Parakeet model does not support language '{}', using '{}'",
+                    lang,
+                    fallback
+                );
+            }
+            fallback
+        };
     }
 }
 
@@ -173,39 +349,13 @@ impl AsrBackend for ParakeetBackend {
             ));
         }
 
-        // Load vocabulary from JSON file alongside the model
-        let vocab_path = path.with_extension("vocab.json");
-        // Also try sibling file with matching name pattern
-        let vocab_path2 = path.parent().map(|p| {
-            let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("model");
-            p.join(format!("{}-vocab.json", stem))
-        });
-
-        let vocab = if vocab_path.exists() {
-            load_vocab(&vocab_path)?
-        } else if let Some(ref vp2) = vocab_path2 {
-            if vp2.exists() {
-                load_vocab(vp2)?
-            } else {
-                log::warn!("No vocab file found, using default English character vocab");
-                default_char_vocab()
-            }
-        } else {
-            log::warn!("No vocab file found, using default English character vocab");
-            default_char_vocab()
-        };
-
-        log::info!("Vocabulary loaded: {} tokens", vocab.len());
-
-        // Create ONNX Runtime session
-        let session = Session::builder()
-            .map_err(|e| anyhow!("Failed to create ONNX session builder: {}", e))?
-            .with_optimization_level(GraphOptimizationLevel::Level3)
-            .map_err(|e| anyhow!("Failed to set optimization level: {}", e))?
-            .with_intra_threads(num_cpus::get().min(4))
-            .map_err(|e| anyhow!("Failed to set thread count: {}", e))?
-            .commit_from_file(path)
-            .map_err(|e| anyhow!("Failed to load ONNX model: {}", e))?;
+        // A transducer export is an encoder with decoder and joiner files beside it
+        let session = create_session(path)?;
+        let metadata = session
+            .metadata()
+            .map_err(|e| anyhow!("Failed to read model metadata: {}", e))?;
+        let export = ExportMetadata::parse(|key| metadata.custom(key))?;
+        drop(metadata);
 
         // Log model input/output info
         log::info!("ONNX model loaded. Inputs:");
@@ -217,36 +367,112 @@ impl AsrBackend for ParakeetBackend {
             log::info!("  - {}", output.name());
         }
 
-        self.session = Some(Arc::new(Mutex::new(session)));
+        // Mel bins: from the metadata, else the model's declared input shape
+        let feat_dim = export
+            .feat_dim
+            .or_else(|| {
+                let shape = session.inputs().first()?.dtype().tensor_shape()?;
+                let dim = *shape.get(1)?;
+                (shape.len() == 3 && dim > 0).then_some(dim as usize)
+            })
+            .unwrap_or(MelConfig::default().n_mels);
+
+        let sessions = match transducer_paths(path) {
+            Some((decoder_path, joiner_path)) => {
+                log::info!(
+                    "Transducer export, loading {:?} and {:?}",
+                    decoder_path,
+                    joiner_path
+                );
+                ParakeetSessions::Transducer {
+                    encoder: session,
+                    decoder: create_session(&decoder_path)?,
+                    joiner: create_session(&joiner_path)?,
+                }
+            }
+            None => ParakeetSessions::Ctc(session),
+        };
+
+        let vocab = match export.vocab {
+            Some(vocab) => vocab,
+            None => match find_vocab(path) {
+                Some(vocab_path) => load_vocab(&vocab_path)?,
+                None => {
+                    log::warn!("No vocab file found, using default English character vocab");
+                    default_char_vocab()
+                }
+            },
+        };
+
+        log::info!(
+            "Vocabulary loaded: {} tokens, {} mel bins{}",
+            vocab.len(),
+            feat_dim,
+            if export.durations.is_empty() {
+                String::new()
+            } else {
+                format!(", TDT durations {:?}", export.durations)
+            }
+        );
+
+        self.mel = MelSpectrogram::new(MelConfig {
+            n_mels: feat_dim,
+            ..MelConfig::default()
+        });
+        self.per_feature = export.per_feature;
+        self.durations = export.durations;
+        self.languages = if export.languages.is_empty() {
+            vec!["en".to_string()]
+        } else {
+            export.languages
+        };
+        self.resolve_language();
+        self.sessions = Some(Arc::new(Mutex::new(sessions)));
         self.vocab = Some(vocab);
         self.model_path = Some(path.to_path_buf());
         self.is_loaded = true;
 
-        log::info!("Parakeet model loaded successfully");
+        log::info!(
+            "Parakeet model loaded successfully (languages: {})",
+            self.languages.join(", ")
+        );
         Ok(())
     }
 
     fn set_language(&mut self, lang: &str) {
-        if lang != "en" && lang != "auto" {
-            log::warn!(
-                "Parakeet only supports English. Ignoring language setting: {}",
-                lang
-            );
-        }
-        self.language = "en".to_string();
+        self.requested_language = lang.to_string();
+        self.resolve_language();
     }
 
     fn language(&self) -> &str {
         &self.language
     }
 
+    fn set_decoder_options(&mut self, options: &DecoderOptions) -> Result<()> {
+        self.set_decoder(CtcDecoder::new(options)?);
+        Ok(())
+    }
+
+    fn set_decoder(&mut self, decoder: CtcDecoder) {
+        let beam_width = decoder.options().beam_width;
+        log::info!(
+            "Parakeet decoding: {}",
+            if beam_width > 1 {
+                format!("beam search (width {})", beam_width)
+            } else {
+                "greedy".to_string()
+            }
+        );
+        self.decoder = decoder;
+    }
+
     async fn transcribe(&self, samples: &[f32]) -> Result<TranscriptionResult> {
         if !self.is_loaded {
             return Err(anyhow!("No model loaded"));
         }
 
-        let session_arc = self
-            .session
+        let sessions_arc = self
+            .sessions
             .as_ref()
             .ok_or_else(|| anyhow!("ONNX session not initialized"))?;
         let vocab = self
@@ -258,33 +484,75 @@ impl AsrBackend for ParakeetBackend {
             return Ok(TranscriptionResult {
                 segments: vec![],
                 full_text: String::new(),
+                language: None,
+                language_probability: None,
             });
         }
 
-        // Step 1: Compute mel spectrogram [n_mels, time_steps]
-        let mel_features = self.mel.compute(samples);
-        let (n_mels, n_frames) = (mel_features.shape()[0], mel_features.shape()[1]);
-
-        if n_frames == 0 {
-            return Ok(TranscriptionResult {
-                segments: vec![],
-                full_text: String::new(),
-            });
-        }
-
-        // Lock session for inference
-        let mut session = session_arc
-            .lock()
-            .map_err(|e| anyhow!("Failed to lock ONNX session: {}", e))?;
+        // Inference holds a core (and the session lock) for the whole chunk; the runtime
+        // moves its other tasks to another thread meanwhile
+        let mut result = tokio::task::block_in_place(|| -> Result<TranscriptionResult> {
+            // Step 1: Compute mel spectrogram [n_mels, time_steps]
+            let mut mel_features = self.mel.compute(samples);
+            if self.per_feature {
+                normalize_per_feature(&mut mel_features);
+            }
+            let (n_mels, n_frames) = (mel_features.shape()[0], mel_features.shape()[1]);
+
+            if n_frames == 0 {
+                return Ok(TranscriptionResult {
+                    segments: vec![],
+                    full_text: String::new(),
+                    language: None,
+                    language_probability: None,
+                });
+            }
 
-        // Try mel features first, fall back to raw audio
-        let result = self
-            .run_inference_mel(&mut session, &mel_features, n_mels, n_frames, vocab)
-            .or_else(|e| {
-                log::warn!("Mel-based inference failed ({}), trying raw audio input", e);
-                self.run_inference_raw(&mut session, samples, vocab)
-            })?;
+            // Lock sessions for inference
+            let mut sessions = sessions_arc
+                .lock()
+                .map_err(|e| anyhow!("Failed to lock ONNX session: {}", e))?;
+
+            match &mut *sessions {
+                ParakeetSessions::Ctc(session) => {
+                    // Try mel features first, fall back to raw audio
+                    self.run_inference_mel(session, &mel_features, n_mels, n_frames, vocab)
+                        .or_else(|e| {
+                            log::warn!(
+                                "Mel-based inference failed ({}), trying raw audio input",
+                                e
+                            );
+                            self.run_inference_raw(session, samples, vocab)
+                        })
+                }
+                ParakeetSessions::Transducer {
+                    encoder,
+                    decoder,
+                    joiner,
+                } => self.run_transducer(
+                    encoder,
+                    decoder,
+                    joiner,
+                    &mel_features,
+                    n_mels,
+                    n_frames,
+                    vocab,
+                ),
+            }
+        })?;
+        result.language = self.result_language(None);
+        Ok(result)
+    }
 
+    /// Multilingual models can't be told a language per call, so this only labels the result
+    async fn transcribe_with_language(
+        &self,
+        samples: &[f32],
+        _prompt: &str,
+        language: &LanguageOptions,
+    ) -> Result<TranscriptionResult> {
+        let mut result = self.transcribe(samples).await?;
+        result.language = self.result_language(language.language.as_deref());
         Ok(result)
     }
 }
@@ -315,7 +583,7 @@ impl ParakeetBackend {
             .run(inputs)
             .map_err(|e| anyhow!("ONNX inference failed: {}", e))?;
 
-        self.decode_ctc_output(&outputs, vocab)
+        self.decode_ctc_output(&outputs, vocab, samples_to_ms(n_frames, 160, 16000))
     }
 
     /// Run inference with raw audio input (if model includes preprocessor)
@@ -339,14 +607,16 @@ impl ParakeetBackend {
             .run(inputs)
             .map_err(|e| anyhow!("ONNX inference failed: {}", e))?;
 
-        self.decode_ctc_output(&outputs, vocab)
+        self.decode_ctc_output(&outputs, vocab, samples_to_ms(samples.len(), 1, 16000))
     }
 
-    /// Decode CTC output logits to text
+    /// Decode CTC output logits to text, with word timings from the frame alignment.
+    /// `audio_ms` is the length of the input, which the output frames span evenly.
     fn decode_ctc_output(
         &self,
         outputs: &SessionOutputs<'_>,
         vocab: &[String],
+        audio_ms: u64,
     ) -> Result<TranscriptionResult> {
         // Get the first output by index
         let first_output = &outputs[0];
@@ -364,62 +634,304 @@ impl ParakeetBackend {
 
         let time_steps = shape[1];
         let vocab_size = shape[2];
-        let blank_id = 0usize; // CTC blank token is typically 0
-
-        // CTC greedy decoding over raw flat tensor data
-        // Data layout: [batch=0, time, vocab] → index = t * vocab_size + v
-        let mut token_ids: Vec<usize> = Vec::new();
-        let mut prev_token = blank_id;
-
-        for t in 0..time_steps {
-            let offset = t * vocab_size;
-            let mut max_val = f32::NEG_INFINITY;
-            let mut max_idx = 0usize;
-            for v in 0..vocab_size {
-                let val = logits_data[offset + v];
-                if val > max_val {
-                    max_val = val;
-                    max_idx = v;
-                }
-            }
+        // NeMo puts blank after the vocabulary; character vocabs list it first
+        let blank_id = blank_token(vocab).unwrap_or(if vocab_size > vocab.len() {
+            vocab.len()
+        } else {
+            0
+        });
 
-            // CTC collapse: skip blanks and repeated tokens
-            if max_idx != blank_id && max_idx != prev_token {
-                token_ids.push(max_idx);
-            }
-            prev_token = max_idx;
+        let aligned = self
+            .decoder
+            .decode(logits_data, time_steps, vocab_size, blank_id, vocab);
+
+        Ok(build_result(&aligned, time_steps, vocab, audio_ms))
+    }
+
+    /// Encode the features, then decode greedily with the prediction and joint networks
+    #[allow(clippy::too_many_arguments)]
+    fn run_transducer(
+        &self,
+        encoder: &mut Session,
+        decoder: &mut Session,
+        joiner: &mut Session,
+        mel_features: &Array2<f32>,
+        n_mels: usize,
+        n_frames: usize,
+        vocab: &[String],
+    ) -> Result<TranscriptionResult> {
+        let mel_flat: Vec<f32> = mel_features.iter().copied().collect();
+
+        let signal_value = Tensor::from_array(([1usize, n_mels, n_frames], mel_flat))
+            .map_err(|e| anyhow!("Failed to create signal tensor: {}", e))?;
+        let length_value = Tensor::from_array(([1usize], vec![n_frames as i64]))
+            .map_err(|e| anyhow!("Failed to create length tensor: {}", e))?;
+
+        let inputs = ort::inputs![
+            "audio_signal" => signal_value,
+            "length" => length_value
+        ];
+
+        let outputs = encoder
+            .run(inputs)
+            .map_err(|e| anyhow!("ONNX encoder failed: {}", e))?;
+
+        // NeMo encoders output [batch, dim, frames] and the valid frame count
+        let (shape_ref, encoded) = outputs[0]
+            .try_extract_tensor::<f32>()
+            .map_err(|e| anyhow!("Failed to extract encoder output: {}", e))?;
+        let shape: Vec<usize> = shape_ref.iter().map(|&d| d as usize).collect();
+        if shape.len() != 3 {
+            return Err(anyhow!(
+                "Unexpected encoder output shape: {:?}, expected 3D",
+                shape
+            ));
         }
+        let (dim, encoded_frames) = (shape[1], shape[2]);
+        let valid_frames = if outputs.len() > 1 {
+            outputs[1]
+                .try_extract_tensor::<i64>()
+                .ok()
+                .and_then(|(_, lengths)| lengths.first().copied())
+                .map_or(encoded_frames, |len| {
+                    (len.max(0) as usize).min(encoded_frames)
+                })
+        } else {
+            encoded_frames
+        };
+
+        // One contiguous vector per frame, for the joiner
+        let frames: Vec<Vec<f32>> = (0..valid_frames)
+            .map(|t| (0..dim).map(|d| encoded[d * encoded_frames + t]).collect())
+            .collect();
+        drop(outputs);
 
-        // Convert token IDs to text
-        let text: String = token_ids
+        let blank_id = blank_token(vocab).unwrap_or(vocab.len());
+        let n_tokens = vocab.len().max(blank_id + 1);
+
+        let mut net = OnnxTransducer {
+            decoder,
+            joiner,
+            frames,
+        };
+        let aligned =
+            transducer::greedy_decode(&mut net, valid_frames, n_tokens, blank_id, &self.durations)?;
+
+        Ok(build_result(
+            &aligned,
+            encoded_frames,
+            vocab,
+            samples_to_ms(n_frames, 160, 16000),
+        ))
+    }
+}
+
+/// The prediction and joint networks of a NeMo transducer export
+struct OnnxTransducer<'a> {
+    decoder: &'a mut Session,
+    joiner: &'a mut Session,
+    /// Encoder output, one vector per frame
+    frames: Vec<Vec<f32>>,
+}
+
+/// Prediction network state tensors: (shape, data)
+type PredictionState = Vec<(Vec<usize>, Vec<f32>)>;
+
+impl TransducerNet for OnnxTransducer<'_> {
+    type State = PredictionState;
+
+    /// Zeroed states shaped like the decoder's inputs after the target and its length
+    fn initial_state(&mut self) -> Result<PredictionState> {
+        Ok(self
+            .decoder
+            .inputs()
             .iter()
-            .filter_map(|&id| vocab.get(id))
-            .map(|token| {
-                // Handle SentencePiece-style tokens (▁ = space)
-                token.replace('\u{2581}', " ")
+            .skip(2)
+            .map(|input| {
+                // Dynamic dimensions are the batch
+                let shape: Vec<usize> = input
+                    .dtype()
+                    .tensor_shape()
+                    .map(|shape| shape.iter().map(|&d| d.max(1) as usize).collect())
+                    .unwrap_or_default();
+                let len = shape.iter().product();
+                (shape, vec![0.0; len])
             })
-            .collect::<String>()
-            .trim()
-            .to_string();
+            .collect())
+    }
 
-        if text.is_empty() {
-            return Ok(TranscriptionResult {
-                segments: vec![],
-                full_text: String::new(),
-            });
+    fn predict(
+        &mut self,
+        token: usize,
+        state: &PredictionState,
+    ) -> Result<(Vec<f32>, PredictionState)> {
+        let int64 = self
+            .decoder
+            .inputs()
+            .first()
+            .and_then(|i| i.dtype().tensor_type())
+            == Some(TensorElementType::Int64);
+        let mut inputs: Vec<SessionInputValue<'_>> = if int64 {
+            vec![
+                Tensor::from_array(([1usize, 1], vec![token as i64]))?.into(),
+                Tensor::from_array(([1usize], vec![1i64]))?.into(),
+            ]
+        } else {
+            vec![
+                Tensor::from_array(([1usize, 1], vec![token as i32]))?.into(),
+                Tensor::from_array(([1usize], vec![1i32]))?.into(),
+            ]
+        };
+        for (shape, data) in state {
+            inputs.push(Tensor::from_array((shape.clone(), data.clone()))?.into());
         }
 
-        // Create a single segment for the full utterance
-        let duration_ms = (samples_to_ms(time_steps, 160, 16000)) as i64;
-        Ok(TranscriptionResult {
-            segments: vec![TranscriptionSegment {
-                text: text.clone(),
-                start_ms: 0,
-                end_ms: duration_ms,
-            }],
-            full_text: text,
+        let outputs = self
+            .decoder
+            .run(inputs.as_slice())
+            .map_err(|e| anyhow!("ONNX decoder failed: {}", e))?;
+
+        let (_, prediction) = outputs[0]
+            .try_extract_tensor::<f32>()
+            .map_err(|e| anyhow!("Failed to extract decoder output: {}", e))?;
+        // The new states are the last outputs, in input order
+        let first_state = outputs.len().saturating_sub(state.len());
+        let next_state = (first_state..outputs.len())
+            .map(|i| {
+                let (shape, data) = outputs[i]
+                    .try_extract_tensor::<f32>()
+                    .map_err(|e| anyhow!("Failed to extract decoder state: {}", e))?;
+                Ok((shape.iter().map(|&d| d as usize).collect(), data.to_vec()))
+            })
+            .collect::<Result<_>>()?;
+        Ok((prediction.to_vec(), next_state))
+    }
+
+    fn join(&mut self, frame: usize, prediction: &[f32]) -> Result<Vec<f32>> {
+        // Joiners take [batch, dim] or [batch, dim, 1], whichever the export declares
+        let shape_for = |index: usize, len: usize| -> Vec<usize> {
+            let rank = self
+                .joiner
+                .inputs()
+                .get(index)
+                .and_then(|i| i.dtype().tensor_shape().map(|s| s.len()))
+                .unwrap_or(2);
+            if rank == 3 {
+                vec![1, len, 1]
+            } else {
+                vec![1, len]
+            }
+        };
+        let encoder_out = &self.frames[frame];
+        let encoder_value =
+            Tensor::from_array((shape_for(0, encoder_out.len()), encoder_out.clone()))?;
+        let decoder_value =
+            Tensor::from_array((shape_for(1, prediction.len()), prediction.to_vec()))?;
+
+        let outputs = self
+            .joiner
+            .run(ort::inputs![encoder_value, decoder_value])
+            .map_err(|e| anyhow!("ONNX joiner failed: {}", e))?;
+        let (_, logits) = outputs[0]
+            .try_extract_tensor::<f32>()
+            .map_err(|e| anyhow!("Failed to extract joiner logits: {}", e))?;
+        Ok(logits.to_vec())
+    }
+}
+
+/// Text and word timings of decoded tokens, as one segment for the utterance.
+/// `audio_ms` is the length of the input, which the `n_frames` output frames span evenly.
+fn build_result(
+    aligned: &[AlignedToken],
+    n_frames: usize,
+    vocab: &[String],
+    audio_ms: u64,
+) -> TranscriptionResult {
+    // Convert token IDs to text
+    let text: String = aligned
+        .iter()
+        .filter_map(|token| vocab.get(token.id))
+        .map(|token| {
+            // Handle SentencePiece-style tokens (▁ = space)
+            token.replace('\u{2581}', " ")
         })
+        .collect::<String>()
+        .trim()
+        .to_string();
+
+    if text.is_empty() {
+        return TranscriptionResult {
+            segments: vec![],
+            full_text: String::new(),
+            language: None,
+            language_probability: None,
+        };
     }
+
+    // Create a single segment for the full utterance
+    let frame_ms = audio_ms as f64 / n_frames.max(1) as f64;
+    let words = words_from_tokens(aligned.iter().filter_map(|token| {
+        Some(TokenTiming {
+            text: vocab.get(token.id)?.clone(),
+            start_ms: (token.first_frame as f64 * frame_ms) as i64,
+            end_ms: ((token.last_frame + 1) as f64 * frame_ms) as i64,
+            probability: token.probability,
+        })
+    }));
+    TranscriptionResult {
+        segments: vec![TranscriptionSegment {
+            text: text.clone(),
+            start_ms: 0,
+            end_ms: audio_ms as i64,
+            words,
+        }],
+        full_text: text,
+        language: None,
+        language_probability: None,
+    }
+}
+
+/// Build an ONNX Runtime session for one model file
+fn create_session(path: &Path) -> Result<Session> {
+    Session::builder()
+        .map_err(|e| anyhow!("Failed to create ONNX session builder: {}", e))?
+        .with_optimization_level(GraphOptimizationLevel::Level3)
+        .map_err(|e| anyhow!("Failed to set optimization level: {}", e))?
+        .with_intra_threads(num_cpus::get().min(4))
+        .map_err(|e| anyhow!("Failed to set thread count: {}", e))?
+        .commit_from_file(path)
+        .map_err(|e| anyhow!("Failed to load ONNX model {:?}: {}", path, e))
+}
+
+/// Decoder and joiner files beside a transducer encoder (`*encoder*.onnx`), if both exist
+fn transducer_paths(encoder: &Path) -> Option<(PathBuf, PathBuf)> {
+    let name = encoder.file_name()?.to_str()?;
+    let at = name.rfind("encoder")?;
+    let sibling =
+        |part: &str| encoder.with_file_name(format!("{}{}{}", &name[..at], part, &name[at + 7..]));
+    let (decoder, joiner) = (sibling("decoder"), sibling("joiner"));
+    (decoder.exists() && joiner.exists()).then_some((decoder, joiner))
+}
+
+/// Find the vocabulary file for a model: `<model>.vocab.json`, `<model>-vocab.json`
+/// (without the `-encoder` part for transducers), or a sherpa-onnx `tokens.txt`
+fn find_vocab(path: &Path) -> Option<PathBuf> {
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("model");
+    [
+        path.with_extension("vocab.json"),
+        path.with_file_name(format!("{}-vocab.json", stem)),
+        path.with_file_name(format!("{}-vocab.json", stem.trim_end_matches("-encoder"))),
+        path.with_file_name("tokens.txt"),
+    ]
+    .into_iter()
+    .find(|candidate| candidate.exists())
+}
+
+/// Id of the blank token when the vocabulary lists it
+fn blank_token(vocab: &[String]) -> Option<usize> {
+    vocab
+        .iter()
+        .position(|token| token == "<blk>" || token == "<blank>")
 }
 
 /// Estimate duration in ms from mel frames
@@ -427,15 +939,38 @@ fn samples_to_ms(n_frames: usize, hop_length: usize, sample_rate: usize) -> u64
     (n_frames * hop_length * 1000 / sample_rate) as u64
 }
 
-/// Load vocabulary from a JSON file (array of token strings)
+/// Load vocabulary from a JSON file (array of token strings), or a `tokens.txt` with one
+/// "token id" pair per line
 fn load_vocab(path: &Path) -> Result<Vec<String>> {
     let content = std::fs::read_to_string(path)
         .map_err(|e| anyhow!("Failed to read vocab file {:?}: {}", path, e))?;
+    if path.extension().and_then(|e| e.to_str()) == Some("txt") {
+        return parse_tokens_txt(&content);
+    }
     let vocab: Vec<String> =
         serde_json::from_str(&content).map_err(|e| anyhow!("Failed to parse vocab JSON: {}", e))?;
     Ok(vocab)
 }
 
+fn parse_tokens_txt(content: &str) -> Result<Vec<String>> {
+    let mut tokens: Vec<(usize, String)> = content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let (token, id) = line
+                .rsplit_once(' ')
+                .ok_or_else(|| anyhow!("Invalid tokens.txt line: {:?}", line))?;
+            let id = id
+                .trim()
+                .parse()
+                .map_err(|_| anyhow!("Invalid token id in tokens.txt: {:?}", line))?;
+            Ok((id, token.to_string()))
+        })
+        .collect::<Result<_>>()?;
+    tokens.sort_by_key(|(id, _)| *id);
+    Ok(tokens.into_iter().map(|(_, token)| token).collect())
+}
+
 /// Default English character-level vocabulary (fallback)
 /// Token 0 = blank (CTC), then characters
 fn default_char_vocab() -> Vec<String> {
@@ -461,11 +996,85 @@ pub fn get_parakeet_models_dir() -> Result<PathBuf> {
 
 /// Check if a Parakeet model is downloaded
 pub fn is_parakeet_model_downloaded(model: ParakeetModel) -> Result<bool> {
-    let model_path = get_parakeet_models_dir()?.join(model.filename());
-    Ok(model_path.exists())
+    let models_dir = get_parakeet_models_dir()?;
+    Ok(models_dir.join(model.filename()).exists()
+        && model
+            .transducer_files()
+            .iter()
+            .all(|(filename, _)| models_dir.join(filename).exists()))
 }
 
 /// Get the full path to a Parakeet model file
 pub fn get_parakeet_model_path(model: ParakeetModel) -> Result<PathBuf> {
     Ok(get_parakeet_models_dir()?.join(model.filename()))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_model_files() {
+        let model: ParakeetModel = "tdt-0.6b-v3".parse().unwrap();
+        assert_eq!(model, ParakeetModel::Tdt06bV3);
+        assert_eq!(model.filename(), "parakeet-tdt-0.6b-v3-encoder.onnx");
+        let files: Vec<String> = model.transducer_files().into_iter().map(|f| f.0).collect();
+        assert_eq!(
+            files,
+            vec![
+                "parakeet-tdt-0.6b-v3-decoder.onnx",
+                "parakeet-tdt-0.6b-v3-joiner.onnx"
+            ]
+        );
+        assert!(model.languages().len() > 1);
+        assert_eq!(ParakeetModel::Ctc06b.filename(), "parakeet-ctc-0.6b.onnx");
+        assert!(ParakeetModel::Ctc06b.transducer_files().is_empty());
+    }
+
+    #[test]
+    fn test_export_metadata() {
+        let metadata = ExportMetadata::parse(|key| {
+            match key {
+                "vocabulary" => Some(r#"["▁a", "b", "<blk>"]"#),
+                "languages" => Some("en, DE,fr"),
+                "feat_dim" => Some("128"),
+                "normalize_type" => Some("per_feature"),
+                "tdt_durations" => Some("0,1,2,3,4"),
+                _ => None,
+            }
+            .map(str::to_string)
+        })
+        .unwrap();
+        assert_eq!(metadata.vocab.as_deref().map(blank_token), Some(Some(2)));
+        assert_eq!(metadata.languages, vec!["en", "de", "fr"]);
+        assert_eq!(metadata.feat_dim, Some(128));
+        assert!(metadata.per_feature);
+        assert_eq!(metadata.durations, vec![0, 1, 2, 3, 4]);
+
+        assert_eq!(
+            ExportMetadata::parse(|_| None).unwrap(),
+            ExportMetadata::default()
+        );
+        assert!(ExportMetadata::parse(|_| Some("x".to_string())).is_err());
+    }
+
+    #[test]
+    fn test_parse_tokens_txt() {
+        let vocab = parse_tokens_txt("<unk> 0\n\u{2581}the 2\ns 1\n<blk> 3\n").unwrap();
+        assert_eq!(vocab, vec!["<unk>", "s", "\u{2581}the", "<blk>"]);
+        assert!(parse_tokens_txt("nospace\n").is_err());
+    }
+
+    #[test]
+    fn test_language_falls_back_to_what_the_model_supports() {
+        let mut backend = ParakeetBackend::new();
+        backend.set_language("de");
+        assert_eq!(backend.language(), "en");
+
+        backend.languages = ParakeetModel::Tdt06bV3.languages();
+        backend.resolve_language();
+        assert_eq!(backend.language(), "de");
+        backend.set_language("ja");
+        assert_eq!(backend.language(), "auto");
+    }
+}
@@ -0,0 +1,300 @@
+// Punctuation and casing restoration
+// CTC models (Parakeet CTC) write lowercase words with no punctuation. A small token
+// classification model (model.onnx + tokenizer.json + config.json, BERT-style) labels each
+// word with the punctuation that follows it and whether it starts with a capital, and
+// sentence starts and "I" are capitalized on top of that.
+//
+// Labels come from `id2label` in config.json, either punctuation only ("O", ".", ",", "?")
+// or punctuation and case combined ("OU" = no punctuation, capitalized; ".O" = period).
+
+use anyhow::{anyhow, Result};
+use ort::session::Session;
+use ort::value::Value;
+use std::path::Path;
+use std::sync::Mutex;
+
+use super::backend::TranscriptionSegment;
+
+/// Words classified per model run (keeps the token count well under 512)
+const MAX_WORDS_PER_RUN: usize = 150;
+/// Punctuation that ends a sentence
+const SENTENCE_END: [char; 3] = ['.', '?', '!'];
+
+/// What the model says about one word
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WordLabel {
+    /// Punctuation written after the word ("" = none)
+    pub punctuation: String,
+    /// Capitalize the word's first letter
+    pub capitalize: bool,
+}
+
+pub struct PunctuationModel {
+    session: Mutex<Session>,
+    tokenizer: tokenizers::Tokenizer,
+    labels: Vec<WordLabel>,
+}
+
+impl PunctuationModel {
+    /// Load from a directory containing model.onnx, tokenizer.json and config.json
+    pub fn load(model_dir: &Path) -> Result<Self> {
+        let model_path = model_dir.join("model.onnx");
+        let tokenizer_path = model_dir.join("tokenizer.json");
+        let config_path = model_dir.join("config.json");
+
+        for path in [&model_path, &tokenizer_path, &config_path] {
+            if !path.exists() {
+                return Err(anyhow!("{:?} not found", path));
+            }
+        }
+
+        log::info!("Loading punctuation model from {:?}", model_dir);
+
+        let config = std::fs::read_to_string(&config_path)
+            .map_err(|e| anyhow!("Failed to read {:?}: {}", config_path, e))?;
+        let labels = parse_labels(&config)?;
+
+        let session = Session::builder()?
+            .with_intra_threads(2)?
+            .commit_from_file(&model_path)?;
+
+        let tokenizer = tokenizers::Tokenizer::from_file(&tokenizer_path)
+            .map_err(|e| anyhow!("Failed to load tokenizer: {}", e))?;
+
+        log::info!("Punctuation model loaded ({} labels)", labels.len());
+        Ok(Self {
+            session: Mutex::new(session),
+            tokenizer,
+            labels,
+        })
+    }
+
+    /// Label every word, a window of words per model run
+    pub fn label_words(&self, words: &[&str]) -> Result<Vec<WordLabel>> {
+        let mut labels = Vec::with_capacity(words.len());
+        for window in words.chunks(MAX_WORDS_PER_RUN) {
+            labels.extend(self.label_window(window)?);
+        }
+        Ok(labels)
+    }
+
+    fn label_window(&self, words: &[&str]) -> Result<Vec<WordLabel>> {
+        let encoding = self
+            .tokenizer
+            .encode(words.to_vec(), true)
+            .map_err(|e| anyhow!("Tokenization failed: {}", e))?;
+        let seq_len = encoding.get_ids().len();
+
+        let mut session = self
+            .session
+            .lock()
+            .map_err(|e| anyhow!("Session lock: {}", e))?;
+
+        // BERT exports take token_type_ids, RoBERTa-style ones don't
+        let mut inputs = Vec::new();
+        for input in session.inputs() {
+            let values: Vec<i64> = match input.name() {
+                "input_ids" => encoding.get_ids().iter().map(|&id| id as i64).collect(),
+                "attention_mask" => encoding
+                    .get_attention_mask()
+                    .iter()
+                    .map(|&m| m as i64)
+                    .collect(),
+                "token_type_ids" => encoding.get_type_ids().iter().map(|&t| t as i64).collect(),
+                name => return Err(anyhow!("Unexpected punctuation model input: {}", name)),
+            };
+            inputs.push((
+                input.name().to_string(),
+                Value::from_array(([1, seq_len], values))?,
+            ));
+        }
+        let outputs = session.run(inputs)?;
+
+        // Output shape: [1, seq_len, labels]
+        let (shape, logits) = outputs[0].try_extract_tensor::<f32>()?;
+        let n_labels = shape[shape.len() - 1] as usize;
+
+        // Punctuation goes after a word, so its last sub-token decides
+        let mut best = vec![None; words.len()];
+        for (t, word) in encoding.get_word_ids().iter().enumerate() {
+            let Some(word) = word.map(|w| w as usize).filter(|&w| w < words.len()) else {
+                continue;
+            };
+            let scores = &logits[t * n_labels..(t + 1) * n_labels];
+            best[word] = scores
+                .iter()
+                .enumerate()
+                .max_by(|a, b| a.1.total_cmp(b.1))
+                .map(|(i, _)| i);
+        }
+        Ok(best
+            .into_iter()
+            .map(|id| {
+                id.and_then(|id| self.labels.get(id))
+                    .cloned()
+                    .unwrap_or_default()
+            })
+            .collect())
+    }
+
+    /// Restore punctuation and casing in a segment, and in its word timings when they are
+    /// the same words as the text
+    pub fn restore_segment(&self, segment: &mut TranscriptionSegment) -> Result<()> {
+        let words: Vec<&str> = segment.text.split_whitespace().collect();
+        let labels = self.label_words(&words)?;
+        let restored = apply_labels(&words, &labels);
+        if segment.words.len() == restored.len() {
+            for (word, text) in segment.words.iter_mut().zip(&restored) {
+                word.text = text.clone();
+            }
+        }
+        segment.text = restored.join(" ");
+        Ok(())
+    }
+}
+
+/// Text that still needs restoring: no capitals and no sentence punctuation anywhere
+pub fn needs_restoration(text: &str) -> bool {
+    text.chars().any(char::is_alphabetic)
+        && !text
+            .chars()
+            .any(|c| c.is_uppercase() || SENTENCE_END.contains(&c) || c == ',')
+}
+
+/// Labels for each class id from a Hugging Face config.json (`id2label`)
+fn parse_labels(config: &str) -> Result<Vec<WordLabel>> {
+    let config: serde_json::Value =
+        serde_json::from_str(config).map_err(|e| anyhow!("Invalid config.json: {}", e))?;
+    let id2label = config
+        .get("id2label")
+        .and_then(|v| v.as_object())
+        .ok_or_else(|| anyhow!("config.json has no id2label"))?;
+    let mut labels = vec![WordLabel::default(); id2label.len()];
+    for (id, label) in id2label {
+        let id: usize = id
+            .parse()
+            .map_err(|_| anyhow!("Invalid label id in config.json: {}", id))?;
+        let label = label
+            .as_str()
+            .ok_or_else(|| anyhow!("Label {} is not a string", id))?;
+        *labels
+            .get_mut(id)
+            .ok_or_else(|| anyhow!("Label id {} out of range", id))? = parse_label(label);
+    }
+    Ok(labels)
+}
+
+fn parse_label(label: &str) -> WordLabel {
+    let chars: Vec<char> = label.chars().collect();
+    // Combined "<punctuation><case>", punctuation "O" meaning none
+    if let [p, case @ ('U' | 'O')] = chars.as_slice() {
+        if *p == 'O' || p.is_ascii_punctuation() {
+            return WordLabel {
+                punctuation: if *p == 'O' {
+                    String::new()
+                } else {
+                    p.to_string()
+                },
+                capitalize: *case == 'U',
+            };
+        }
+    }
+    let none = matches!(label, "O" | "0" | "");
+    WordLabel {
+        punctuation: if none {
+            String::new()
+        } else {
+            label.to_string()
+        },
+        capitalize: false,
+    }
+}
+
+/// Write each word with its label, capitalizing sentence starts and "I"
+pub fn apply_labels(words: &[&str], labels: &[WordLabel]) -> Vec<String> {
+    let mut sentence_start = true;
+    words
+        .iter()
+        .enumerate()
+        .map(|(i, &word)| {
+            let label = labels.get(i).cloned().unwrap_or_default();
+            let lower = word.to_lowercase();
+            let is_i = lower == "i" || lower.starts_with("i'");
+            let mut text = if label.capitalize || sentence_start || is_i {
+                capitalize(word)
+            } else {
+                word.to_string()
+            };
+            // Keep punctuation the word already had
+            if !text.ends_with(|c: char| c.is_ascii_punctuation()) {
+                text.push_str(&label.punctuation);
+            }
+            sentence_start = text.ends_with(SENTENCE_END);
+            text
+        })
+        .collect()
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_labels() {
+        let labels =
+            parse_labels(r#"{"id2label": {"0": "OU", "1": "OO", "2": ".O", "3": "?U"}}"#).unwrap();
+        assert_eq!(
+            labels[0],
+            WordLabel {
+                punctuation: String::new(),
+                capitalize: true
+            }
+        );
+        assert_eq!(labels[2].punctuation, ".");
+        assert!(!labels[2].capitalize);
+        assert!(labels[3].capitalize && labels[3].punctuation == "?");
+
+        let plain = parse_labels(r#"{"id2label": {"0": "0", "1": ",", "2": "."}}"#).unwrap();
+        assert_eq!(plain[0], WordLabel::default());
+        assert_eq!(plain[1].punctuation, ",");
+        assert!(parse_labels("{}").is_err());
+    }
+
+    #[test]
+    fn test_apply_labels() {
+        let label = |punctuation: &str, capitalize: bool| WordLabel {
+            punctuation: punctuation.to_string(),
+            capitalize,
+        };
+        let words = ["so", "i", "met", "anna", "today", "did", "you"];
+        let labels = [
+            label(",", false),
+            label("", false),
+            label("", false),
+            label("", true),
+            label(".", false),
+            label("", false),
+            label("?", false),
+        ];
+        assert_eq!(
+            apply_labels(&words, &labels).join(" "),
+            "So, I met Anna today. Did you?"
+        );
+    }
+
+    #[test]
+    fn test_needs_restoration() {
+        assert!(needs_restoration("hello there how are you"));
+        assert!(!needs_restoration("Hello there."));
+        assert!(!needs_restoration("hello there, how are you"));
+        assert!(!needs_restoration("123"));
+    }
+}
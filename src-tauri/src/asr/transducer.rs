@@ -0,0 +1,171 @@
+// Transducer decoding
+// Greedy decoding for NeMo RNN-T and TDT (token-and-duration transducer) models. The
+// prediction network is fed the last emitted token, and the joint network combines its
+// output with one encoder frame into token logits. TDT joiners also predict how many
+// frames the token covers, so decoding can skip ahead instead of visiting every frame.
+
+use anyhow::Result;
+
+use super::ctc_decoder::AlignedToken;
+
+/// Tokens emitted on one encoder frame before moving on (guards against loops)
+pub const MAX_SYMBOLS_PER_FRAME: usize = 10;
+
+/// The prediction and joint networks of a transducer
+pub trait TransducerNet {
+    /// Recurrent state of the prediction network
+    type State;
+
+    /// Prediction network state before any token
+    fn initial_state(&mut self) -> Result<Self::State>;
+
+    /// Run the prediction network on a token, returning its output and the next state
+    fn predict(&mut self, token: usize, state: &Self::State) -> Result<(Vec<f32>, Self::State)>;
+
+    /// Run the joint network on an encoder frame and a prediction output, returning logits
+    /// over the tokens (blank included) followed by the TDT durations, if any
+    fn join(&mut self, frame: usize, prediction: &[f32]) -> Result<Vec<f32>>;
+}
+
+/// Greedy transducer decoding over `n_frames` encoder frames.
+/// `n_tokens` logits are tokens (blank included); `durations` lists the frame counts a TDT
+/// joiner's remaining logits stand for, and is empty for RNN-T.
+pub fn greedy_decode<N: TransducerNet>(
+    net: &mut N,
+    n_frames: usize,
+    n_tokens: usize,
+    blank_id: usize,
+    durations: &[usize],
+) -> Result<Vec<AlignedToken>> {
+    let mut tokens = Vec::new();
+    let mut state = net.initial_state()?;
+    // NeMo primes the prediction network with blank
+    let (mut prediction, mut next_state) = net.predict(blank_id, &state)?;
+
+    let mut t = 0;
+    while t < n_frames {
+        let mut emitted = 0;
+        loop {
+            let logits = net.join(t, &prediction)?;
+            let (token, probability) = argmax_softmax(&logits[..n_tokens.min(logits.len())]);
+            // Frames this step consumes: the predicted duration for TDT, one blank for RNN-T
+            let skip = if durations.is_empty() {
+                usize::from(token == blank_id)
+            } else {
+                let (d, _) = argmax_softmax(logits.get(n_tokens..).unwrap_or(&[]));
+                durations.get(d).copied().unwrap_or(1)
+            };
+
+            if token != blank_id {
+                tokens.push(AlignedToken {
+                    id: token,
+                    first_frame: t,
+                    last_frame: (t + skip.max(1) - 1).min(n_frames - 1),
+                    probability,
+                });
+                state = next_state;
+                (prediction, next_state) = net.predict(token, &state)?;
+                emitted += 1;
+            }
+
+            if skip > 0 {
+                t += skip;
+                break;
+            }
+            // A blank with no duration, or too many tokens on one frame, still moves on
+            if token == blank_id || emitted >= MAX_SYMBOLS_PER_FRAME {
+                t += 1;
+                break;
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+/// Index and softmax probability of the largest logit
+fn argmax_softmax(logits: &[f32]) -> (usize, f32) {
+    let mut max_val = f32::NEG_INFINITY;
+    let mut max_idx = 0usize;
+    for (i, &val) in logits.iter().enumerate() {
+        if val > max_val {
+            max_val = val;
+            max_idx = i;
+        }
+    }
+    let sum: f32 = logits.iter().map(|&val| (val - max_val).exp()).sum();
+    (max_idx, 1.0 / sum)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A transducer that emits a scripted token sequence. Each step is the (token, duration)
+    /// the joiner picks for the current frame and the tokens emitted so far.
+    struct Scripted {
+        steps: Vec<(usize, usize)>,
+        n_tokens: usize,
+        n_durations: usize,
+        joins: Vec<usize>,
+    }
+
+    impl TransducerNet for Scripted {
+        type State = usize;
+
+        fn initial_state(&mut self) -> Result<usize> {
+            Ok(0)
+        }
+
+        fn predict(&mut self, token: usize, state: &usize) -> Result<(Vec<f32>, usize)> {
+            // The prediction output is the number of non-blank tokens seen
+            let seen = if token == 0 { *state } else { state + 1 };
+            Ok((vec![seen as f32], seen))
+        }
+
+        fn join(&mut self, frame: usize, _prediction: &[f32]) -> Result<Vec<f32>> {
+            self.joins.push(frame);
+            let (token, duration) = self.steps[self.joins.len() - 1];
+            let mut logits = vec![0.0; self.n_tokens + self.n_durations];
+            logits[token] = 5.0;
+            if self.n_durations > 0 {
+                logits[self.n_tokens + duration] = 5.0;
+            }
+            Ok(logits)
+        }
+    }
+
+    #[test]
+    fn test_rnnt_greedy_emits_until_blank() {
+        // Frame 0: "a", "b", blank; frame 1: blank; frame 2: "c", blank
+        let mut net = Scripted {
+            steps: vec![(1, 0), (2, 0), (0, 0), (0, 0), (3, 0), (0, 0)],
+            n_tokens: 4,
+            n_durations: 0,
+            joins: Vec::new(),
+        };
+        let tokens = greedy_decode(&mut net, 3, 4, 0, &[]).unwrap();
+        let spans: Vec<(usize, usize)> = tokens.iter().map(|t| (t.id, t.first_frame)).collect();
+        assert_eq!(spans, vec![(1, 0), (2, 0), (3, 2)]);
+        assert_eq!(net.joins, vec![0, 0, 0, 1, 2, 2]);
+        let expected = 5f32.exp() / (5f32.exp() + 3.0);
+        assert!((tokens[0].probability - expected).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_tdt_greedy_skips_predicted_durations() {
+        // Durations [0, 1, 2, 4]: "a" for 2 frames, "b" without moving, "c" for 4, blank 1
+        let mut net = Scripted {
+            steps: vec![(1, 2), (2, 0), (3, 3), (0, 1)],
+            n_tokens: 4,
+            n_durations: 4,
+            joins: Vec::new(),
+        };
+        let tokens = greedy_decode(&mut net, 7, 4, 0, &[0, 1, 2, 4]).unwrap();
+        let spans: Vec<(usize, usize, usize)> = tokens
+            .iter()
+            .map(|t| (t.id, t.first_frame, t.last_frame))
+            .collect();
+        assert_eq!(spans, vec![(1, 0, 1), (2, 2, 2), (3, 2, 5)]);
+        assert_eq!(net.joins, vec![0, 2, 2, 6]);
+    }
+}
@@ -5,15 +5,28 @@ use anyhow::{anyhow, Result};
 use async_trait::async_trait;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use tokio::sync::Mutex;
-use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters};
-
-use super::backend::{AsrBackend, TranscriptionResult, TranscriptionSegment};
+use whisper_rs::{
+    FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters, WhisperState,
+    WhisperToken,
+};
+
+use super::backend::{
+    pick_language, words_from_tokens, AsrBackend, LanguageOptions, TokenTiming,
+    TranscriptionResult, TranscriptionSegment, WordTiming,
+};
+
+/// Threads for language detection (Whisper's own default for decoding)
+fn detect_threads() -> usize {
+    num_cpus::get().clamp(1, 4)
+}
 
 pub struct WhisperBackend {
-    context: Option<Arc<Mutex<WhisperContext>>>,
+    // Shared by concurrent transcriptions; each decodes on a state of its own
+    context: Option<Arc<WhisperContext>>,
     model_path: Option<PathBuf>,
     language: String,
+    /// Decoder threads per transcription; 0 leaves it to Whisper
+    threads: usize,
 }
 
 impl WhisperBackend {
@@ -22,6 +35,7 @@ impl WhisperBackend {
             context: None,
             model_path: None,
             language: "en".to_string(),
+            threads: 0,
         }
     }
 
@@ -60,7 +74,7 @@ impl AsrBackend for WhisperBackend {
         )
         .map_err(|e| anyhow!("Failed to load Whisper model: {:?}", e))?;
 
-        self.context = Some(Arc::new(Mutex::new(ctx)));
+        self.context = Some(Arc::new(ctx));
         self.model_path = Some(path.to_path_buf());
 
         log::info!("Whisper model loaded successfully");
@@ -75,95 +89,254 @@ impl AsrBackend for WhisperBackend {
         &self.language
     }
 
+    fn set_threads(&mut self, threads: usize) {
+        self.threads = threads;
+    }
+
+    fn parallel(&self) -> bool {
+        true
+    }
+
     async fn transcribe(&self, audio_samples: &[f32]) -> Result<TranscriptionResult> {
+        self.transcribe_with_prompt(audio_samples, "").await
+    }
+
+    async fn transcribe_with_prompt(
+        &self,
+        audio_samples: &[f32],
+        prompt: &str,
+    ) -> Result<TranscriptionResult> {
+        self.transcribe_with_language(audio_samples, prompt, &LanguageOptions::default())
+            .await
+    }
+
+    async fn transcribe_with_language(
+        &self,
+        audio_samples: &[f32],
+        prompt: &str,
+        options: &LanguageOptions,
+    ) -> Result<TranscriptionResult> {
+        self.decode(audio_samples, prompt, options, false).await
+    }
+
+    async fn translate_to_english(
+        &self,
+        audio_samples: &[f32],
+        options: &LanguageOptions,
+    ) -> Result<Option<TranscriptionResult>> {
+        self.decode(audio_samples, "", options, true)
+            .await
+            .map(Some)
+    }
+}
+
+impl WhisperBackend {
+    /// Run the decoder, transcribing or (with `translate`) translating into English. Decoding
+    /// keeps a core busy for seconds, so it runs on a blocking thread.
+    async fn decode(
+        &self,
+        audio_samples: &[f32],
+        prompt: &str,
+        options: &LanguageOptions,
+        translate: bool,
+    ) -> Result<TranscriptionResult> {
         let context = self
             .context
-            .as_ref()
+            .clone()
             .ok_or_else(|| anyhow!("No model loaded"))?;
 
         if audio_samples.is_empty() {
             return Ok(TranscriptionResult {
                 segments: vec![],
                 full_text: String::new(),
+                language: None,
+                language_probability: None,
             });
         }
 
-        let ctx = context.lock().await;
-
-        // Create a new state for this transcription
-        let mut state = ctx
-            .create_state()
-            .map_err(|e| anyhow!("Failed to create state: {:?}", e))?;
+        let samples = audio_samples.to_vec();
+        let prompt = prompt.to_string();
+        let requested = options
+            .language
+            .clone()
+            .unwrap_or_else(|| self.language.clone());
+        let candidates = options.candidates.clone();
+        let threads = self.threads;
+        tokio::task::spawn_blocking(move || {
+            decode(
+                &context,
+                &samples,
+                &prompt,
+                &requested,
+                &candidates,
+                threads,
+                translate,
+            )
+        })
+        .await
+        .map_err(|e| anyhow!("Transcription panicked: {}", e))?
+    }
+}
 
-        // Configure transcription parameters
-        let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+/// Decode on a state of its own, in `requested` language ("auto" = detect it among
+/// `candidates`)
+fn decode(
+    ctx: &WhisperContext,
+    audio_samples: &[f32],
+    prompt: &str,
+    requested: &str,
+    candidates: &[String],
+    threads: usize,
+    translate: bool,
+) -> Result<TranscriptionResult> {
+    // Create a new state for this transcription
+    let mut state = ctx
+        .create_state()
+        .map_err(|e| anyhow!("Failed to create state: {:?}", e))?;
+
+    // Detect the language ourselves rather than leave it to the decoder, so it can be
+    // restricted to candidates and reported with the result
+    let (language, language_probability) = if requested == "auto" {
+        let (code, probability) = detect_language(&mut state, audio_samples, candidates)?;
+        (code, Some(probability))
+    } else {
+        (requested.to_string(), None)
+    };
+
+    // Configure transcription parameters
+    let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+    params.set_language(Some(&language));
+    if threads > 0 {
+        params.set_n_threads(threads as i32);
+    }
 
-        // Set language
-        if self.language == "auto" {
-            params.set_language(None); // Auto-detect
-        } else {
-            params.set_language(Some(&self.language));
-        }
+    // Translation decodes the same speech as English text
+    params.set_translate(translate);
 
-        // Optimize for real-time transcription
-        params.set_translate(false);
-        params.set_no_context(true);
-        params.set_single_segment(false);
-        params.set_print_special(false);
-        params.set_print_progress(false);
-        params.set_print_realtime(false);
-        params.set_print_timestamps(false);
-        params.set_suppress_blank(true);
-        params.set_suppress_non_speech_tokens(true);
-
-        // Run transcription
-        state
-            .full(params, audio_samples)
-            .map_err(|e| anyhow!("Transcription failed: {:?}", e))?;
-
-        // Extract segments
-        let num_segments = state
-            .full_n_segments()
-            .map_err(|e| anyhow!("Failed to get segments: {:?}", e))?;
-
-        let mut segments = Vec::new();
-        let mut full_text = String::new();
-
-        for i in 0..num_segments {
-            let text = state
-                .full_get_segment_text(i)
-                .map_err(|e| anyhow!("Failed to get segment text: {:?}", e))?;
-
-            let start = state
-                .full_get_segment_t0(i)
-                .map_err(|e| anyhow!("Failed to get segment start: {:?}", e))?;
-
-            let end = state
-                .full_get_segment_t1(i)
-                .map_err(|e| anyhow!("Failed to get segment end: {:?}", e))?;
-
-            // Convert from centiseconds to milliseconds
-            let start_ms = start * 10;
-            let end_ms = end * 10;
-
-            let trimmed_text = text.trim().to_string();
-            if !trimmed_text.is_empty() {
-                if !full_text.is_empty() {
-                    full_text.push(' ');
-                }
-                full_text.push_str(&trimmed_text);
-
-                segments.push(TranscriptionSegment {
-                    text: trimmed_text,
-                    start_ms,
-                    end_ms,
-                });
+    // Optimize for real-time transcription
+    params.set_no_context(true);
+    // Vocabulary and preceding speech, in place of the decoder's own context
+    if !prompt.is_empty() {
+        params.set_initial_prompt(prompt);
+    }
+    params.set_single_segment(false);
+    params.set_print_special(false);
+    params.set_print_progress(false);
+    params.set_print_realtime(false);
+    params.set_print_timestamps(false);
+    params.set_suppress_blank(true);
+    params.set_suppress_non_speech_tokens(true);
+    // Per-token times and probabilities, for word timings and confidence
+    params.set_token_timestamps(true);
+
+    // Run transcription
+    state
+        .full(params, audio_samples)
+        .map_err(|e| anyhow!("Transcription failed: {:?}", e))?;
+
+    // Extract segments
+    let num_segments = state
+        .full_n_segments()
+        .map_err(|e| anyhow!("Failed to get segments: {:?}", e))?;
+
+    let mut segments = Vec::new();
+    let mut full_text = String::new();
+    // Tokens from here on are special (timestamps, language, task)
+    let eot = ctx.token_eot();
+
+    for i in 0..num_segments {
+        let text = state
+            .full_get_segment_text(i)
+            .map_err(|e| anyhow!("Failed to get segment text: {:?}", e))?;
+
+        let start = state
+            .full_get_segment_t0(i)
+            .map_err(|e| anyhow!("Failed to get segment start: {:?}", e))?;
+
+        let end = state
+            .full_get_segment_t1(i)
+            .map_err(|e| anyhow!("Failed to get segment end: {:?}", e))?;
+
+        // Convert from centiseconds to milliseconds
+        let start_ms = start * 10;
+        let end_ms = end * 10;
+
+        let trimmed_text = text.trim().to_string();
+        if !trimmed_text.is_empty() {
+            if !full_text.is_empty() {
+                full_text.push(' ');
             }
+            full_text.push_str(&trimmed_text);
+
+            segments.push(TranscriptionSegment {
+                text: trimmed_text,
+                start_ms,
+                end_ms,
+                words: segment_words(&state, i, eot)?,
+            });
         }
+    }
+
+    Ok(TranscriptionResult {
+        segments,
+        full_text,
+        language: Some(language),
+        language_probability,
+    })
+}
 
-        Ok(TranscriptionResult {
-            segments,
-            full_text,
+/// Detect the spoken language of the audio (its first 30 seconds), among `candidates` when
+/// any of them is a language Whisper knows
+fn detect_language(
+    state: &mut WhisperState,
+    samples: &[f32],
+    candidates: &[String],
+) -> Result<(String, f32)> {
+    state
+        .pcm_to_mel(samples, detect_threads())
+        .map_err(|e| anyhow!("Failed to compute mel spectrogram: {:?}", e))?;
+    let (_, probabilities) = state
+        .lang_detect(0, detect_threads())
+        .map_err(|e| anyhow!("Language detection failed: {:?}", e))?;
+    let detected: Vec<(&str, f32)> = probabilities
+        .iter()
+        .enumerate()
+        .filter_map(|(id, &p)| Some((whisper_rs::get_lang_str(id as i32)?, p)))
+        .collect();
+    let (code, probability) = pick_language(detected.iter().copied(), candidates)
+        .or_else(|| {
+            log::warn!("No detectable language in {:?}, detecting any", candidates);
+            pick_language(detected.iter().copied(), &[])
         })
+        .ok_or_else(|| anyhow!("Language detection returned no languages"))?;
+    log::debug!("Detected language {} ({:.2})", code, probability);
+    Ok((code.to_string(), probability))
+}
+
+/// Word timings of one segment, from its text tokens
+fn segment_words(state: &WhisperState, segment: i32, eot: WhisperToken) -> Result<Vec<WordTiming>> {
+    let n_tokens = state
+        .full_n_tokens(segment)
+        .map_err(|e| anyhow!("Failed to get token count: {:?}", e))?;
+    let mut tokens = Vec::new();
+    for j in 0..n_tokens {
+        let data = state
+            .full_get_token_data(segment, j)
+            .map_err(|e| anyhow!("Failed to get token data: {:?}", e))?;
+        if data.id >= eot {
+            continue;
+        }
+        // Multi-byte characters can be split across tokens
+        let text = state
+            .full_get_token_text_lossy(segment, j)
+            .map_err(|e| anyhow!("Failed to get token text: {:?}", e))?;
+        tokens.push(TokenTiming {
+            text,
+            // Centiseconds, like the segment times
+            start_ms: data.t0 * 10,
+            end_ms: data.t1 * 10,
+            probability: data.p,
+        });
     }
+    Ok(words_from_tokens(tokens))
 }
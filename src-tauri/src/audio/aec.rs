@@ -0,0 +1,493 @@
+// Acoustic echo cancellation
+// Removes remote voices that leak from the speakers into the mic, using the system-audio
+// stream as the far-end reference.
+//
+// - The system producer pushes its 16 kHz samples into a shared `EchoReference`
+// - The mic producer runs `EchoCanceller::process` before chunking
+// - Bulk delay between the two streams (capture pipelines + speaker-to-mic path) is found by
+//   correlating 10 ms log-energy envelopes; a time-domain NLMS filter then models the room
+//   response around that delay
+// - A Geigel double-talk detector freezes adaptation while the local user is speaking
+//
+// Both producers count samples on the same wall-clock timeline, so reference index
+// `t - delay` lines up with mic sample `t`.
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+use super::vad::frame_energy_db;
+
+/// Envelope frame: 10 ms at 16 kHz
+const ENV_FRAME: usize = 160;
+/// NLMS filter length: 32 ms of room response around the bulk delay
+const FILTER_TAPS: usize = 512;
+/// NLMS step size
+const STEP_SIZE: f32 = 0.4;
+/// Largest bulk delay searched (envelope frames): 1 s
+const MAX_DELAY_FRAMES: usize = 100;
+/// Envelope window used for delay estimation (frames): 3 s
+const DELAY_WINDOW_FRAMES: usize = 300;
+/// Re-estimate delay every second of mic audio
+const DELAY_UPDATE_FRAMES: usize = 100;
+/// Reference samples kept for filtering: bulk delay + taps + polling slack
+const REFERENCE_KEEP_SAMPLES: usize = 16000 * 3;
+/// Mic audio held back waiting for the reference to catch up (system audio is polled
+/// separately and may lag slightly)
+const MAX_WAIT_SAMPLES: usize = 16000 / 2;
+/// Adaptation stays frozen this long after double-talk is detected
+const DOUBLE_TALK_HANGOVER: usize = 480;
+
+#[derive(Default)]
+struct ReferenceState {
+    /// Recent far-end samples; samples[0] is timeline index `start`
+    samples: VecDeque<f32>,
+    start: u64,
+    /// 10 ms log energies; envelope[0] is frame `env_start`
+    envelope: VecDeque<f32>,
+    env_start: u64,
+    /// Samples not yet forming a full envelope frame
+    partial: Vec<f32>,
+}
+
+/// Far-end (system audio) signal shared between the system and mic producers.
+#[derive(Default)]
+pub struct EchoReference {
+    state: Mutex<ReferenceState>,
+    active: AtomicBool,
+}
+
+impl EchoReference {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Mark the reference as live (system capture running) or gone.
+    pub fn set_active(&self, active: bool) {
+        self.active.store(active, Ordering::SeqCst);
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.active.load(Ordering::SeqCst)
+    }
+
+    /// Append far-end samples in timeline order.
+    pub fn push(&self, samples: &[f32]) {
+        let Ok(mut st) = self.state.lock() else {
+            return;
+        };
+        st.samples.extend(samples.iter().copied());
+        let excess = st.samples.len().saturating_sub(REFERENCE_KEEP_SAMPLES);
+        if excess > 0 {
+            st.samples.drain(..excess);
+            st.start += excess as u64;
+        }
+
+        st.partial.extend_from_slice(samples);
+        let frames = st.partial.len() / ENV_FRAME;
+        for i in 0..frames {
+            let e = frame_energy_db(&st.partial[i * ENV_FRAME..(i + 1) * ENV_FRAME]);
+            st.envelope.push_back(e);
+        }
+        st.partial.drain(..frames * ENV_FRAME);
+        let keep_frames = DELAY_WINDOW_FRAMES + MAX_DELAY_FRAMES + DELAY_UPDATE_FRAMES;
+        let excess = st.envelope.len().saturating_sub(keep_frames);
+        if excess > 0 {
+            st.envelope.drain(..excess);
+            st.env_start += excess as u64;
+        }
+    }
+
+    /// Timeline index one past the newest reference sample.
+    fn end(&self) -> u64 {
+        self.state
+            .lock()
+            .map(|st| st.start + st.samples.len() as u64)
+            .unwrap_or(0)
+    }
+
+    /// Copy samples for timeline range [from, to); missing samples are zero.
+    fn read(&self, from: u64, to: u64, out: &mut Vec<f32>) {
+        out.clear();
+        let Ok(st) = self.state.lock() else {
+            out.resize((to - from) as usize, 0.0);
+            return;
+        };
+        let end = st.start + st.samples.len() as u64;
+        for idx in from..to {
+            if idx >= st.start && idx < end {
+                out.push(st.samples[(idx - st.start) as usize]);
+            } else {
+                out.push(0.0);
+            }
+        }
+    }
+
+    /// Envelope frames [from, to), or None if not all available.
+    fn envelope(&self, from: u64, to: u64) -> Option<Vec<f32>> {
+        let st = self.state.lock().ok()?;
+        let end = st.env_start + st.envelope.len() as u64;
+        if from < st.env_start || to > end {
+            return None;
+        }
+        Some(
+            st.envelope
+                .range((from - st.env_start) as usize..(to - st.env_start) as usize)
+                .copied()
+                .collect(),
+        )
+    }
+}
+
+/// Pearson correlation of two equal-length series; None if either is flat.
+fn correlation(a: &[f32], b: &[f32]) -> Option<f32> {
+    let n = a.len() as f32;
+    let mean_a = a.iter().sum::<f32>() / n;
+    let mean_b = b.iter().sum::<f32>() / n;
+    let (mut cov, mut var_a, mut var_b) = (0.0f32, 0.0f32, 0.0f32);
+    for (x, y) in a.iter().zip(b) {
+        let (dx, dy) = (x - mean_a, y - mean_b);
+        cov += dx * dy;
+        var_a += dx * dx;
+        var_b += dy * dy;
+    }
+    // Require a few dB of variation in both, otherwise there's nothing to align
+    if var_a / n < 4.0 || var_b / n < 4.0 {
+        return None;
+    }
+    Some(cov / (var_a.sqrt() * var_b.sqrt()))
+}
+
+/// Mic-side echo canceller. Output has exactly as many samples as input (possibly delayed
+/// while waiting for the reference), so timeline positions are preserved.
+pub struct EchoCanceller {
+    /// Mic samples waiting for reference coverage
+    queue: VecDeque<f32>,
+    /// Timeline index of queue[0]
+    position: u64,
+    /// Bulk delay in samples (reference index = mic index - delay)
+    delay: u64,
+    weights: Vec<f32>,
+    /// Last FILTER_TAPS reference samples, newest last (kept in a 2x buffer to avoid shifting)
+    history: Vec<f32>,
+    history_energy: f32,
+    double_talk: usize,
+    /// Mic envelope for delay estimation; mic_env[0] is frame `mic_env_start`
+    mic_env: VecDeque<f32>,
+    mic_env_start: u64,
+    mic_partial: Vec<f32>,
+    frames_since_estimate: usize,
+    scratch: Vec<f32>,
+}
+
+impl Default for EchoCanceller {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EchoCanceller {
+    pub fn new() -> Self {
+        Self {
+            queue: VecDeque::new(),
+            position: 0,
+            delay: 0,
+            weights: vec![0.0; FILTER_TAPS],
+            history: vec![0.0; FILTER_TAPS],
+            history_energy: 0.0,
+            double_talk: 0,
+            mic_env: VecDeque::new(),
+            mic_env_start: 0,
+            mic_partial: Vec::new(),
+            frames_since_estimate: 0,
+            scratch: Vec::new(),
+        }
+    }
+
+    /// Current bulk delay estimate in milliseconds
+    pub fn delay_ms(&self) -> u64 {
+        self.delay / 16
+    }
+
+    /// Feed mic samples; returns cleaned samples that are ready.
+    pub fn process(&mut self, mic: &[f32], reference: &EchoReference) -> Vec<f32> {
+        self.track_envelope(mic, reference);
+        self.queue.extend(mic.iter().copied());
+
+        if !reference.is_active() {
+            // No far end: pass straight through
+            self.position += self.queue.len() as u64;
+            return self.queue.drain(..).collect();
+        }
+
+        // Process as far as the reference reaches, or further if it's lagging too much
+        let ref_end = reference.end();
+        let covered = (ref_end + self.delay).saturating_sub(self.position) as usize;
+        let ready = if self.queue.len() > MAX_WAIT_SAMPLES {
+            self.queue.len()
+        } else {
+            covered.min(self.queue.len())
+        };
+        self.cancel(ready, reference)
+    }
+
+    /// Process everything still queued (end of recording).
+    pub fn flush(&mut self, reference: &EchoReference) -> Vec<f32> {
+        let n = self.queue.len();
+        self.cancel(n, reference)
+    }
+
+    fn cancel(&mut self, n: usize, reference: &EchoReference) -> Vec<f32> {
+        if n == 0 {
+            return Vec::new();
+        }
+        let ref_from = self.position.saturating_sub(self.delay);
+        let ref_to = (self.position + n as u64).saturating_sub(self.delay);
+        let mut far = std::mem::take(&mut self.scratch);
+        reference.read(ref_from, ref_to, &mut far);
+        // Mic samples earlier than the delay have no reference: pad the front
+        let missing = n - far.len();
+        let far_at = |i: usize| if i < missing { 0.0 } else { far[i - missing] };
+
+        let mut out = Vec::with_capacity(n);
+        for (i, m) in self.queue.drain(..n).enumerate() {
+            let x = far_at(i);
+
+            // Slide the reference window
+            let oldest = self.history[self.history.len() - FILTER_TAPS];
+            self.history_energy = (self.history_energy + x * x - oldest * oldest).max(0.0);
+            if self.history.len() == FILTER_TAPS * 2 {
+                self.history.drain(..FILTER_TAPS);
+            }
+            self.history.push(x);
+            let window = &self.history[self.history.len() - FILTER_TAPS..];
+
+            let echo: f32 = window.iter().zip(&self.weights).map(|(x, w)| x * w).sum();
+            let e = m - echo;
+
+            // Geigel double-talk detector: mic much louder than anything the far end could produce
+            let far_peak = window.iter().fold(0.0f32, |acc, v| acc.max(v.abs()));
+            if m.abs() > 0.6 * far_peak {
+                self.double_talk = DOUBLE_TALK_HANGOVER;
+            } else if self.double_talk > 0 {
+                self.double_talk -= 1;
+            }
+
+            if self.double_talk == 0 && self.history_energy > 1e-6 {
+                let g = STEP_SIZE * e / (self.history_energy + 1e-3);
+                for (w, x) in self.weights.iter_mut().zip(window) {
+                    *w += g * x;
+                }
+            }
+
+            out.push(if e.is_finite() { e.clamp(-1.0, 1.0) } else { m });
+        }
+        self.position += n as u64;
+        self.scratch = far;
+        out
+    }
+
+    fn track_envelope(&mut self, mic: &[f32], reference: &EchoReference) {
+        self.mic_partial.extend_from_slice(mic);
+        let frames = self.mic_partial.len() / ENV_FRAME;
+        for i in 0..frames {
+            let e = frame_energy_db(&self.mic_partial[i * ENV_FRAME..(i + 1) * ENV_FRAME]);
+            self.mic_env.push_back(e);
+        }
+        self.mic_partial.drain(..frames * ENV_FRAME);
+        let excess = self.mic_env.len().saturating_sub(DELAY_WINDOW_FRAMES);
+        if excess > 0 {
+            self.mic_env.drain(..excess);
+            self.mic_env_start += excess as u64;
+        }
+
+        self.frames_since_estimate += frames;
+        if self.frames_since_estimate >= DELAY_UPDATE_FRAMES
+            && self.mic_env.len() == DELAY_WINDOW_FRAMES
+        {
+            self.frames_since_estimate = 0;
+            self.estimate_delay(reference);
+        }
+    }
+
+    fn estimate_delay(&mut self, reference: &EchoReference) {
+        let mic: Vec<f32> = self.mic_env.iter().copied().collect();
+        let from = self.mic_env_start;
+        let to = from + mic.len() as u64;
+
+        let mut best: Option<(usize, f32)> = None;
+        for lag in 0..=MAX_DELAY_FRAMES {
+            if from < lag as u64 {
+                break;
+            }
+            let Some(far) = reference.envelope(from - lag as u64, to - lag as u64) else {
+                continue;
+            };
+            if let Some(c) = correlation(&mic, &far) {
+                if best.is_none_or(|(_, b)| c > b) {
+                    best = Some((lag, c));
+                }
+            }
+        }
+
+        if let Some((lag, corr)) = best.filter(|&(_, c)| c > 0.5) {
+            // Start the filter one frame early so the true delay lands inside its window
+            let delay = (lag * ENV_FRAME).saturating_sub(ENV_FRAME) as u64;
+            if delay.abs_diff(self.delay) > ENV_FRAME as u64 {
+                log::info!(
+                    "Echo path delay {} ms (correlation {:.2})",
+                    delay / 16,
+                    corr
+                );
+                self.delay = delay;
+                self.weights.iter_mut().for_each(|w| *w = 0.0);
+            }
+        }
+    }
+}
+
+/// Lowercase alphanumeric words, for comparing transcripts of the same audio.
+fn normalized_words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric() && c != '\'')
+        .filter(|w| !w.is_empty())
+        .map(|w| w.to_lowercase())
+        .collect()
+}
+
+/// Whether two transcripts look like the same speech heard twice (remote audio transcribed
+/// from the system stream and again from the mic). Most words of the shorter text must appear
+/// in the longer one; very short texts must match exactly.
+pub fn is_echo_duplicate(a: &str, b: &str) -> bool {
+    let (a, b) = (normalized_words(a), normalized_words(b));
+    let (short, long) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+    if short.is_empty() {
+        return false;
+    }
+    if short.len() < 3 {
+        return short == long;
+    }
+    let mut remaining = long;
+    let mut shared = 0;
+    for word in &short {
+        if let Some(i) = remaining.iter().position(|w| w == word) {
+            remaining.swap_remove(i);
+            shared += 1;
+        }
+    }
+    shared as f32 / short.len() as f32 >= 0.8
+}
+
+/// Texts with fewer words are everyday replies ("yes", "okay, thanks") that both sides of a
+/// call say on their own, so matching text alone doesn't make them echo
+const SHORT_ECHO_WORDS: usize = 3;
+/// Allowed timing mismatch between two copies of longer speech
+const ECHO_SLACK_MS: i64 = 2_000;
+/// Short copies must start and end this close together: the echo path delay at most, plus
+/// some chunking jitter
+const SHORT_ECHO_SLACK_MS: i64 = (MAX_DELAY_FRAMES * ENV_FRAME / 16) as i64 + 250;
+
+/// Whether speech transcribed from one stream (start and end ms on a clock shared with the
+/// other stream) is the echo of, or the original of, speech transcribed from the other.
+/// Short texts count only when their timing matches closely too.
+pub fn is_echo_pair(a: (i64, i64), a_text: &str, b: (i64, i64), b_text: &str) -> bool {
+    if !is_echo_duplicate(a_text, b_text) {
+        return false;
+    }
+    let words = normalized_words(a_text)
+        .len()
+        .min(normalized_words(b_text).len());
+    if words < SHORT_ECHO_WORDS {
+        (a.0 - b.0).abs() <= SHORT_ECHO_SLACK_MS && (a.1 - b.1).abs() <= SHORT_ECHO_SLACK_MS
+    } else {
+        a.0 <= b.1 + ECHO_SLACK_MS && b.0 <= a.1 + ECHO_SLACK_MS
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Speech-like far end: noise with a slow on/off envelope
+    fn far_end(n: usize) -> Vec<f32> {
+        let mut x: u32 = 1;
+        (0..n)
+            .map(|i| {
+                x = x.wrapping_mul(1664525).wrapping_add(1013904223);
+                let noise = (x >> 8) as f32 / (1u32 << 24) as f32 - 0.5;
+                let gate = if (i / 4000) % 3 == 2 { 0.05 } else { 1.0 };
+                0.4 * noise * gate
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_cancels_delayed_echo() {
+        let delay = 2400; // 150 ms
+        let far = far_end(16000 * 12);
+        let mic: Vec<f32> = (0..far.len())
+            .map(|i| {
+                let tap = |lag: usize| i.checked_sub(lag).map_or(0.0, |j| far[j]);
+                0.3 * tap(delay) + 0.1 * tap(delay + 40)
+            })
+            .collect();
+
+        let reference = EchoReference::new();
+        reference.set_active(true);
+        let mut aec = EchoCanceller::new();
+        let mut out = Vec::new();
+        for (f, m) in far.chunks(1600).zip(mic.chunks(1600)) {
+            reference.push(f);
+            out.extend(aec.process(m, &reference));
+        }
+        out.extend(aec.flush(&reference));
+        assert_eq!(out.len(), mic.len());
+
+        let tail = mic.len() - 16000 * 2;
+        let energy = |s: &[f32]| s.iter().map(|v| v * v).sum::<f32>();
+        let erle = 10.0 * (energy(&mic[tail..]) / energy(&out[tail..])).log10();
+        assert!(
+            erle > 10.0,
+            "ERLE {:.1} dB, delay {} ms",
+            erle,
+            aec.delay_ms()
+        );
+    }
+
+    #[test]
+    fn test_echo_duplicate_text() {
+        assert!(is_echo_duplicate(
+            "Can everyone see my screen?",
+            "can everyone see my screen"
+        ));
+        assert!(is_echo_duplicate(
+            "so the launch moves to next Tuesday",
+            "The launch moves to next Tuesday."
+        ));
+        assert!(!is_echo_duplicate(
+            "so the launch moves to next Tuesday",
+            "that works for me, Tuesday is fine"
+        ));
+        assert!(!is_echo_duplicate("yes", "yes we can"));
+    }
+
+    #[test]
+    fn test_echo_pair_timing() {
+        let (system, mic) = ((10_000, 10_600), (10_300, 10_900));
+        // A short reply heard back through the speakers
+        assert!(is_echo_pair(system, "Okay.", mic, "okay"));
+        // The local user saying "okay" seconds later is not an echo
+        assert!(!is_echo_pair(system, "Okay.", (13_000, 13_500), "okay"));
+        // Longer speech only has to overlap, give or take the slack
+        let text = "so the launch moves to next Tuesday";
+        assert!(is_echo_pair((0, 3000), text, (4500, 7000), text));
+        assert!(!is_echo_pair((0, 3000), text, (6000, 9000), text));
+    }
+
+    #[test]
+    fn test_passthrough_without_reference() {
+        let reference = EchoReference::new();
+        let mut aec = EchoCanceller::new();
+        let mic = far_end(1000);
+        assert_eq!(aec.process(&mic, &reference), mic);
+    }
+}
@@ -0,0 +1,278 @@
+// Audio file decoding
+// Streams mono samples out of existing recordings for import.
+//
+// - Plain PCM / float WAV is read with hound
+// - Everything else (MP3, AAC/M4A, ALAC, OGG Vorbis, FLAC, ADPCM WAV, and the audio track of
+//   MP4/MOV/MKV/WebM) goes through symphonia
+//
+// Samples come out at the file's own rate in blocks of about a second, so long recordings
+// never have to be decoded into memory all at once.
+
+use anyhow::{anyhow, Result};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::{Decoder, DecoderOptions, CODEC_TYPE_NULL};
+use symphonia::core::errors::Error as SymphoniaError;
+use symphonia::core::formats::{FormatOptions, FormatReader};
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+
+/// File extensions offered in the import dialog
+pub const SUPPORTED_EXTENSIONS: &[&str] = &[
+    "wav", "mp3", "m4a", "aac", "mp4", "m4v", "mov", "mkv", "webm", "ogg", "oga", "flac", "caf",
+];
+
+enum Source {
+    Wav {
+        reader: hound::WavReader<BufReader<File>>,
+        channels: usize,
+        /// Int samples are scaled by this to land in [-1, 1]; None = float samples
+        int_scale: Option<f32>,
+    },
+    Media {
+        format: Box<dyn FormatReader>,
+        decoder: Box<dyn Decoder>,
+        track_id: u32,
+        buffer: Option<SampleBuffer<f32>>,
+    },
+}
+
+/// Streaming decoder producing mono f32 samples.
+pub struct AudioFileReader {
+    source: Source,
+    sample_rate: u32,
+    total_frames: Option<u64>,
+}
+
+fn mix_to_mono(interleaved: &[f32], channels: usize, out: &mut Vec<f32>) {
+    let channels = channels.max(1);
+    out.extend(
+        interleaved
+            .chunks_exact(channels)
+            .map(|frame| frame.iter().sum::<f32>() / channels as f32),
+    );
+}
+
+impl AudioFileReader {
+    pub fn open(path: &Path) -> Result<Self> {
+        let is_wav = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| e.eq_ignore_ascii_case("wav"));
+        if is_wav {
+            // hound rejects compressed WAV (ADPCM etc.); symphonia handles those
+            if let Ok(reader) = Self::open_wav(path) {
+                return Ok(reader);
+            }
+        }
+        Self::open_media(path)
+    }
+
+    fn open_wav(path: &Path) -> Result<Self> {
+        let reader = hound::WavReader::open(path)?;
+        let spec = reader.spec();
+        let int_scale = match spec.sample_format {
+            hound::SampleFormat::Int => Some(1.0 / (1u64 << (spec.bits_per_sample - 1)) as f32),
+            hound::SampleFormat::Float => None,
+        };
+        Ok(Self {
+            sample_rate: spec.sample_rate,
+            total_frames: Some(reader.duration() as u64),
+            source: Source::Wav {
+                channels: spec.channels as usize,
+                int_scale,
+                reader,
+            },
+        })
+    }
+
+    fn open_media(path: &Path) -> Result<Self> {
+        let file = File::open(path)?;
+        let stream = MediaSourceStream::new(Box::new(file), Default::default());
+        let mut hint = Hint::new();
+        if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
+            hint.with_extension(ext);
+        }
+        let probed = symphonia::default::get_probe()
+            .format(
+                &hint,
+                stream,
+                &FormatOptions::default(),
+                &MetadataOptions::default(),
+            )
+            .map_err(|e| anyhow!("Unsupported or unreadable media file: {}", e))?;
+        let format = probed.format;
+
+        // Video containers list several tracks; take the first one we can decode as audio
+        let track = format
+            .tracks()
+            .iter()
+            .find(|t| {
+                t.codec_params.codec != CODEC_TYPE_NULL && t.codec_params.sample_rate.is_some()
+            })
+            .ok_or_else(|| anyhow!("No audio track found"))?;
+        let decoder = symphonia::default::get_codecs()
+            .make(&track.codec_params, &DecoderOptions::default())
+            .map_err(|e| anyhow!("Unsupported audio codec: {}", e))?;
+
+        Ok(Self {
+            sample_rate: track.codec_params.sample_rate.unwrap_or(16000),
+            total_frames: track.codec_params.n_frames,
+            source: Source::Media {
+                track_id: track.id,
+                format,
+                decoder,
+                buffer: None,
+            },
+        })
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// Duration from the container header, when it records one
+    pub fn duration_ms(&self) -> Option<i64> {
+        self.total_frames
+            .map(|frames| (frames * 1000 / self.sample_rate.max(1) as u64) as i64)
+    }
+
+    /// Next block of mono samples (about one second), or None at the end of the file.
+    pub fn read_block(&mut self) -> Result<Option<Vec<f32>>> {
+        let block_frames = self.sample_rate as usize;
+        let mut out = Vec::with_capacity(block_frames);
+
+        match &mut self.source {
+            Source::Wav {
+                reader,
+                channels,
+                int_scale,
+            } => {
+                let wanted = block_frames * *channels;
+                let interleaved = match int_scale {
+                    Some(scale) => reader
+                        .samples::<i32>()
+                        .take(wanted)
+                        .map(|s| s.map(|v| v as f32 * *scale))
+                        .collect::<Result<Vec<f32>, _>>()?,
+                    None => reader
+                        .samples::<f32>()
+                        .take(wanted)
+                        .collect::<Result<Vec<f32>, _>>()?,
+                };
+                mix_to_mono(&interleaved, *channels, &mut out);
+            }
+            Source::Media {
+                format,
+                decoder,
+                track_id,
+                buffer,
+            } => {
+                while out.len() < block_frames {
+                    let packet = match format.next_packet() {
+                        Ok(packet) => packet,
+                        Err(SymphoniaError::IoError(e))
+                            if e.kind() == std::io::ErrorKind::UnexpectedEof =>
+                        {
+                            break;
+                        }
+                        // Chained streams (e.g. OGG with a new logical stream) end the track
+                        Err(SymphoniaError::ResetRequired) => break,
+                        Err(e) => return Err(anyhow!("Failed to read media packet: {}", e)),
+                    };
+                    if packet.track_id() != *track_id {
+                        continue;
+                    }
+                    let decoded = match decoder.decode(&packet) {
+                        Ok(decoded) => decoded,
+                        Err(SymphoniaError::DecodeError(e)) => {
+                            // A corrupt frame shouldn't sink the whole import
+                            log::warn!("Skipping undecodable audio packet: {}", e);
+                            continue;
+                        }
+                        Err(e) => return Err(anyhow!("Audio decode failed: {}", e)),
+                    };
+                    let spec = *decoded.spec();
+                    let capacity = decoded.capacity() as u64;
+                    let buf = match buffer {
+                        Some(buf)
+                            if buf.capacity() as u64 >= capacity * spec.channels.count() as u64 =>
+                        {
+                            buf
+                        }
+                        _ => buffer.insert(SampleBuffer::new(capacity, spec)),
+                    };
+                    buf.copy_interleaved_ref(decoded);
+                    mix_to_mono(buf.samples(), spec.channels.count(), &mut out);
+                }
+            }
+        }
+
+        Ok(if out.is_empty() { None } else { Some(out) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::AudioRecorder;
+
+    #[test]
+    fn test_reads_pcm_wav_as_mono() {
+        let path = std::env::temp_dir().join(format!("phantom-file-{}.wav", std::process::id()));
+        let spec = hound::WavSpec {
+            channels: 2,
+            sample_rate: 8000,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+        for _ in 0..12000 {
+            writer.write_sample(16384i16).unwrap();
+            writer.write_sample(0i16).unwrap();
+        }
+        writer.finalize().unwrap();
+
+        let mut reader = AudioFileReader::open(&path).unwrap();
+        assert_eq!(reader.sample_rate(), 8000);
+        assert_eq!(reader.duration_ms(), Some(1500));
+        let mut samples = Vec::new();
+        while let Some(block) = reader.read_block().unwrap() {
+            samples.extend(block);
+        }
+        std::fs::remove_file(&path).ok();
+        assert_eq!(samples.len(), 12000);
+        assert!((samples[100] - 0.25).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_reads_adpcm_recording() {
+        let path =
+            std::env::temp_dir().join(format!("phantom-file-{}-adpcm.wav", std::process::id()));
+        let tone: Vec<f32> = (0..16000)
+            .map(|i| 0.3 * (2.0 * std::f32::consts::PI * 440.0 * i as f32 / 16000.0).sin())
+            .collect();
+        let mut recorder = AudioRecorder::create(&path).unwrap();
+        recorder.write(&tone).unwrap();
+        recorder.finish().unwrap();
+
+        let mut reader = AudioFileReader::open(&path).unwrap();
+        assert_eq!(reader.sample_rate(), 16000);
+        let mut samples = Vec::new();
+        while let Some(block) = reader.read_block().unwrap() {
+            samples.extend(block);
+        }
+        std::fs::remove_file(&path).ok();
+        assert!(samples.len() >= tone.len());
+        let err: f32 = tone
+            .iter()
+            .zip(&samples)
+            .map(|(a, b)| (a - b).abs())
+            .sum::<f32>()
+            / tone.len() as f32;
+        assert!(err < 0.02, "{}", err);
+    }
+}
@@ -0,0 +1,232 @@
+// Input level metering and health checks
+// Runs on each input's samples as the device delivers them (before resampling, echo
+// cancellation and gain), so a muted mic reads as digital silence and an overdriven one as
+// clipping, whatever the pre-processing would make of it.
+//
+// - Readings (RMS, peak, clipped samples) come out once per reporting interval of audio
+// - Warnings start when silence or clipping has lasted the configured time, and clear once
+//   the input recovers
+
+use serde::Serialize;
+
+/// Peak below this (-80 dBFS) counts as digital silence
+const SILENCE_PEAK: f32 = 1e-4;
+/// Samples at or above this count as clipped
+const CLIP_LEVEL: f32 = 0.99;
+/// Clipping runs are measured in windows: the run grows while every window has clipped samples
+const CLIP_WINDOW_MS: u64 = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MeterWarning {
+    Silent,
+    Clipping,
+}
+
+/// Levels over one reporting interval
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MeterReading {
+    /// Linear RMS (0.0 to 1.0)
+    pub rms: f32,
+    pub peak: f32,
+    pub clipped_samples: u64,
+    /// Length of the current run of digital silence
+    pub silent_ms: u64,
+    /// Length of the current run of clipping
+    pub clipping_ms: u64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MeterEvent {
+    Reading(MeterReading),
+    /// A warning started (`active`) or cleared, after `duration_ms` of the condition
+    Warning {
+        kind: MeterWarning,
+        active: bool,
+        duration_ms: u64,
+    },
+}
+
+pub struct LevelMeter {
+    sample_rate: u32,
+    interval_ms: u64,
+    /// Silence or clipping this long raises a warning (0 = never)
+    warn_after_ms: u64,
+    // Current interval
+    sum_sq: f64,
+    peak: f32,
+    clipped: u64,
+    count: u64,
+    // Health
+    silent_samples: u64,
+    clip_window_samples: u64,
+    clip_in_window: bool,
+    clipping_ms: u64,
+    silent_warned: bool,
+    clipping_warned: bool,
+}
+
+impl LevelMeter {
+    pub fn new(sample_rate: u32, interval_ms: u64, warn_after_ms: u64) -> Self {
+        Self {
+            sample_rate: sample_rate.max(1),
+            interval_ms: interval_ms.max(1),
+            warn_after_ms,
+            sum_sq: 0.0,
+            peak: 0.0,
+            clipped: 0,
+            count: 0,
+            silent_samples: 0,
+            clip_window_samples: 0,
+            clip_in_window: false,
+            clipping_ms: 0,
+            silent_warned: false,
+            clipping_warned: false,
+        }
+    }
+
+    /// Follow a device switch. Samples counted so far keep their duration approximately.
+    pub fn set_sample_rate(&mut self, sample_rate: u32) {
+        self.sample_rate = sample_rate.max(1);
+    }
+
+    fn ms(&self, samples: u64) -> u64 {
+        samples * 1000 / self.sample_rate as u64
+    }
+
+    pub fn push(&mut self, samples: &[f32]) -> Vec<MeterEvent> {
+        let mut events = Vec::new();
+        let interval_samples = (self.interval_ms * self.sample_rate as u64 / 1000).max(1);
+        let window_samples = (CLIP_WINDOW_MS * self.sample_rate as u64 / 1000).max(1);
+
+        for &s in samples {
+            let abs = s.abs();
+            self.sum_sq += (s * s) as f64;
+            self.peak = self.peak.max(abs);
+            self.count += 1;
+
+            if abs < SILENCE_PEAK {
+                self.silent_samples += 1;
+            } else {
+                self.silent_samples = 0;
+            }
+
+            if abs >= CLIP_LEVEL {
+                self.clipped += 1;
+                self.clip_in_window = true;
+            }
+            self.clip_window_samples += 1;
+            if self.clip_window_samples >= window_samples {
+                self.clipping_ms = if self.clip_in_window {
+                    self.clipping_ms + CLIP_WINDOW_MS
+                } else {
+                    0
+                };
+                self.clip_window_samples = 0;
+                self.clip_in_window = false;
+            }
+
+            if self.count >= interval_samples {
+                self.finish_interval(&mut events);
+            }
+        }
+        events
+    }
+
+    fn finish_interval(&mut self, events: &mut Vec<MeterEvent>) {
+        let reading = MeterReading {
+            rms: (self.sum_sq / self.count as f64).sqrt() as f32,
+            peak: self.peak,
+            clipped_samples: self.clipped,
+            silent_ms: self.ms(self.silent_samples),
+            clipping_ms: self.clipping_ms,
+        };
+        self.sum_sq = 0.0;
+        self.peak = 0.0;
+        self.clipped = 0;
+        self.count = 0;
+
+        if self.warn_after_ms > 0 {
+            let checks = [
+                (MeterWarning::Silent, reading.silent_ms),
+                (MeterWarning::Clipping, reading.clipping_ms),
+            ];
+            for (kind, duration_ms) in checks {
+                let warned = match kind {
+                    MeterWarning::Silent => &mut self.silent_warned,
+                    MeterWarning::Clipping => &mut self.clipping_warned,
+                };
+                let active = duration_ms >= self.warn_after_ms;
+                if active != *warned {
+                    *warned = active;
+                    events.push(MeterEvent::Warning {
+                        kind,
+                        active,
+                        duration_ms,
+                    });
+                }
+            }
+        }
+        events.push(MeterEvent::Reading(reading));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tone(n: usize, amp: f32) -> Vec<f32> {
+        (0..n)
+            .map(|i| amp * (2.0 * std::f32::consts::PI * 300.0 * i as f32 / 16000.0).sin())
+            .collect()
+    }
+
+    fn warnings(events: &[MeterEvent]) -> Vec<(MeterWarning, bool)> {
+        events
+            .iter()
+            .filter_map(|e| match e {
+                MeterEvent::Warning { kind, active, .. } => Some((*kind, *active)),
+                MeterEvent::Reading(_) => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_readings_per_interval() {
+        let mut meter = LevelMeter::new(16000, 250, 0);
+        let events = meter.push(&tone(16000, 0.5));
+        assert_eq!(events.len(), 4);
+        let MeterEvent::Reading(reading) = &events[0] else {
+            panic!("expected a reading");
+        };
+        assert!((reading.rms - 0.5 / 2f32.sqrt()).abs() < 0.01);
+        assert!((reading.peak - 0.5).abs() < 0.01);
+        assert_eq!(reading.clipped_samples, 0);
+        assert_eq!(reading.silent_ms, 0);
+    }
+
+    #[test]
+    fn test_silence_warning_starts_and_clears() {
+        let mut meter = LevelMeter::new(16000, 250, 3000);
+        let events = meter.push(&vec![0.0; 16000 * 2]);
+        assert!(warnings(&events).is_empty());
+        let events = meter.push(&vec![0.0; 16000 * 2]);
+        assert_eq!(warnings(&events), vec![(MeterWarning::Silent, true)]);
+        let events = meter.push(&tone(4000, 0.1));
+        assert_eq!(warnings(&events), vec![(MeterWarning::Silent, false)]);
+    }
+
+    #[test]
+    fn test_sustained_clipping_warns() {
+        let mut meter = LevelMeter::new(16000, 250, 2000);
+        // Overdriven speech: every window has flat-topped peaks
+        let clipped: Vec<f32> = tone(16000 * 3, 2.0)
+            .into_iter()
+            .map(|s| s.clamp(-1.0, 1.0))
+            .collect();
+        let events = meter.push(&clipped);
+        assert_eq!(warnings(&events), vec![(MeterWarning::Clipping, true)]);
+        let events = meter.push(&tone(16000 * 2, 0.2));
+        assert_eq!(warnings(&events), vec![(MeterWarning::Clipping, false)]);
+    }
+}
@@ -1,31 +1,318 @@
 // Audio capture module
-// Handles audio input capture for macOS and Windows
-
+// Handles audio input capture for macOS, Windows and Linux
+
+pub mod aec;
+pub mod dsp;
+pub mod file;
+pub mod meter;
+pub mod recorder;
+pub mod source;
 pub mod system;
-pub use system::SystemAudioCapture;
+pub mod vad;
+pub use aec::{EchoCanceller, EchoReference};
+pub use dsp::{AudioProcessor, DspConfig};
+pub use file::AudioFileReader;
+pub use meter::{LevelMeter, MeterEvent, MeterWarning};
+pub use recorder::AudioRecorder;
+pub use source::{CaptureSource, FileSource, ToneSource};
+pub use system::{MonitorSource, SystemAudioCapture};
 
 use anyhow::{anyhow, Result};
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
-use cpal::{Device, Host, SampleFormat, Stream, StreamConfig};
-use std::sync::atomic::{AtomicBool, Ordering};
+use cpal::{
+    Device, FromSample, Host, SampleFormat, SizedSample, Stream, StreamConfig,
+    SupportedStreamConfig, SupportedStreamConfigRange,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
 
 const BUFFER_SECONDS: usize = 30;
+/// Window for the RMS level meter
+const RMS_WINDOW: usize = 1024;
+
+/// Counters shared between the audio callback and readers. Atomics only, so the
+/// real-time thread never waits on a lock.
+#[derive(Default)]
+struct CaptureCounters {
+    captured_samples: AtomicU64,
+    dropped_samples: AtomicU64,
+    overruns: AtomicU64,
+    /// f32 bits of the RMS over the last RMS_WINDOW samples
+    rms_bits: AtomicU32,
+    /// f32 bits of the level after pre-processing, published by the reader
+    processed_rms_bits: AtomicU32,
+    has_processed_level: AtomicBool,
+    /// Set by the stream error callback (device unplugged, backend failure)
+    stream_error: AtomicBool,
+    /// Wall-clock time (ms since the Unix epoch) samples last arrived; 0 = none yet
+    last_sample_ms: AtomicU64,
+}
+
+/// Snapshot of capture health for diagnostics
+#[derive(Debug, Clone, Serialize)]
+pub struct AudioCaptureStats {
+    pub sample_rate: u32,
+    /// Mono samples delivered by the device since start
+    pub captured_samples: u64,
+    /// Mono samples discarded because the ring buffer was full
+    pub dropped_samples: u64,
+    /// Callbacks that hit a full ring buffer
+    pub overruns: u64,
+    /// Samples currently waiting to be read
+    pub buffered_samples: usize,
+    pub capacity_samples: usize,
+}
+
+/// One mono signal taken from the stream: a single channel, or all channels mixed down.
+/// Each tap has its own ring buffer so separate inputs on one device stay separate.
+struct CaptureTap {
+    /// 0-based channel index; None = mix all channels
+    channel: Option<usize>,
+    producer: rtrb::Producer<f32>,
+}
+
+/// Mono signal of one tap from interleaved frames. Channels the stream doesn't have read as silence.
+fn tap_signal<'a, T: Copy>(
+    data: &'a [T],
+    channels: usize,
+    channel: Option<usize>,
+    to_f32: &'a impl Fn(T) -> f32,
+) -> impl Iterator<Item = f32> + Clone + 'a {
+    data.chunks_exact(channels).map(move |frame| match channel {
+        Some(ch) => frame.get(ch).map_or(0.0, |&s| to_f32(s)),
+        None => frame.iter().map(|&s| to_f32(s)).sum::<f32>() / channels as f32,
+    })
+}
+
+/// Callback side of the capture pipeline: owns the ring buffer producers and the RMS window.
+struct CaptureSink {
+    taps: Vec<CaptureTap>,
+    counters: Arc<CaptureCounters>,
+    rms_window: [f32; RMS_WINDOW],
+    rms_pos: usize,
+    rms_sum: f32,
+}
+
+impl CaptureSink {
+    fn new(taps: Vec<CaptureTap>, counters: Arc<CaptureCounters>) -> Self {
+        Self {
+            taps,
+            counters,
+            rms_window: [0.0; RMS_WINDOW],
+            rms_pos: 0,
+            rms_sum: 0.0,
+        }
+    }
+
+    /// Split interleaved frames into the taps' mono signals and push them without blocking.
+    /// Whatever doesn't fit is dropped and counted as an overrun.
+    fn push_interleaved<T: Copy>(
+        &mut self,
+        data: &[T],
+        channels: usize,
+        to_f32: impl Fn(T) -> f32,
+    ) {
+        let channels = channels.max(1);
+        let frames = data.len() / channels;
+
+        // Running sum over a fixed window keeps the level meter O(1) per sample.
+        // The meter follows the first tap (the primary input).
+        let metered = self.taps.first().and_then(|t| t.channel);
+        for s in tap_signal(data, channels, metered, &to_f32) {
+            let sq = s * s;
+            self.rms_sum += sq - self.rms_window[self.rms_pos];
+            self.rms_window[self.rms_pos] = sq;
+            self.rms_pos = (self.rms_pos + 1) % RMS_WINDOW;
+            if self.rms_pos == 0 {
+                // Re-sum once per window to cancel floating point drift
+                self.rms_sum = self.rms_window.iter().sum();
+            }
+        }
+        let rms = (self.rms_sum.max(0.0) / RMS_WINDOW as f32).sqrt();
+        self.counters
+            .rms_bits
+            .store(rms.to_bits(), Ordering::Relaxed);
+
+        let mut dropped = 0;
+        for tap in self.taps.iter_mut() {
+            let writable = frames.min(tap.producer.slots());
+            if writable > 0 {
+                if let Ok(chunk) = tap.producer.write_chunk_uninit(writable) {
+                    chunk.fill_from_iter(tap_signal(data, channels, tap.channel, &to_f32));
+                }
+            }
+            dropped += frames - writable;
+        }
+        self.counters
+            .captured_samples
+            .fetch_add(frames as u64, Ordering::Relaxed);
+        if frames > 0 {
+            let now_ms = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map_or(0, |d| d.as_millis() as u64);
+            self.counters
+                .last_sample_ms
+                .store(now_ms, Ordering::Relaxed);
+        }
+        if dropped > 0 {
+            self.counters
+                .dropped_samples
+                .fetch_add(dropped as u64, Ordering::Relaxed);
+            self.counters.overruns.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
 
 pub struct AudioCapture {
     host: Host,
     device: Option<Device>,
+    /// Explicit input format; None = prefer 16 kHz mono, else the device default
+    requested_format: Option<AudioInputFormat>,
+    /// Format of the running stream
+    stream_format: Option<AudioInputFormat>,
     stream: Option<Stream>,
-    samples: Arc<Mutex<Vec<f32>>>,
+    /// Channel taken by each tap (None = mixdown). Defaults to a single mixed-down tap.
+    taps: Vec<Option<u16>>,
+    /// Reader side of each tap's ring buffer. Only readers take this lock; the callback owns the producers.
+    consumers: Mutex<Vec<rtrb::Consumer<f32>>>,
+    capacity: usize,
+    counters: Arc<CaptureCounters>,
     is_recording: Arc<AtomicBool>,
     sample_rate: u32,
-    recent_samples: Arc<Mutex<Vec<f32>>>, // For RMS calculation
 }
 
 #[derive(Debug, Clone)]
 pub struct AudioDevice {
     pub name: String,
     pub is_default: bool,
+    /// Every configuration range the device supports
+    pub configs: Vec<AudioConfigRange>,
+    pub default_format: Option<AudioInputFormat>,
+}
+
+/// A supported input configuration range as reported by the device
+#[derive(Debug, Clone, Serialize)]
+pub struct AudioConfigRange {
+    pub channels: u16,
+    pub min_sample_rate: u32,
+    pub max_sample_rate: u32,
+    /// cpal sample format name: "i8", "i16", "i32", "i64", "u8", ..., "f32", "f64"
+    pub sample_format: String,
+}
+
+/// A concrete input format to open a device with
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AudioInputFormat {
+    pub sample_rate: u32,
+    pub channels: u16,
+    pub sample_format: String,
+}
+
+impl From<&SupportedStreamConfig> for AudioInputFormat {
+    fn from(config: &SupportedStreamConfig) -> Self {
+        Self {
+            sample_rate: config.sample_rate().0,
+            channels: config.channels(),
+            sample_format: config.sample_format().to_string(),
+        }
+    }
+}
+
+impl From<&SupportedStreamConfigRange> for AudioConfigRange {
+    fn from(range: &SupportedStreamConfigRange) -> Self {
+        Self {
+            channels: range.channels(),
+            min_sample_rate: range.min_sample_rate().0,
+            max_sample_rate: range.max_sample_rate().0,
+            sample_format: range.sample_format().to_string(),
+        }
+    }
+}
+
+/// Lower is better: native float first, then integer formats by how common they are
+fn format_rank(format: SampleFormat) -> u8 {
+    match format {
+        SampleFormat::F32 => 0,
+        SampleFormat::I16 => 1,
+        SampleFormat::I32 => 2,
+        SampleFormat::F64 => 3,
+        _ => 4,
+    }
+}
+
+/// Pick a stream config from a device's supported ranges with at least `min_channels` channels.
+/// An explicit request wins if the device supports it; otherwise prefer a 16 kHz config
+/// (no resampling needed) with the fewest channels and the cheapest sample format, then any
+/// config with enough channels at its highest rate.
+/// Returns None when no preference applies, meaning the device default should be used.
+fn choose_config(
+    ranges: &[SupportedStreamConfigRange],
+    requested: Option<&AudioInputFormat>,
+    min_channels: u16,
+) -> Option<SupportedStreamConfig> {
+    let ranges: Vec<SupportedStreamConfigRange> = ranges
+        .iter()
+        .filter(|r| r.channels() >= min_channels)
+        .cloned()
+        .collect();
+
+    if let Some(req) = requested {
+        let exact = ranges.iter().find_map(|r| {
+            if r.channels() == req.channels && r.sample_format().to_string() == req.sample_format {
+                r.try_with_sample_rate(cpal::SampleRate(req.sample_rate))
+            } else {
+                None
+            }
+        });
+        if exact.is_some() {
+            return exact;
+        }
+        log::warn!(
+            "Requested input format {:?} not supported by device, negotiating instead",
+            req
+        );
+    }
+
+    let at_16k = ranges
+        .iter()
+        .filter(|r| r.min_sample_rate().0 <= 16000 && 16000 <= r.max_sample_rate().0)
+        .min_by_key(|r| (r.channels(), format_rank(r.sample_format())))
+        .and_then(|r| r.try_with_sample_rate(cpal::SampleRate(16000)));
+    if at_16k.is_some() || min_channels <= 1 {
+        return at_16k;
+    }
+
+    // The default config may not have enough channels for the requested taps
+    ranges
+        .iter()
+        .min_by_key(|r| (r.channels(), format_rank(r.sample_format())))
+        .map(|r| r.with_max_sample_rate())
+}
+
+/// Build an input stream for any sample type cpal can deliver, converting to f32 in the callback.
+fn build_stream<T>(
+    device: &Device,
+    config: &StreamConfig,
+    mut sink: CaptureSink,
+) -> std::result::Result<Stream, cpal::BuildStreamError>
+where
+    T: SizedSample,
+    f32: FromSample<T>,
+{
+    let channels = config.channels as usize;
+    let counters = sink.counters.clone();
+    device.build_input_stream(
+        config,
+        move |data: &[T], _: &cpal::InputCallbackInfo| {
+            sink.push_interleaved(data, channels, |s| s.to_sample::<f32>());
+        },
+        move |err| {
+            log::error!("Audio stream error: {}", err);
+            counters.stream_error.store(true, Ordering::Relaxed);
+        },
+        None,
+    )
 }
 
 // Make AudioCapture Send by storing stream separately
@@ -38,11 +325,15 @@ impl AudioCapture {
         Ok(Self {
             host,
             device: None,
+            requested_format: None,
+            stream_format: None,
             stream: None,
-            samples: Arc::new(Mutex::new(Vec::new())),
+            taps: vec![None],
+            consumers: Mutex::new(Vec::new()),
+            capacity: 0,
+            counters: Arc::new(CaptureCounters::default()),
             is_recording: Arc::new(AtomicBool::new(false)),
             sample_rate: 16000,
-            recent_samples: Arc::new(Mutex::new(Vec::new())),
         })
     }
 
@@ -57,7 +348,20 @@ impl AudioCapture {
             .filter_map(|device| {
                 device.name().ok().map(|name| {
                     let is_default = default_name.as_ref() == Some(&name);
-                    AudioDevice { name, is_default }
+                    let configs = device
+                        .supported_input_configs()
+                        .map(|ranges| ranges.map(|r| AudioConfigRange::from(&r)).collect())
+                        .unwrap_or_default();
+                    let default_format = device
+                        .default_input_config()
+                        .ok()
+                        .map(|c| AudioInputFormat::from(&c));
+                    AudioDevice {
+                        name,
+                        is_default,
+                        configs,
+                        default_format,
+                    }
                 })
             })
             .collect();
@@ -86,8 +390,32 @@ impl AudioCapture {
         Ok(())
     }
 
+    /// Request a specific input format (None = automatic negotiation). Applies on the next start.
+    pub fn select_format(&mut self, format: Option<AudioInputFormat>) {
+        self.requested_format = format;
+    }
+
+    /// Split the stream into separate mono signals, one per entry: a 0-based channel index,
+    /// or None to mix all channels. Applies on the next start.
+    pub fn select_channels(&mut self, taps: Vec<Option<u16>>) {
+        self.taps = if taps.is_empty() { vec![None] } else { taps };
+    }
+
+    /// Format of the running stream, if started
+    pub fn stream_format(&self) -> Option<&AudioInputFormat> {
+        self.stream_format.as_ref()
+    }
+
     /// Start capturing audio
     pub fn start(&mut self) -> Result<()> {
+        self.start_stream(false)
+    }
+
+    /// Start the stream. With `remap`, taps of channels the device doesn't have are kept
+    /// running instead of failing: the first falls back to all channels mixed, the others
+    /// stay silent. Used when failing over to another device, so a channel layout made for
+    /// the preferred device doesn't keep the fallback from starting.
+    fn start_stream(&mut self, remap: bool) -> Result<()> {
         if self.is_recording.load(Ordering::SeqCst) {
             return Ok(());
         }
@@ -100,24 +428,74 @@ impl AudioCapture {
             .device
             .as_ref()
             .ok_or_else(|| anyhow!("No device selected"))?;
-        let supported_config = device.default_input_config()?;
+        let ranges: Vec<SupportedStreamConfigRange> = device
+            .supported_input_configs()
+            .map(|r| r.collect())
+            .unwrap_or_default();
+        let min_channels = self
+            .taps
+            .iter()
+            .map(|t| t.map_or(1, |ch| ch + 1))
+            .max()
+            .unwrap_or(1);
+        let supported_config =
+            match choose_config(&ranges, self.requested_format.as_ref(), min_channels) {
+                Some(config) => config,
+                None => device.default_input_config()?,
+            };
+        let channels = supported_config.channels();
+        let mut stream_taps = self.taps.clone();
+        if channels < min_channels {
+            if !remap {
+                return Err(anyhow!(
+                    "Device has {} channels, channel {} requested",
+                    channels,
+                    min_channels
+                ));
+            }
+            for (i, tap) in stream_taps.iter_mut().enumerate() {
+                match *tap {
+                    Some(ch) if ch >= channels && i == 0 => {
+                        log::warn!(
+                            "Device has {} channels, mixing them for channel {}",
+                            channels,
+                            ch + 1
+                        );
+                        *tap = None;
+                    }
+                    Some(ch) if ch >= channels => {
+                        log::warn!(
+                            "Device has {} channels, channel {} is silent until it returns",
+                            channels,
+                            ch + 1
+                        );
+                    }
+                    _ => {}
+                }
+            }
+        }
 
         log::info!("Device config: {:?}", supported_config);
 
         self.sample_rate = supported_config.sample_rate().0;
         self.is_recording.store(true, Ordering::SeqCst);
 
-        // Clear existing samples
-        if let Ok(mut samples) = self.samples.lock() {
-            samples.clear();
-            // Pre-allocate for 30 seconds
-            samples.reserve(self.sample_rate as usize * BUFFER_SECONDS);
+        // Fresh ring buffers and counters for this stream (bounded to 30 seconds of mono audio per tap)
+        self.capacity = self.sample_rate as usize * BUFFER_SECONDS;
+        let mut taps = Vec::with_capacity(stream_taps.len());
+        let mut consumers = Vec::with_capacity(stream_taps.len());
+        for channel in &stream_taps {
+            let (producer, consumer) = rtrb::RingBuffer::new(self.capacity);
+            taps.push(CaptureTap {
+                channel: channel.map(usize::from),
+                producer,
+            });
+            consumers.push(consumer);
         }
-
-        // Clear recent samples for RMS calculation
-        if let Ok(mut recent) = self.recent_samples.lock() {
-            recent.clear();
+        if let Ok(mut c) = self.consumers.lock() {
+            *c = consumers;
         }
+        self.counters = Arc::new(CaptureCounters::default());
 
         let config = StreamConfig {
             channels: supported_config.channels(),
@@ -125,98 +503,22 @@ impl AudioCapture {
             buffer_size: cpal::BufferSize::Default,
         };
 
-        let samples = self.samples.clone();
-        let recent_samples = self.recent_samples.clone();
-        let channels = config.channels as usize;
-        let err_fn = |err| log::error!("Audio stream error: {}", err);
+        let sink = CaptureSink::new(taps, self.counters.clone());
 
         let stream = match supported_config.sample_format() {
-            SampleFormat::F32 => {
-                device.build_input_stream(
-                    &config,
-                    move |data: &[f32], _: &cpal::InputCallbackInfo| {
-                        if let Ok(mut buf) = samples.lock() {
-                            // Convert to mono
-                            if channels > 1 {
-                                for chunk in data.chunks(channels) {
-                                    let mono = chunk.iter().sum::<f32>() / channels as f32;
-                                    buf.push(mono);
-                                }
-                            } else {
-                                buf.extend_from_slice(data);
-                            }
-                        }
-                        // Store recent samples for RMS calculation
-                        if let Ok(mut recent) = recent_samples.lock() {
-                            recent.clear();
-                            if channels > 1 {
-                                for chunk in data.chunks(channels) {
-                                    let mono = chunk.iter().sum::<f32>() / channels as f32;
-                                    recent.push(mono);
-                                }
-                            } else {
-                                recent.extend_from_slice(data);
-                            }
-                            // Keep only last 1024 samples
-                            if recent.len() > 1024 {
-                                let excess = recent.len() - 1024;
-                                recent.drain(0..excess);
-                            }
-                        }
-                    },
-                    err_fn,
-                    None,
-                )?
-            }
-            SampleFormat::I16 => {
-                device.build_input_stream(
-                    &config,
-                    move |data: &[i16], _: &cpal::InputCallbackInfo| {
-                        if let Ok(mut buf) = samples.lock() {
-                            if channels > 1 {
-                                for chunk in data.chunks(channels) {
-                                    let mono = chunk
-                                        .iter()
-                                        .map(|&s| s as f32 / i16::MAX as f32)
-                                        .sum::<f32>()
-                                        / channels as f32;
-                                    buf.push(mono);
-                                }
-                            } else {
-                                for &s in data {
-                                    buf.push(s as f32 / i16::MAX as f32);
-                                }
-                            }
-                        }
-                        // Store recent samples for RMS calculation
-                        if let Ok(mut recent) = recent_samples.lock() {
-                            recent.clear();
-                            if channels > 1 {
-                                for chunk in data.chunks(channels) {
-                                    let mono = chunk
-                                        .iter()
-                                        .map(|&s| s as f32 / i16::MAX as f32)
-                                        .sum::<f32>()
-                                        / channels as f32;
-                                    recent.push(mono);
-                                }
-                            } else {
-                                for &s in data {
-                                    recent.push(s as f32 / i16::MAX as f32);
-                                }
-                            }
-                            if recent.len() > 1024 {
-                                let excess = recent.len() - 1024;
-                                recent.drain(0..excess);
-                            }
-                        }
-                    },
-                    err_fn,
-                    None,
-                )?
-            }
-            _ => return Err(anyhow!("Unsupported sample format")),
+            SampleFormat::I8 => build_stream::<i8>(device, &config, sink)?,
+            SampleFormat::I16 => build_stream::<i16>(device, &config, sink)?,
+            SampleFormat::I32 => build_stream::<i32>(device, &config, sink)?,
+            SampleFormat::I64 => build_stream::<i64>(device, &config, sink)?,
+            SampleFormat::U8 => build_stream::<u8>(device, &config, sink)?,
+            SampleFormat::U16 => build_stream::<u16>(device, &config, sink)?,
+            SampleFormat::U32 => build_stream::<u32>(device, &config, sink)?,
+            SampleFormat::U64 => build_stream::<u64>(device, &config, sink)?,
+            SampleFormat::F32 => build_stream::<f32>(device, &config, sink)?,
+            SampleFormat::F64 => build_stream::<f64>(device, &config, sink)?,
+            other => return Err(anyhow!("Unsupported sample format: {}", other)),
         };
+        self.stream_format = Some(AudioInputFormat::from(&supported_config));
 
         stream.play()?;
         self.stream = Some(stream);
@@ -233,6 +535,42 @@ impl AudioCapture {
         Ok(())
     }
 
+    /// Name of the device currently selected
+    pub fn current_device_name(&self) -> Option<String> {
+        self.device.as_ref().and_then(|d| d.name().ok())
+    }
+
+    /// Names of the input devices currently connected. Enumeration can be slow, so it needs no
+    /// capture (and no lock on one).
+    pub fn input_device_names() -> Vec<String> {
+        cpal::default_host()
+            .input_devices()
+            .map(|devices| devices.filter_map(|d| d.name().ok()).collect())
+            .unwrap_or_default()
+    }
+
+    /// When the running stream last delivered samples (None = not yet)
+    pub fn last_sample_time(&self) -> Option<std::time::SystemTime> {
+        match self.counters.last_sample_ms.load(Ordering::Relaxed) {
+            0 => None,
+            ms => Some(std::time::UNIX_EPOCH + std::time::Duration::from_millis(ms)),
+        }
+    }
+
+    /// True once the running stream has reported an error (e.g. the device was unplugged)
+    pub fn has_stream_error(&self) -> bool {
+        self.counters.stream_error.load(Ordering::Relaxed)
+    }
+
+    /// Reopen capture on another device (None = system default), keeping every tap even if
+    /// the device lacks its channel. Read any pending samples first; the ring buffer is
+    /// replaced.
+    pub fn restart_with(&mut self, device_name: Option<&str>) -> Result<()> {
+        self.stop()?;
+        self.select_device(device_name)?;
+        self.start_stream(true)
+    }
+
     /// Check if currently recording
     pub fn is_recording(&self) -> bool {
         self.is_recording.load(Ordering::SeqCst)
@@ -243,34 +581,80 @@ impl AudioCapture {
         self.sample_rate
     }
 
-    /// Get all audio samples and clear buffer
+    /// Get all audio samples of the first tap and clear its buffer
     pub fn get_samples(&self) -> Vec<f32> {
-        if let Ok(mut samples) = self.samples.lock() {
-            let result = samples.clone();
-            samples.clear();
-            result
-        } else {
-            vec![]
-        }
+        self.get_tap_samples()
+            .into_iter()
+            .next()
+            .unwrap_or_default()
+    }
+
+    /// Drain every tap's buffer, in the order given to `select_channels`
+    pub fn get_tap_samples(&self) -> Vec<Vec<f32>> {
+        let Ok(mut consumers) = self.consumers.lock() else {
+            return vec![];
+        };
+        consumers
+            .iter_mut()
+            .map(|consumer| {
+                let available = consumer.slots();
+                match consumer.read_chunk(available) {
+                    Ok(chunk) => {
+                        let (a, b) = chunk.as_slices();
+                        let mut result = Vec::with_capacity(available);
+                        result.extend_from_slice(a);
+                        result.extend_from_slice(b);
+                        chunk.commit_all();
+                        result
+                    }
+                    Err(_) => vec![],
+                }
+            })
+            .collect()
     }
 
-    /// Get available sample count
+    /// Get available sample count (first tap)
     pub fn available_samples(&self) -> usize {
-        self.samples.lock().map(|s| s.len()).unwrap_or(0)
+        self.consumers
+            .lock()
+            .ok()
+            .and_then(|c| c.first().map(|c| c.slots()))
+            .unwrap_or(0)
     }
 
-    /// Get the current RMS audio level (0.0 to 1.0)
+    /// Publish the level of the audio after pre-processing (noise suppression, gain), so the
+    /// meter shows what is actually transcribed
+    pub fn set_processed_level(&self, rms: f32) {
+        self.counters
+            .processed_rms_bits
+            .store(rms.to_bits(), Ordering::Relaxed);
+        self.counters
+            .has_processed_level
+            .store(true, Ordering::Relaxed);
+    }
+
+    /// Get the current RMS audio level (0.0 to 1.0). Uses the processed level when the
+    /// reader publishes one, otherwise the raw device level.
     pub fn get_rms_level(&self) -> f32 {
-        if let Ok(samples) = self.recent_samples.lock() {
-            if samples.is_empty() {
-                return 0.0;
-            }
-            let sum: f32 = samples.iter().map(|s| s * s).sum();
-            let rms = (sum / samples.len() as f32).sqrt();
-            // Normalize to 0-1 range (assuming 16-bit equivalent)
-            (rms * 3.0).min(1.0) // Scale up for better visualization
+        let bits = if self.counters.has_processed_level.load(Ordering::Relaxed) {
+            &self.counters.processed_rms_bits
         } else {
-            0.0
+            &self.counters.rms_bits
+        };
+        let rms = f32::from_bits(bits.load(Ordering::Relaxed));
+        // Normalize to 0-1 range (assuming 16-bit equivalent)
+        (rms * 3.0).min(1.0) // Scale up for better visualization
+    }
+
+    /// Ring buffer and overrun counters for the current stream
+    pub fn stats(&self) -> AudioCaptureStats {
+        AudioCaptureStats {
+            sample_rate: self.sample_rate,
+            captured_samples: self.counters.captured_samples.load(Ordering::Relaxed),
+            dropped_samples: self.counters.dropped_samples.load(Ordering::Relaxed),
+            overruns: self.counters.overruns.load(Ordering::Relaxed),
+            buffered_samples: self.available_samples(),
+            capacity_samples: self.capacity,
         }
     }
 }
@@ -280,3 +664,55 @@ impl Drop for AudioCapture {
         let _ = self.stop();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cpal::SupportedBufferSize;
+
+    fn range(
+        channels: u16,
+        min: u32,
+        max: u32,
+        format: SampleFormat,
+    ) -> SupportedStreamConfigRange {
+        SupportedStreamConfigRange::new(
+            channels,
+            cpal::SampleRate(min),
+            cpal::SampleRate(max),
+            SupportedBufferSize::Unknown,
+            format,
+        )
+    }
+
+    #[test]
+    fn test_choose_config_prefers_16k_mono() {
+        let ranges = vec![
+            range(2, 8000, 48000, SampleFormat::F32),
+            range(1, 8000, 48000, SampleFormat::I32),
+            range(1, 44100, 48000, SampleFormat::F32),
+        ];
+        let config = choose_config(&ranges, None, 1).unwrap();
+        assert_eq!(config.sample_rate().0, 16000);
+        assert_eq!(config.channels(), 1);
+        assert_eq!(config.sample_format(), SampleFormat::I32);
+
+        // Explicit request wins when supported
+        let requested = AudioInputFormat {
+            sample_rate: 48000,
+            channels: 2,
+            sample_format: "f32".to_string(),
+        };
+        let config = choose_config(&ranges, Some(&requested), 1).unwrap();
+        assert_eq!(AudioInputFormat::from(&config), requested);
+
+        // Nothing at 16 kHz: caller falls back to the device default
+        assert!(choose_config(&ranges[2..], None, 1).is_none());
+
+        // Separate channels need a config that has them
+        let config = choose_config(&ranges, None, 2).unwrap();
+        assert_eq!(config.channels(), 2);
+        let config = choose_config(&ranges[1..], None, 2);
+        assert!(config.is_none());
+    }
+}
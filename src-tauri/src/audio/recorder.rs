@@ -0,0 +1,404 @@
+// Meeting audio persistence
+// Writes 16 kHz mono audio to IMA ADPCM WAV files (4:1 vs 16-bit PCM) and reads
+// arbitrary millisecond ranges back for playback.
+//
+// IMA ADPCM is a standard WAV codec (format tag 0x0011) playable by ffmpeg, VLC, sox,
+// etc. Fixed-size blocks make seeking trivial: every block starts with the decoder
+// state, so a range read only decodes the blocks it touches.
+
+use anyhow::{anyhow, Result};
+use std::fs::File;
+use std::io::{BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+const SAMPLE_RATE: u32 = 16000;
+const BLOCK_ALIGN: usize = 512;
+/// One header sample + two samples per remaining byte.
+const SAMPLES_PER_BLOCK: usize = 1 + (BLOCK_ALIGN - 4) * 2;
+const WAVE_FORMAT_IMA_ADPCM: u16 = 0x0011;
+/// RIFF(12) + fmt(8 + 20) + fact(8 + 4) + data header(8)
+const HEADER_LEN: u64 = 60;
+/// Rewrite the header every ~10 s so a crash leaves a playable file.
+const HEADER_UPDATE_BLOCKS: u64 = 160;
+
+const STEP_TABLE: [i32; 89] = [
+    7, 8, 9, 10, 11, 12, 13, 14, 16, 17, 19, 21, 23, 25, 28, 31, 34, 37, 41, 45, 50, 55, 60, 66,
+    73, 80, 88, 97, 107, 118, 130, 143, 157, 173, 190, 209, 230, 253, 279, 307, 337, 371, 408, 449,
+    494, 544, 598, 658, 724, 796, 876, 963, 1060, 1166, 1282, 1411, 1552, 1707, 1878, 2066, 2272,
+    2499, 2749, 3024, 3327, 3660, 4026, 4428, 4871, 5358, 5894, 6484, 7132, 7845, 8630, 9493,
+    10442, 11487, 12635, 13899, 15289, 16818, 18500, 20350, 22385, 24623, 27086, 29794, 32767,
+];
+
+const INDEX_TABLE: [i32; 16] = [-1, -1, -1, -1, 2, 4, 6, 8, -1, -1, -1, -1, 2, 4, 6, 8];
+
+/// IMA ADPCM codec state (shared by encoder and decoder).
+#[derive(Debug, Clone, Copy, Default)]
+struct AdpcmState {
+    predictor: i32,
+    index: i32,
+}
+
+impl AdpcmState {
+    fn encode(&mut self, sample: i16) -> u8 {
+        let step = STEP_TABLE[self.index as usize];
+        let mut diff = sample as i32 - self.predictor;
+        let mut nibble = 0u8;
+        if diff < 0 {
+            nibble = 8;
+            diff = -diff;
+        }
+        if diff >= step {
+            nibble |= 4;
+            diff -= step;
+        }
+        if diff >= step >> 1 {
+            nibble |= 2;
+            diff -= step >> 1;
+        }
+        if diff >= step >> 2 {
+            nibble |= 1;
+        }
+        // Update state exactly as the decoder will, so both stay in lockstep
+        self.decode(nibble);
+        nibble
+    }
+
+    fn decode(&mut self, nibble: u8) -> i16 {
+        let step = STEP_TABLE[self.index as usize];
+        let mut delta = step >> 3;
+        if nibble & 4 != 0 {
+            delta += step;
+        }
+        if nibble & 2 != 0 {
+            delta += step >> 1;
+        }
+        if nibble & 1 != 0 {
+            delta += step >> 2;
+        }
+        if nibble & 8 != 0 {
+            self.predictor -= delta;
+        } else {
+            self.predictor += delta;
+        }
+        self.predictor = self.predictor.clamp(i16::MIN as i32, i16::MAX as i32);
+        self.index = (self.index + INDEX_TABLE[nibble as usize]).clamp(0, 88);
+        self.predictor as i16
+    }
+}
+
+fn f32_to_i16(s: f32) -> i16 {
+    (s.clamp(-1.0, 1.0) * i16::MAX as f32) as i16
+}
+
+/// Encode exactly SAMPLES_PER_BLOCK samples into one BLOCK_ALIGN-byte block.
+fn encode_block(samples: &[i16], state: &mut AdpcmState) -> [u8; BLOCK_ALIGN] {
+    let mut block = [0u8; BLOCK_ALIGN];
+    state.predictor = samples[0] as i32;
+    block[0..2].copy_from_slice(&samples[0].to_le_bytes());
+    block[2] = state.index as u8;
+    block[3] = 0;
+    for (i, pair) in samples[1..].chunks(2).enumerate() {
+        let lo = state.encode(pair[0]);
+        let hi = pair.get(1).map(|&s| state.encode(s)).unwrap_or(0);
+        block[4 + i] = lo | (hi << 4);
+    }
+    block
+}
+
+/// Decode one block into SAMPLES_PER_BLOCK samples.
+fn decode_block(block: &[u8], out: &mut Vec<f32>) {
+    let mut state = AdpcmState {
+        predictor: i16::from_le_bytes([block[0], block[1]]) as i32,
+        index: (block[2] as i32).clamp(0, 88),
+    };
+    out.push(state.predictor as f32 / i16::MAX as f32);
+    for &byte in &block[4..] {
+        out.push(state.decode(byte & 0x0f) as f32 / i16::MAX as f32);
+        out.push(state.decode(byte >> 4) as f32 / i16::MAX as f32);
+    }
+}
+
+/// Streams one source's audio into an IMA ADPCM WAV file while recording.
+pub struct AudioRecorder {
+    writer: BufWriter<File>,
+    pending: Vec<i16>,
+    state: AdpcmState,
+    blocks_written: u64,
+    samples_written: u64,
+}
+
+impl AudioRecorder {
+    /// Create (or truncate) a recording file. Input must be 16 kHz mono.
+    pub fn create(path: &Path) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let file = File::create(path)?;
+        let mut recorder = Self {
+            writer: BufWriter::new(file),
+            pending: Vec::with_capacity(SAMPLES_PER_BLOCK),
+            state: AdpcmState::default(),
+            blocks_written: 0,
+            samples_written: 0,
+        };
+        recorder.write_header()?;
+        Ok(recorder)
+    }
+
+    /// Append 16 kHz mono samples.
+    pub fn write(&mut self, samples: &[f32]) -> Result<()> {
+        for &s in samples {
+            self.pending.push(f32_to_i16(s));
+            if self.pending.len() == SAMPLES_PER_BLOCK {
+                self.flush_block()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Duration written so far, in milliseconds.
+    pub fn duration_ms(&self) -> i64 {
+        ((self.samples_written + self.pending.len() as u64) * 1000 / SAMPLE_RATE as u64) as i64
+    }
+
+    /// Flush the partial last block (zero-padded) and finalize the header.
+    pub fn finish(mut self) -> Result<()> {
+        if !self.pending.is_empty() {
+            let real = self.pending.len() as u64;
+            self.pending.resize(SAMPLES_PER_BLOCK, 0);
+            self.flush_block()?;
+            // Only count the real samples; the fact chunk tells readers where to stop
+            self.samples_written -= SAMPLES_PER_BLOCK as u64 - real;
+        }
+        self.write_header()?;
+        self.writer.flush()?;
+        Ok(())
+    }
+
+    fn flush_block(&mut self) -> Result<()> {
+        let block = encode_block(&self.pending, &mut self.state);
+        self.writer.write_all(&block)?;
+        self.pending.clear();
+        self.blocks_written += 1;
+        self.samples_written += SAMPLES_PER_BLOCK as u64;
+        if self.blocks_written.is_multiple_of(HEADER_UPDATE_BLOCKS) {
+            self.write_header()?;
+        }
+        Ok(())
+    }
+
+    fn write_header(&mut self) -> Result<()> {
+        let data_len = (self.blocks_written * BLOCK_ALIGN as u64) as u32;
+        let byte_rate = SAMPLE_RATE * BLOCK_ALIGN as u32 / SAMPLES_PER_BLOCK as u32;
+
+        let mut h = Vec::with_capacity(HEADER_LEN as usize);
+        h.extend_from_slice(b"RIFF");
+        h.extend_from_slice(&(HEADER_LEN as u32 - 8 + data_len).to_le_bytes());
+        h.extend_from_slice(b"WAVE");
+        h.extend_from_slice(b"fmt ");
+        h.extend_from_slice(&20u32.to_le_bytes());
+        h.extend_from_slice(&WAVE_FORMAT_IMA_ADPCM.to_le_bytes());
+        h.extend_from_slice(&1u16.to_le_bytes()); // mono
+        h.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+        h.extend_from_slice(&byte_rate.to_le_bytes());
+        h.extend_from_slice(&(BLOCK_ALIGN as u16).to_le_bytes());
+        h.extend_from_slice(&4u16.to_le_bytes()); // bits per sample
+        h.extend_from_slice(&2u16.to_le_bytes()); // cbSize
+        h.extend_from_slice(&(SAMPLES_PER_BLOCK as u16).to_le_bytes());
+        h.extend_from_slice(b"fact");
+        h.extend_from_slice(&4u32.to_le_bytes());
+        h.extend_from_slice(&(self.samples_written as u32).to_le_bytes());
+        h.extend_from_slice(b"data");
+        h.extend_from_slice(&data_len.to_le_bytes());
+
+        self.writer.flush()?;
+        let file = self.writer.get_mut();
+        let pos = file.stream_position()?;
+        file.seek(SeekFrom::Start(0))?;
+        file.write_all(&h)?;
+        file.seek(SeekFrom::Start(pos.max(HEADER_LEN)))?;
+        Ok(())
+    }
+}
+
+/// Layout of a recording file discovered by walking its RIFF chunks.
+struct AdpcmLayout {
+    data_offset: u64,
+    block_align: usize,
+    samples_per_block: usize,
+    total_samples: u64,
+}
+
+fn read_layout(file: &mut File) -> Result<AdpcmLayout> {
+    let mut riff = [0u8; 12];
+    file.read_exact(&mut riff)?;
+    if &riff[0..4] != b"RIFF" || &riff[8..12] != b"WAVE" {
+        return Err(anyhow!("Not a WAV file"));
+    }
+
+    let mut fmt: Option<(usize, usize)> = None;
+    let mut fact_samples: Option<u64> = None;
+    loop {
+        let mut hdr = [0u8; 8];
+        file.read_exact(&mut hdr)?;
+        let len = u32::from_le_bytes([hdr[4], hdr[5], hdr[6], hdr[7]]) as u64;
+        match &hdr[0..4] {
+            b"fmt " => {
+                let mut body = vec![0u8; len as usize];
+                file.read_exact(&mut body)?;
+                if body.len() < 20
+                    || u16::from_le_bytes([body[0], body[1]]) != WAVE_FORMAT_IMA_ADPCM
+                    || u16::from_le_bytes([body[2], body[3]]) != 1
+                {
+                    return Err(anyhow!(
+                        "Unsupported recording format (expected mono IMA ADPCM)"
+                    ));
+                }
+                let block_align = u16::from_le_bytes([body[12], body[13]]) as usize;
+                let spb = u16::from_le_bytes([body[18], body[19]]) as usize;
+                fmt = Some((block_align, spb));
+            }
+            b"fact" => {
+                let mut body = [0u8; 4];
+                file.read_exact(&mut body)?;
+                fact_samples = Some(u32::from_le_bytes(body) as u64);
+                file.seek(SeekFrom::Current(len as i64 - 4))?;
+            }
+            b"data" => {
+                let (block_align, samples_per_block) =
+                    fmt.ok_or_else(|| anyhow!("WAV data chunk before fmt chunk"))?;
+                let data_offset = file.stream_position()?;
+                // The header may be stale after a crash; trust the file length then
+                let file_data_len = file.metadata()?.len().saturating_sub(data_offset);
+                let data_len = if len == 0 {
+                    file_data_len
+                } else {
+                    len.min(file_data_len)
+                };
+                let block_samples = data_len / block_align as u64 * samples_per_block as u64;
+                let total_samples = match fact_samples {
+                    Some(n) if n > 0 => n.min(block_samples),
+                    _ => block_samples,
+                };
+                return Ok(AdpcmLayout {
+                    data_offset,
+                    block_align,
+                    samples_per_block,
+                    total_samples,
+                });
+            }
+            _ => {
+                file.seek(SeekFrom::Current(len as i64 + (len & 1) as i64))?;
+            }
+        }
+    }
+}
+
+/// Read `[start_ms, end_ms)` of a recording as 16 kHz mono f32 samples.
+pub fn read_range(path: &Path, start_ms: i64, end_ms: i64) -> Result<Vec<f32>> {
+    let mut file = File::open(path)?;
+    let layout = read_layout(&mut file)?;
+
+    // Saturating, so a range ending at i64::MAX reads to the end of the recording
+    let rate = SAMPLE_RATE as i64 / 1000;
+    let start = (start_ms.max(0).saturating_mul(rate) as u64).min(layout.total_samples);
+    let end = (end_ms.max(0).saturating_mul(rate) as u64).min(layout.total_samples);
+    if start >= end {
+        return Ok(Vec::new());
+    }
+
+    let first_block = start / layout.samples_per_block as u64;
+    let last_block = (end - 1) / layout.samples_per_block as u64;
+    file.seek(SeekFrom::Start(
+        layout.data_offset + first_block * layout.block_align as u64,
+    ))?;
+
+    let mut decoded =
+        Vec::with_capacity(((last_block - first_block + 1) as usize) * layout.samples_per_block);
+    let mut block = vec![0u8; layout.block_align];
+    for _ in first_block..=last_block {
+        file.read_exact(&mut block)?;
+        decode_block(&block, &mut decoded);
+    }
+
+    let offset = (start - first_block * layout.samples_per_block as u64) as usize;
+    let len = (end - start) as usize;
+    Ok(decoded[offset..offset + len].to_vec())
+}
+
+/// Total duration of a recording in milliseconds.
+pub fn recording_duration_ms(path: &Path) -> Result<i64> {
+    let mut file = File::open(path)?;
+    let layout = read_layout(&mut file)?;
+    Ok((layout.total_samples * 1000 / SAMPLE_RATE as u64) as i64)
+}
+
+/// Encode 16 kHz mono samples as an in-memory 16-bit PCM WAV (for playback in the UI).
+pub fn encode_wav_pcm16(samples: &[f32]) -> Result<Vec<u8>> {
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate: SAMPLE_RATE,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut cursor = std::io::Cursor::new(Vec::new());
+    {
+        let mut writer = hound::WavWriter::new(&mut cursor, spec)?;
+        for &s in samples {
+            writer.write_sample(f32_to_i16(s))?;
+        }
+        writer.finalize()?;
+    }
+    Ok(cursor.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sine(n: usize) -> Vec<f32> {
+        (0..n)
+            .map(|i| 0.5 * (2.0 * std::f32::consts::PI * 440.0 * i as f32 / 16000.0).sin())
+            .collect()
+    }
+
+    #[test]
+    fn test_adpcm_roundtrip_range() {
+        let path = std::env::temp_dir().join(format!("phantomear-rec-{}.wav", std::process::id()));
+        let input = sine(16000 * 3 + 123);
+
+        let mut rec = AudioRecorder::create(&path).unwrap();
+        rec.write(&input[..20000]).unwrap();
+        rec.write(&input[20000..]).unwrap();
+        assert_eq!(rec.duration_ms(), 3007);
+        rec.finish().unwrap();
+
+        assert_eq!(recording_duration_ms(&path).unwrap(), 3007);
+
+        let out = read_range(&path, 1000, 2000).unwrap();
+        assert_eq!(out.len(), 16000);
+        let max_err = out
+            .iter()
+            .zip(&input[16000..32000])
+            .map(|(a, b)| (a - b).abs())
+            .fold(0.0f32, f32::max);
+        assert!(max_err < 0.05, "max error {}", max_err);
+
+        // Reading past the end is clamped
+        assert_eq!(
+            read_range(&path, 2900, 9000).unwrap().len(),
+            123 + 16000 * 3 - 46400
+        );
+        assert_eq!(read_range(&path, 2900, i64::MAX).unwrap().len(), 1723);
+        assert!(read_range(&path, i64::MAX, i64::MAX).unwrap().is_empty());
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_encode_wav_pcm16() {
+        let wav = encode_wav_pcm16(&sine(1600)).unwrap();
+        assert_eq!(&wav[0..4], b"RIFF");
+        assert_eq!(wav.len(), 44 + 1600 * 2);
+    }
+}
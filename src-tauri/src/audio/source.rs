@@ -0,0 +1,352 @@
+// Audio sources for the recording pipeline
+// The producers pull from a `CaptureSource` about every 100 ms, so a live device, system
+// audio, an audio file or a generated signal all go through the same conditioning, chunking,
+// transcription and events.
+//
+// - AudioCapture: cpal input device (hot-plug supervision goes through `device()`)
+// - SystemAudioCapture: ScreenCaptureKit / PulseAudio monitor
+// - FileSource: decodes a recording and plays it back in step with the wall clock
+// - ToneSource: sine tone or silence of a fixed length
+//
+// File and generated sources can run faster than real time and always deliver the same
+// samples, so a problem seen in a meeting can be replayed through the exact live pipeline.
+
+use anyhow::{anyhow, Result};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use super::{AudioCapture, AudioCaptureStats, AudioFileReader, SystemAudioCapture};
+
+/// Sample rate of generated audio
+const TONE_SAMPLE_RATE: u32 = 16000;
+
+/// Something the recording pipeline can pull mono audio from.
+pub trait CaptureSource: Send {
+    fn start(&mut self) -> Result<()>;
+
+    fn stop(&mut self) -> Result<()>;
+
+    /// Rate of the samples returned by `read`
+    fn sample_rate(&self) -> u32;
+
+    /// Samples delivered since the last call, one buffer per tap
+    fn read(&mut self) -> Vec<Vec<f32>>;
+
+    /// True once a finite source has delivered all of its audio
+    fn is_finished(&self) -> bool {
+        false
+    }
+
+    /// Level for the meter (0.0 to 1.0)
+    fn rms_level(&self) -> f32 {
+        0.0
+    }
+
+    /// Publish the level after pre-processing, for sources that drive the meter
+    fn set_processed_level(&mut self, _rms: f32) {}
+
+    /// Ring buffer statistics, for sources that have one
+    fn stats(&self) -> Option<AudioCaptureStats> {
+        None
+    }
+
+    /// The cpal device behind this source, for hot-plug supervision
+    fn device(&mut self) -> Option<&mut AudioCapture> {
+        None
+    }
+}
+
+impl CaptureSource for AudioCapture {
+    fn start(&mut self) -> Result<()> {
+        AudioCapture::start(self)
+    }
+
+    fn stop(&mut self) -> Result<()> {
+        AudioCapture::stop(self)
+    }
+
+    fn sample_rate(&self) -> u32 {
+        AudioCapture::sample_rate(self)
+    }
+
+    fn read(&mut self) -> Vec<Vec<f32>> {
+        self.get_tap_samples()
+    }
+
+    fn rms_level(&self) -> f32 {
+        self.get_rms_level()
+    }
+
+    fn set_processed_level(&mut self, rms: f32) {
+        AudioCapture::set_processed_level(self, rms)
+    }
+
+    fn stats(&self) -> Option<AudioCaptureStats> {
+        Some(AudioCapture::stats(self))
+    }
+
+    fn device(&mut self) -> Option<&mut AudioCapture> {
+        Some(self)
+    }
+}
+
+impl CaptureSource for SystemAudioCapture {
+    fn start(&mut self) -> Result<()> {
+        SystemAudioCapture::start(self).map_err(|e| anyhow!(e))
+    }
+
+    fn stop(&mut self) -> Result<()> {
+        SystemAudioCapture::stop(self);
+        Ok(())
+    }
+
+    fn sample_rate(&self) -> u32 {
+        SystemAudioCapture::sample_rate(self)
+    }
+
+    fn read(&mut self) -> Vec<Vec<f32>> {
+        vec![self.get_samples()]
+    }
+}
+
+/// Releases samples in step with the wall clock, `speed` times faster than real time.
+struct Pacer {
+    sample_rate: u32,
+    speed: f32,
+    started: Option<Instant>,
+    released: u64,
+}
+
+impl Pacer {
+    fn new(sample_rate: u32, speed: f32) -> Self {
+        Self {
+            sample_rate,
+            speed,
+            started: None,
+            released: 0,
+        }
+    }
+
+    fn start(&mut self) {
+        self.started = Some(Instant::now());
+        self.released = 0;
+    }
+
+    fn stop(&mut self) {
+        self.started = None;
+    }
+
+    /// Samples that became due since the last call
+    fn due(&mut self) -> usize {
+        let Some(started) = self.started else {
+            return 0;
+        };
+        let total =
+            (started.elapsed().as_secs_f64() * self.speed as f64 * self.sample_rate as f64) as u64;
+        let due = total.saturating_sub(self.released);
+        self.released = total;
+        due as usize
+    }
+}
+
+/// Meter level of a block, scaled like `AudioCapture::get_rms_level`
+fn meter_level(samples: &[f32], previous: f32) -> f32 {
+    if samples.is_empty() {
+        return previous;
+    }
+    let rms = (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt();
+    (rms * 3.0).min(1.0)
+}
+
+fn check_speed(speed: f32) -> Result<()> {
+    if speed.is_finite() && speed > 0.0 {
+        Ok(())
+    } else {
+        Err(anyhow!("Playback speed must be positive, got {}", speed))
+    }
+}
+
+/// Plays an audio file (anything `AudioFileReader` decodes) as if it were being captured.
+pub struct FileSource {
+    reader: AudioFileReader,
+    pacer: Pacer,
+    /// Decoded samples not yet released
+    pending: Vec<f32>,
+    at_end: bool,
+    level: f32,
+}
+
+impl FileSource {
+    pub fn open(path: &Path, speed: f32) -> Result<Self> {
+        check_speed(speed)?;
+        let reader = AudioFileReader::open(path)?;
+        Ok(Self {
+            pacer: Pacer::new(reader.sample_rate(), speed),
+            reader,
+            pending: Vec::new(),
+            at_end: false,
+            level: 0.0,
+        })
+    }
+}
+
+impl CaptureSource for FileSource {
+    fn start(&mut self) -> Result<()> {
+        self.pacer.start();
+        Ok(())
+    }
+
+    fn stop(&mut self) -> Result<()> {
+        self.pacer.stop();
+        Ok(())
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.reader.sample_rate()
+    }
+
+    fn read(&mut self) -> Vec<Vec<f32>> {
+        let due = self.pacer.due();
+        while self.pending.len() < due && !self.at_end {
+            match self.reader.read_block() {
+                Ok(Some(block)) => self.pending.extend(block),
+                Ok(None) => self.at_end = true,
+                Err(e) => {
+                    log::error!("Audio file playback failed: {}", e);
+                    self.at_end = true;
+                }
+            }
+        }
+        let out: Vec<f32> = self.pending.drain(..due.min(self.pending.len())).collect();
+        self.level = meter_level(&out, self.level);
+        vec![out]
+    }
+
+    fn is_finished(&self) -> bool {
+        self.at_end && self.pending.is_empty()
+    }
+
+    fn rms_level(&self) -> f32 {
+        self.level
+    }
+}
+
+/// Generated sine tone (or silence) of a fixed length.
+pub struct ToneSource {
+    frequency: f32,
+    amplitude: f32,
+    total_samples: u64,
+    position: u64,
+    pacer: Pacer,
+    level: f32,
+}
+
+impl ToneSource {
+    pub fn new(frequency: f32, amplitude: f32, duration: Duration, speed: f32) -> Result<Self> {
+        check_speed(speed)?;
+        Ok(Self {
+            frequency,
+            amplitude,
+            total_samples: (duration.as_secs_f64() * TONE_SAMPLE_RATE as f64) as u64,
+            position: 0,
+            pacer: Pacer::new(TONE_SAMPLE_RATE, speed),
+            level: 0.0,
+        })
+    }
+
+    pub fn silence(duration: Duration, speed: f32) -> Result<Self> {
+        Self::new(0.0, 0.0, duration, speed)
+    }
+}
+
+impl CaptureSource for ToneSource {
+    fn start(&mut self) -> Result<()> {
+        self.pacer.start();
+        Ok(())
+    }
+
+    fn stop(&mut self) -> Result<()> {
+        self.pacer.stop();
+        Ok(())
+    }
+
+    fn sample_rate(&self) -> u32 {
+        TONE_SAMPLE_RATE
+    }
+
+    fn read(&mut self) -> Vec<Vec<f32>> {
+        let n = (self.pacer.due() as u64).min(self.total_samples - self.position);
+        let step = 2.0 * std::f64::consts::PI * self.frequency as f64 / TONE_SAMPLE_RATE as f64;
+        let out: Vec<f32> = (self.position..self.position + n)
+            .map(|i| self.amplitude * (step * i as f64).sin() as f32)
+            .collect();
+        self.position += n;
+        self.level = meter_level(&out, self.level);
+        vec![out]
+    }
+
+    fn is_finished(&self) -> bool {
+        self.position >= self.total_samples
+    }
+
+    fn rms_level(&self) -> f32 {
+        self.level
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Read a finite source to the end, as the producer would
+    fn drain(source: &mut dyn CaptureSource) -> Vec<f32> {
+        source.start().unwrap();
+        let mut samples = Vec::new();
+        for _ in 0..500 {
+            samples.extend(source.read().remove(0));
+            if source.is_finished() {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        assert!(source.is_finished());
+        samples
+    }
+
+    #[test]
+    fn test_tone_source_delivers_exact_length() {
+        let mut tone = ToneSource::new(440.0, 0.5, Duration::from_secs(3), 200.0).unwrap();
+        assert!(tone.read()[0].is_empty(), "nothing before start");
+        let samples = drain(&mut tone);
+        assert_eq!(samples.len(), 48000);
+        assert!(
+            (samples[10] - 0.5 * (2.0 * std::f32::consts::PI * 440.0 * 10.0 / 16000.0).sin()).abs()
+                < 1e-5
+        );
+        assert!(tone.rms_level() > 0.0);
+
+        assert!(ToneSource::silence(Duration::from_secs(1), 0.0).is_err());
+    }
+
+    #[test]
+    fn test_file_source_replays_whole_file() {
+        let path = std::env::temp_dir().join(format!("phantom-source-{}.wav", std::process::id()));
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: 16000,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+        for i in 0..40000 {
+            writer.write_sample((i % 100) as i16).unwrap();
+        }
+        writer.finalize().unwrap();
+
+        let mut source = FileSource::open(&path, 200.0).unwrap();
+        let samples = drain(&mut source);
+        std::fs::remove_file(&path).ok();
+        assert_eq!(samples.len(), 40000);
+        assert!((samples[150] - 50.0 / 32768.0).abs() < 1e-6);
+    }
+}
@@ -1,4 +1,5 @@
-// System audio capture via ScreenCaptureKit (macOS 12.3+)
+// System audio capture via ScreenCaptureKit (macOS 12.3+) or a PulseAudio/PipeWire
+// monitor source (Linux).
 // Captures all application audio output — works regardless of speaker volume or
 // whether the user is wearing headphones/AirPods.
 
@@ -46,17 +47,90 @@ impl SCStreamOutputTrait for SystemAudioHandler {
     }
 }
 
+// ─── Monitor sources (Linux only) ─────────────────────────────────────────────
+
+/// A PulseAudio/PipeWire monitor source — the loopback of an output sink.
+#[derive(Debug, Clone)]
+pub struct MonitorSource {
+    /// Source name as passed to `parec --device`, e.g. `alsa_output.<card>.analog-stereo.monitor`
+    pub name: String,
+    /// Human-readable description, e.g. "Monitor of Built-in Audio Analog Stereo"
+    pub description: String,
+    /// True if this is the monitor of the current default sink
+    pub is_default: bool,
+}
+
+/// Parse `pactl list sources` output into (name, description) pairs of monitor sources.
+#[cfg(target_os = "linux")]
+fn parse_pactl_monitor_sources(output: &str) -> Vec<(String, String)> {
+    let mut sources = Vec::new();
+    let mut name: Option<String> = None;
+
+    for line in output.lines() {
+        let line = line.trim();
+        if line.starts_with("Source #") {
+            name = None;
+        } else if let Some(n) = line.strip_prefix("Name:") {
+            name = Some(n.trim().to_string());
+        } else if let Some(d) = line.strip_prefix("Description:") {
+            if let Some(n) = name.take() {
+                if n.ends_with(".monitor") {
+                    sources.push((n, d.trim().to_string()));
+                }
+            }
+        }
+    }
+
+    sources
+}
+
+/// Parse the default sink name out of `pactl info` output.
+#[cfg(target_os = "linux")]
+fn parse_pactl_default_sink(output: &str) -> Option<String> {
+    output
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("Default Sink:"))
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+#[cfg(target_os = "linux")]
+fn run_pactl(args: &[&str]) -> Result<String, String> {
+    let output = std::process::Command::new("pactl")
+        .args(args)
+        .env("LC_ALL", "C") // field labels are localized otherwise
+        .output()
+        .map_err(|e| format!("Failed to run pactl (is PulseAudio/PipeWire running?): {e}"))?;
+    if !output.status.success() {
+        return Err(format!(
+            "pactl {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
 // ─── Public struct ────────────────────────────────────────────────────────────
 
-/// Captures system-wide audio output via ScreenCaptureKit.
+/// Captures system-wide audio output via ScreenCaptureKit (macOS) or the monitor
+/// source of a PulseAudio/PipeWire sink (Linux).
 ///
-/// On non-macOS platforms this is a no-op stub so the rest of the codebase
+/// On other platforms this is a no-op stub so the rest of the codebase
 /// compiles without platform-specific guards everywhere.
 pub struct SystemAudioCapture {
     samples: Arc<Mutex<Vec<f32>>>,
     is_running: Arc<AtomicBool>,
     #[cfg(target_os = "macos")]
     stream: Option<SCStream>,
+    /// Monitor source to record; None = monitor of the default sink.
+    #[cfg(target_os = "linux")]
+    source: Option<String>,
+    /// `parec` child process streaming raw f32 PCM on stdout.
+    #[cfg(target_os = "linux")]
+    child: Option<std::process::Child>,
+    #[cfg(target_os = "linux")]
+    reader: Option<std::thread::JoinHandle<()>>,
 }
 
 // SCStream holds ObjC objects; we always access it from a single async task.
@@ -75,7 +149,51 @@ impl SystemAudioCapture {
             is_running: Arc::new(AtomicBool::new(false)),
             #[cfg(target_os = "macos")]
             stream: None,
+            #[cfg(target_os = "linux")]
+            source: None,
+            #[cfg(target_os = "linux")]
+            child: None,
+            #[cfg(target_os = "linux")]
+            reader: None,
+        }
+    }
+
+    /// List available system audio sources.
+    ///
+    /// On Linux these are the monitor sources of every output sink. ScreenCaptureKit
+    /// always captures the full system mix, so on macOS the list is empty.
+    pub fn list_sources() -> Result<Vec<MonitorSource>, String> {
+        #[cfg(target_os = "linux")]
+        {
+            let default_monitor = run_pactl(&["info"])
+                .ok()
+                .and_then(|info| parse_pactl_default_sink(&info))
+                .map(|sink| format!("{sink}.monitor"));
+
+            let listing = run_pactl(&["list", "sources"])?;
+            Ok(parse_pactl_monitor_sources(&listing)
+                .into_iter()
+                .map(|(name, description)| MonitorSource {
+                    is_default: default_monitor.as_deref() == Some(name.as_str()),
+                    name,
+                    description,
+                })
+                .collect())
+        }
+
+        #[cfg(not(target_os = "linux"))]
+        Ok(Vec::new())
+    }
+
+    /// Select a monitor source by name, or the default sink's monitor if None.
+    /// Only meaningful on Linux; ignored elsewhere.
+    pub fn select_source(&mut self, source_name: Option<&str>) {
+        #[cfg(target_os = "linux")]
+        {
+            self.source = source_name.map(|s| s.to_string());
         }
+        #[cfg(not(target_os = "linux"))]
+        let _ = source_name;
     }
 
     /// Start capturing system audio. Returns an error string on failure.
@@ -114,21 +232,102 @@ impl SystemAudioCapture {
 
             let mut stream = SCStream::new(&filter, &config);
             stream.add_output_handler(handler, SCStreamOutputType::Audio);
-            stream
-                .start_capture()
-                .map_err(|e| format!("SCStream::start_capture failed: {e:?}"))?;
+            // Running before the first callback can arrive
+            self.is_running.store(true, Ordering::SeqCst);
+            if let Err(e) = stream.start_capture() {
+                self.is_running.store(false, Ordering::SeqCst);
+                return Err(format!("SCStream::start_capture failed: {e:?}"));
+            }
 
             self.stream = Some(stream);
-            self.is_running.store(true, Ordering::SeqCst);
             log::info!("System audio capture started (ScreenCaptureKit, 16 kHz mono)");
             Ok(())
         }
 
-        #[cfg(not(target_os = "macos"))]
-        Err("System audio capture requires macOS 12.3+".to_string())
+        #[cfg(target_os = "linux")]
+        {
+            use std::io::Read;
+            use std::process::{Command, Stdio};
+
+            let source = match self.source.clone() {
+                Some(s) => s,
+                None => {
+                    let sources = Self::list_sources()?;
+                    sources
+                        .iter()
+                        .find(|s| s.is_default)
+                        .or_else(|| sources.first())
+                        .map(|s| s.name.clone())
+                        .ok_or_else(|| "No PulseAudio/PipeWire monitor source found".to_string())?
+                }
+            };
+
+            // The sound server resamples and downmixes for us, so we receive
+            // 16 kHz mono f32 exactly like the ScreenCaptureKit path.
+            let mut child = Command::new("parec")
+                .args([
+                    "--device",
+                    source.as_str(),
+                    "--format=float32le",
+                    "--rate=16000",
+                    "--channels=1",
+                    "--latency-msec=100",
+                    "--client-name=PhantomEar",
+                ])
+                .stdout(Stdio::piped())
+                .stderr(Stdio::null())
+                .spawn()
+                .map_err(|e| format!("Failed to start parec: {e}"))?;
+
+            let mut stdout = child
+                .stdout
+                .take()
+                .ok_or_else(|| "parec stdout unavailable".to_string())?;
+
+            let samples = self.samples.clone();
+            let is_running = self.is_running.clone();
+            // Set before the reader starts, so an early parec exit clears it rather than
+            // being overwritten
+            self.is_running.store(true, Ordering::SeqCst);
+            let reader = std::thread::spawn(move || {
+                let mut buf = [0u8; 3200]; // 50 ms of 16 kHz f32
+                let mut pending: Vec<u8> = Vec::with_capacity(buf.len() + 4);
+                loop {
+                    let n = match stdout.read(&mut buf) {
+                        Ok(0) | Err(_) => break,
+                        Ok(n) => n,
+                    };
+                    // Reads are not guaranteed to end on a sample boundary.
+                    pending.extend_from_slice(&buf[..n]);
+                    let whole = pending.len() / 4 * 4;
+                    if let Ok(mut guard) = samples.lock() {
+                        guard.extend(
+                            pending[..whole]
+                                .chunks_exact(4)
+                                .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]])),
+                        );
+                    }
+                    pending.drain(..whole);
+                }
+                if is_running.swap(false, Ordering::SeqCst) {
+                    log::warn!("parec exited unexpectedly, system audio capture stopped");
+                }
+            });
+
+            self.child = Some(child);
+            self.reader = Some(reader);
+            log::info!("System audio capture started (monitor source {source}, 16 kHz mono)");
+            Ok(())
+        }
+
+        #[cfg(not(any(target_os = "macos", target_os = "linux")))]
+        Err(
+            "System audio capture requires macOS 12.3+ or Linux with PulseAudio/PipeWire"
+                .to_string(),
+        )
     }
 
-    /// Stop capturing and release SCK resources.
+    /// Stop capturing and release SCK resources / the parec process.
     pub fn stop(&mut self) {
         self.is_running.store(false, Ordering::SeqCst);
         #[cfg(target_os = "macos")]
@@ -136,6 +335,15 @@ impl SystemAudioCapture {
             let _ = stream.stop_capture();
             log::info!("System audio capture stopped");
         }
+        #[cfg(target_os = "linux")]
+        if let Some(mut child) = self.child.take() {
+            let _ = child.kill();
+            let _ = child.wait();
+            if let Some(reader) = self.reader.take() {
+                let _ = reader.join();
+            }
+            log::info!("System audio capture stopped");
+        }
     }
 
     /// Drain and return all buffered PCM samples since the last call.
@@ -165,3 +373,39 @@ impl Drop for SystemAudioCapture {
         self.stop();
     }
 }
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pactl_monitor_sources() {
+        let output = "Source #0\n\
+                      \tState: SUSPENDED\n\
+                      \tName: alsa_output.pci-0000_00_1f.3.analog-stereo.monitor\n\
+                      \tDescription: Monitor of Built-in Audio Analog Stereo\n\
+                      \n\
+                      Source #1\n\
+                      \tState: RUNNING\n\
+                      \tName: alsa_input.pci-0000_00_1f.3.analog-stereo\n\
+                      \tDescription: Built-in Audio Analog Stereo\n";
+        let sources = parse_pactl_monitor_sources(output);
+        assert_eq!(sources.len(), 1);
+        assert_eq!(
+            sources[0].0,
+            "alsa_output.pci-0000_00_1f.3.analog-stereo.monitor"
+        );
+        assert_eq!(sources[0].1, "Monitor of Built-in Audio Analog Stereo");
+    }
+
+    #[test]
+    fn test_parse_pactl_default_sink() {
+        let info =
+            "Server Name: PulseAudio (on PipeWire 1.0.5)\nDefault Sink: alsa_output.usb-headset\n";
+        assert_eq!(
+            parse_pactl_default_sink(info).as_deref(),
+            Some("alsa_output.usb-headset")
+        );
+        assert_eq!(parse_pactl_default_sink("Server Name: x\n"), None);
+    }
+}
@@ -0,0 +1,209 @@
+// Voice activity detection
+// Frame-level energy + spectral detector for 16 kHz mono audio.
+//
+// Per 30 ms frame:
+// - Energy (dB) compared against an adaptive noise floor, so quiet speakers in a quiet
+//   room still register while a noisy room doesn't read as constant speech
+// - Speech-band ratio: share of energy inside 250-3500 Hz (voice formants) vs. the whole
+//   frame, which rejects rumble, hum and broadband hiss
+// - Zero-crossing rate, which rejects white-ish noise that happens to be loud
+// A short hangover keeps word endings and brief stops inside the speech region.
+
+use std::f32::consts::PI;
+
+const SAMPLE_RATE: f32 = 16000.0;
+
+/// Voice activity detector tuning
+#[derive(Debug, Clone)]
+pub struct VadConfig {
+    /// Analysis frame length in milliseconds
+    pub frame_ms: u32,
+    /// How far above the noise floor a frame must be to count as speech (dB)
+    pub threshold_db: f32,
+    /// Frames below this absolute level are never speech (dBFS)
+    pub min_energy_db: f32,
+    /// Minimum fraction of frame energy inside the speech band
+    pub min_speech_band_ratio: f32,
+    /// Maximum zero-crossing rate (crossings per sample) for voiced audio
+    pub max_zero_crossing_rate: f32,
+    /// Frames of speech to keep reporting after the detector goes quiet
+    pub hangover_frames: u32,
+}
+
+impl Default for VadConfig {
+    fn default() -> Self {
+        Self {
+            frame_ms: 30,
+            threshold_db: 9.0,
+            min_energy_db: -60.0,
+            min_speech_band_ratio: 0.45,
+            max_zero_crossing_rate: 0.35,
+            hangover_frames: 6,
+        }
+    }
+}
+
+impl VadConfig {
+    pub fn frame_samples(&self) -> usize {
+        (SAMPLE_RATE as u32 * self.frame_ms / 1000) as usize
+    }
+}
+
+/// Second-order IIR section (RBJ audio EQ cookbook), transposed direct form II.
+#[derive(Debug, Clone)]
+pub(crate) struct Biquad {
+    b0: f32,
+    b1: f32,
+    b2: f32,
+    a1: f32,
+    a2: f32,
+    z1: f32,
+    z2: f32,
+}
+
+impl Biquad {
+    fn from_coefficients(b: [f32; 3], a: [f32; 3]) -> Self {
+        Self {
+            b0: b[0] / a[0],
+            b1: b[1] / a[0],
+            b2: b[2] / a[0],
+            a1: a[1] / a[0],
+            a2: a[2] / a[0],
+            z1: 0.0,
+            z2: 0.0,
+        }
+    }
+
+    pub(crate) fn high_pass(cutoff_hz: f32, sample_rate: f32) -> Self {
+        let w0 = 2.0 * PI * cutoff_hz / sample_rate;
+        let alpha = w0.sin() / (2.0 * std::f32::consts::FRAC_1_SQRT_2);
+        let cos = w0.cos();
+        Self::from_coefficients(
+            [(1.0 + cos) / 2.0, -(1.0 + cos), (1.0 + cos) / 2.0],
+            [1.0 + alpha, -2.0 * cos, 1.0 - alpha],
+        )
+    }
+
+    pub(crate) fn low_pass(cutoff_hz: f32, sample_rate: f32) -> Self {
+        let w0 = 2.0 * PI * cutoff_hz / sample_rate;
+        let alpha = w0.sin() / (2.0 * std::f32::consts::FRAC_1_SQRT_2);
+        let cos = w0.cos();
+        Self::from_coefficients(
+            [(1.0 - cos) / 2.0, 1.0 - cos, (1.0 - cos) / 2.0],
+            [1.0 + alpha, -2.0 * cos, 1.0 - alpha],
+        )
+    }
+
+    pub(crate) fn process(&mut self, x: f32) -> f32 {
+        let y = self.b0 * x + self.z1;
+        self.z1 = self.b1 * x - self.a1 * y + self.z2;
+        self.z2 = self.b2 * x - self.a2 * y;
+        y
+    }
+}
+
+fn to_db(power: f32) -> f32 {
+    10.0 * power.max(1e-12).log10()
+}
+
+/// Mean power of a frame in dBFS
+pub fn frame_energy_db(frame: &[f32]) -> f32 {
+    if frame.is_empty() {
+        return to_db(0.0);
+    }
+    to_db(frame.iter().map(|s| s * s).sum::<f32>() / frame.len() as f32)
+}
+
+/// Streaming voice activity detector. Feed consecutive frames of `frame_samples()` length.
+pub struct VoiceActivityDetector {
+    config: VadConfig,
+    high_pass: Biquad,
+    low_pass: Biquad,
+    /// Estimated background level (dBFS). Starts at the absolute floor and rises with
+    /// non-speech frames, so a recording that opens mid-sentence is still detected
+    noise_db: f32,
+    hangover: u32,
+}
+
+impl VoiceActivityDetector {
+    pub fn new(config: VadConfig) -> Self {
+        Self {
+            high_pass: Biquad::high_pass(250.0, SAMPLE_RATE),
+            low_pass: Biquad::low_pass(3500.0, SAMPLE_RATE),
+            noise_db: config.min_energy_db,
+            hangover: 0,
+            config,
+        }
+    }
+
+    pub fn config(&self) -> &VadConfig {
+        &self.config
+    }
+
+    /// Current noise floor estimate (dBFS)
+    pub fn noise_floor_db(&self) -> f32 {
+        self.noise_db
+    }
+
+    /// Classify one frame. Returns true while speech is active (including hangover).
+    pub fn process_frame(&mut self, frame: &[f32]) -> bool {
+        if frame.is_empty() {
+            return self.hangover > 0;
+        }
+
+        let mut total = 0.0f32;
+        let mut band = 0.0f32;
+        let mut crossings = 0usize;
+        let mut prev = frame[0];
+        for &s in frame {
+            total += s * s;
+            let b = self.low_pass.process(self.high_pass.process(s));
+            band += b * b;
+            if (s >= 0.0) != (prev >= 0.0) {
+                crossings += 1;
+            }
+            prev = s;
+        }
+        let n = frame.len() as f32;
+        let energy_db = to_db(total / n);
+        let band_ratio = if total > 0.0 { band / total } else { 0.0 };
+        let zcr = crossings as f32 / n;
+
+        let noise_db = self.noise_db;
+        let voiced = energy_db > self.config.min_energy_db
+            && energy_db > noise_db + self.config.threshold_db
+            && band_ratio >= self.config.min_speech_band_ratio
+            && zcr <= self.config.max_zero_crossing_rate;
+
+        // Noise floor: drop immediately to quieter frames, follow non-speech frames smoothly,
+        // and creep up slowly during long "speech" so a step change in background noise
+        // can't lock the detector on
+        let updated = if energy_db < noise_db {
+            energy_db
+        } else if !voiced {
+            0.9 * noise_db + 0.1 * energy_db
+        } else {
+            noise_db + 0.02
+        };
+        self.noise_db = updated.max(self.config.min_energy_db);
+
+        if voiced {
+            self.hangover = self.config.hangover_frames;
+            true
+        } else if self.hangover > 0 {
+            self.hangover -= 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.high_pass.z1 = 0.0;
+        self.high_pass.z2 = 0.0;
+        self.low_pass.z1 = 0.0;
+        self.low_pass.z2 = 0.0;
+        self.noise_db = self.config.min_energy_db;
+        self.hangover = 0;
+    }
+}
@@ -8,24 +8,41 @@
 use crate::asr::parakeet_backend::ParakeetModel;
 #[cfg(feature = "parakeet")]
 use crate::asr::AsrBackendType;
-use crate::asr::{self, TranscriptionEngine, WhisperModel};
-use crate::audio::AudioCapture;
-#[cfg(target_os = "macos")]
+use crate::asr::{self, punctuation, PunctuationModel, TranscriptionEngine, WhisperModel};
+use crate::audio::file::{self as audio_file, AudioFileReader};
+use crate::audio::recorder;
+#[cfg(any(target_os = "macos", target_os = "linux"))]
 use crate::audio::SystemAudioCapture;
+use crate::audio::{
+    AudioCapture, AudioCaptureStats, AudioConfigRange, AudioInputFormat, AudioProcessor,
+    AudioRecorder, CaptureSource, DspConfig, EchoCanceller, EchoReference, FileSource, LevelMeter,
+    MeterEvent, MeterWarning, ToneSource,
+};
 use crate::detection::MeetingDetector;
 use crate::embeddings::{self, EmbeddingModel};
 use crate::llm::{LlmClient, LlmProvider};
 use crate::models::{self, ModelInfo};
 use crate::storage::{
-    Database, MeetingListItem, SearchResult, SegmentRow, SemanticSearchResult, Speaker,
+    Database, LowConfidenceSegment, MeetingListItem, SearchResult, SegmentRow,
+    SemanticSearchResult, Speaker, WordRow,
+};
+use crate::transcription::final_pass;
+use crate::transcription::load_shedding::{LoadShedder, LoadSheddingOptions, SheddingAction};
+use crate::transcription::translation::{self, TranslationMode};
+use crate::transcription::workers::{
+    self, run_transcription_worker, ChunkQueue, ChunkTranscript, TranscribedChunk, WorkerPool,
+    WorkerStats,
+};
+use crate::transcription::{
+    language, prompt, stitch, window_around, AudioChunk, AudioSource, SpeechChunk, SpeechChunker,
+    TranscriptionConfig,
 };
-use crate::transcription::TranscriptionConfig;
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Arc;
-use tauri::{AppHandle, Emitter, State};
-use tokio::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::sync::{Mutex, RwLock};
 
 // ============================================================================
 // Types
@@ -37,8 +54,18 @@ pub struct TranscriptSegment {
     pub time: String,
     pub text: String,
     pub timestamp_ms: u64,
-    /// "mic" = local user, "system" = remote participants via SCK
+    /// End of the speech (older meetings: same as `timestamp_ms`)
+    #[serde(default)]
+    pub end_ms: u64,
+    /// "mic" = local user, "system" = remote participants via SCK / PulseAudio monitor,
+    /// anything else = the label of one of `Settings::audio_inputs`
     pub source: String,
+    /// Language the segment was transcribed in (None = not known)
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Second text in another language, when translation is on
+    #[serde(default)]
+    pub translation: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -58,6 +85,81 @@ pub struct Settings {
     pub asr_backend: String,
     #[serde(default)]
     pub audio_device: Option<String>,
+    /// Explicit mic format (None = 16 kHz mono if the device supports it, else its default)
+    #[serde(default)]
+    pub audio_input_format: Option<AudioInputFormat>,
+    /// Several mics or channels captured as separately labelled sources.
+    /// Empty = a single mic from `audio_device`.
+    #[serde(default)]
+    pub audio_inputs: Vec<AudioInputConfig>,
+    /// Linux: PulseAudio/PipeWire monitor source for system audio (None = default sink's monitor)
+    #[serde(default)]
+    pub system_audio_source: Option<String>,
+    /// Keep a compressed recording of each source alongside the transcript
+    #[serde(default)]
+    pub save_meeting_audio: bool,
+    /// Subtract speaker playback (system audio) from the mic and drop echoed duplicate text
+    #[serde(default)]
+    pub echo_cancellation: bool,
+    /// Mic pre-processing: 80 Hz high-pass (DC offset, rumble). The pre-processing stages are
+    /// opt-in, so upgrading doesn't change how existing setups sound.
+    #[serde(default)]
+    pub high_pass_filter: bool,
+    /// Mic pre-processing: spectral noise suppression (fans, hum, hiss)
+    #[serde(default)]
+    pub noise_suppression: bool,
+    /// Mic pre-processing: automatic gain for quiet or distant speakers
+    #[serde(default)]
+    pub auto_gain_control: bool,
+    /// Warn when a mic has been completely silent or clipping this long (0 = never)
+    #[serde(default = "default_input_warning_secs")]
+    pub input_warning_secs: u32,
+    /// Transcribe speech in progress about once a second and show it as partial text
+    #[serde(default)]
+    pub live_partials: bool,
+    /// Names, products and acronyms to bias transcription toward, in every meeting
+    #[serde(default)]
+    pub vocabulary: Vec<String>,
+    /// Prompt Whisper with the preceding text of the same source
+    #[serde(default = "default_true")]
+    pub context_prompting: bool,
+    /// Parakeet decoding: greedy by default, or beam search with an optional language model
+    #[serde(default)]
+    pub ctc_decoder: asr::DecoderOptions,
+    /// Restore punctuation and casing in text that has none (CTC models), when the
+    /// punctuation model is loaded
+    #[serde(default = "default_true")]
+    pub restore_punctuation: bool,
+    /// Language of individual sources, by source name (e.g. mic = "en", system = "es").
+    /// Sources not listed use `language`.
+    #[serde(default)]
+    pub source_languages: std::collections::HashMap<String, String>,
+    /// Languages "auto" may detect (empty = any Whisper language)
+    #[serde(default)]
+    pub auto_languages: Vec<String>,
+    /// Second text per segment: "off", "whisper" (speech to English, a second Whisper pass)
+    /// or "llm" (the configured LLM, into `translation_language`)
+    #[serde(default = "default_translation_mode")]
+    pub translation_mode: String,
+    /// Language code of LLM translations ("whisper" mode always writes English)
+    #[serde(default = "default_translation_language")]
+    pub translation_language: String,
+    /// Chunks transcribed at once (0 = one per four cores, up to four)
+    #[serde(default)]
+    pub transcription_workers: usize,
+    /// Decoder threads of each transcription worker (0 = the cores split among workers)
+    #[serde(default)]
+    pub transcription_threads: usize,
+    /// What to give up when transcription falls behind real time
+    #[serde(default)]
+    pub load_shedding: LoadSheddingOptions,
+    /// Whisper model that transcribes each recording again once it stops, replacing the live
+    /// transcript (None = keep the live transcript)
+    #[serde(default)]
+    pub final_pass_model: Option<String>,
+    /// Keep the live transcript for comparison when the final pass replaces it
+    #[serde(default)]
+    pub keep_live_transcript: bool,
     // AI Features
     #[serde(default)]
     pub enhance_transcripts: bool,
@@ -69,10 +171,22 @@ fn default_true() -> bool {
     true
 }
 
+fn default_input_warning_secs() -> u32 {
+    10
+}
+
 fn default_asr_backend() -> String {
     "whisper".to_string()
 }
 
+fn default_translation_mode() -> String {
+    "off".to_string()
+}
+
+fn default_translation_language() -> String {
+    "en".to_string()
+}
+
 impl Default for Settings {
     fn default() -> Self {
         Self {
@@ -87,6 +201,29 @@ impl Default for Settings {
             language: "en".to_string(),
             asr_backend: "whisper".to_string(),
             audio_device: None,
+            audio_input_format: None,
+            audio_inputs: Vec::new(),
+            system_audio_source: None,
+            save_meeting_audio: false,
+            echo_cancellation: false,
+            high_pass_filter: false,
+            noise_suppression: false,
+            auto_gain_control: false,
+            input_warning_secs: 10,
+            live_partials: false,
+            vocabulary: Vec::new(),
+            context_prompting: true,
+            ctc_decoder: asr::DecoderOptions::default(),
+            restore_punctuation: true,
+            source_languages: std::collections::HashMap::new(),
+            auto_languages: Vec::new(),
+            translation_mode: "off".to_string(),
+            translation_language: "en".to_string(),
+            transcription_workers: 0,
+            transcription_threads: 0,
+            load_shedding: LoadSheddingOptions::default(),
+            final_pass_model: None,
+            keep_live_transcript: false,
             // AI Features (default off)
             enhance_transcripts: true,
             detect_questions: true,
@@ -94,6 +231,84 @@ impl Default for Settings {
     }
 }
 
+/// One labelled capture input: a whole device mixed down, or a single channel of it
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct AudioInputConfig {
+    /// Device name (None = system default input)
+    #[serde(default)]
+    pub device: Option<String>,
+    /// 0-based channel taken on its own (None = all channels mixed down)
+    #[serde(default)]
+    pub channel: Option<u16>,
+    /// Speaker or position shown on segments, e.g. "Alice" or "Room"
+    pub label: String,
+}
+
+/// Plays a file or generated signal through the recording pipeline instead of live capture,
+/// so a run can be reproduced exactly
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ReplayConfig {
+    /// Played as the mic
+    pub mic: ReplayInput,
+    /// Played as system audio (macOS/Linux); None = no system audio
+    #[serde(default)]
+    pub system: Option<ReplayInput>,
+    /// 1.0 = real time; higher values play faster
+    #[serde(default = "default_replay_speed")]
+    pub speed: f32,
+}
+
+fn default_replay_speed() -> f32 {
+    1.0
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum ReplayInput {
+    /// Any file the importer can decode
+    File {
+        path: String,
+    },
+    /// Sine tone
+    Tone {
+        frequency: f32,
+        amplitude: f32,
+        duration_secs: f32,
+    },
+    Silence {
+        duration_secs: f32,
+    },
+}
+
+impl ReplayInput {
+    /// Open the source, not yet started
+    fn open(&self, speed: f32) -> Result<Box<dyn CaptureSource>, String> {
+        let duration = |secs: &f32| {
+            std::time::Duration::try_from_secs_f32(*secs)
+                .map_err(|e| format!("Invalid replay duration: {}", e))
+        };
+        let source: Box<dyn CaptureSource> = match self {
+            ReplayInput::File { path } => Box::new(
+                FileSource::open(std::path::Path::new(path), speed)
+                    .map_err(|e| format!("Failed to open {}: {}", path, e))?,
+            ),
+            ReplayInput::Tone {
+                frequency,
+                amplitude,
+                duration_secs,
+            } => Box::new(
+                ToneSource::new(*frequency, *amplitude, duration(duration_secs)?, speed)
+                    .map_err(|e| format!("Invalid replay tone: {}", e))?,
+            ),
+            ReplayInput::Silence { duration_secs } => Box::new(
+                ToneSource::silence(duration(duration_secs)?, speed)
+                    .map_err(|e| format!("Invalid replay silence: {}", e))?,
+            ),
+        };
+        Ok(source)
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ModelStatus {
     pub whisper_downloaded: bool,
@@ -113,6 +328,15 @@ pub struct Summary {
 pub struct AudioDeviceInfo {
     pub name: String,
     pub is_default: bool,
+    pub configs: Vec<AudioConfigRange>,
+    pub default_format: Option<AudioInputFormat>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct SystemAudioSourceInfo {
+    pub name: String,
+    pub description: String,
+    pub is_default: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -146,13 +370,59 @@ pub struct MeetingEndedEvent {
     pub message: String,
 }
 
+/// Event emitted when the recording input switches devices mid-meeting
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AudioDeviceChangedEvent {
+    /// Source of the affected input ("mic" or an input label)
+    pub source: String,
+    /// Device now capturing (None if no input could be opened)
+    pub device: Option<String>,
+    pub previous: Option<String>,
+    /// "disconnected", "stream-error" or "reconnected"
+    pub reason: String,
+    /// True while running on the default input instead of the configured device
+    pub is_fallback: bool,
+    /// Audio missed during the switch, padded with silence on the timeline
+    pub gap_ms: i64,
+}
+
+/// Periodic level report for one input, emitted while recording
+#[derive(Debug, Serialize, Clone)]
+pub struct AudioMeterEvent {
+    pub source: String,
+    /// Meter level (0.0 to 1.0), after pre-processing when it runs
+    pub level: f32,
+    /// Raw input RMS and peak (linear, 0.0 to 1.0)
+    pub rms: f32,
+    pub peak: f32,
+    pub clipped_samples: u64,
+    /// Length of the current run of digital silence
+    pub silent_ms: u64,
+    /// Length of the current run of clipping
+    pub clipping_ms: u64,
+    /// Totals for the capture stream (shared by the inputs of one device)
+    pub dropped_samples: u64,
+    pub overruns: u64,
+}
+
+/// Emitted when an input has been silent or clipping for `Settings::input_warning_secs`,
+/// and again (`active: false`) once it recovers
+#[derive(Debug, Serialize, Clone)]
+pub struct AudioWarningEvent {
+    pub source: String,
+    pub kind: MeterWarning,
+    pub active: bool,
+    pub duration_ms: u64,
+}
+
 // ============================================================================
 // App State
 // ============================================================================
 
 pub struct AppState {
-    pub audio_capture: Arc<Mutex<Option<AudioCapture>>>,
-    pub transcription_engine: Arc<Mutex<Option<TranscriptionEngine>>>,
+    /// Primary capture stream of the active recording (drives the level meter)
+    pub audio_capture: Arc<Mutex<Option<Box<dyn CaptureSource>>>>,
+    pub transcription_engine: Arc<RwLock<Option<TranscriptionEngine>>>,
     pub transcript: Arc<Mutex<Vec<TranscriptSegment>>>,
     pub is_recording: Arc<Mutex<bool>>,
     pub is_paused: Arc<Mutex<bool>>,
@@ -160,47 +430,111 @@ pub struct AppState {
     pub db: Arc<Database>,
     pub active_meeting_id: Arc<Mutex<Option<String>>>,
     pub embedding_model: Arc<Mutex<Option<EmbeddingModel>>>,
+    pub punctuation_model: Arc<Mutex<Option<PunctuationModel>>>,
     // Meeting detection
     pub meeting_detector: Arc<Mutex<MeetingDetector>>,
     pub detection_running: Arc<AtomicBool>,
     // Transcription queue depth (number of chunks waiting to be transcribed)
     pub pending_chunks: Arc<AtomicUsize>,
+    /// Real-time factor of each transcription worker of the current recording
+    pub worker_stats: Arc<std::sync::Mutex<Vec<WorkerStats>>>,
 }
 
-/// A captured and resampled audio chunk ready for transcription.
-/// Passed from the audio producer to the transcription consumer via mpsc channel.
-/// Which physical source the audio came from.
-#[derive(Debug, Clone, PartialEq)]
-enum AudioSource {
-    /// Microphone — the local user's voice.
-    Mic,
-    /// System audio via ScreenCaptureKit — remote participants / any app audio.
-    #[cfg(target_os = "macos")]
-    System,
+/// Source names for the configured inputs. Names double as recording file names, so labels
+/// are reduced to [A-Za-z0-9-_]; empty, reserved ("mic", "system") and repeated names are
+/// replaced or numbered.
+fn input_sources(inputs: &[AudioInputConfig]) -> Vec<AudioSource> {
+    let mut names: Vec<String> = Vec::with_capacity(inputs.len());
+    for (i, input) in inputs.iter().enumerate() {
+        let slug: String = input
+            .label
+            .trim()
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                    c
+                } else {
+                    '-'
+                }
+            })
+            .collect();
+        let mut name = match slug.as_str() {
+            "" | "mic" | "system" => format!("input-{}", i + 1),
+            _ => slug,
+        };
+        let base = name.clone();
+        let mut n = 2;
+        while names.contains(&name) {
+            name = format!("{}-{}", base, n);
+            n += 1;
+        }
+        names.push(name);
+    }
+    names.into_iter().map(AudioSource::Input).collect()
 }
 
-impl AudioSource {
-    fn as_str(&self) -> &'static str {
-        match self {
-            AudioSource::Mic => "mic",
-            #[cfg(target_os = "macos")]
-            AudioSource::System => "system",
+/// Path of a source's recording inside a meeting's audio directory.
+fn source_audio_path(audio_dir: &std::path::Path, source: &str) -> std::path::PathBuf {
+    audio_dir.join(format!("{}.wav", source))
+}
+
+/// Raw audio is written per source into <app data>/audio/<meeting id>/. Links the directory
+/// to the meeting and returns it, or None (logged) if that isn't possible.
+fn meeting_audio_dir(
+    app: &AppHandle,
+    db: &Database,
+    meeting_id: &str,
+) -> Option<std::path::PathBuf> {
+    match app.path().app_data_dir() {
+        Ok(dir) => {
+            let dir = dir.join("audio").join(meeting_id);
+            match db.set_meeting_audio_dir(meeting_id, Some(&dir.to_string_lossy())) {
+                Ok(()) => Some(dir),
+                Err(e) => {
+                    log::error!("Failed to link meeting audio: {}", e);
+                    None
+                }
+            }
+        }
+        Err(e) => {
+            log::error!("Failed to get app data dir for meeting audio: {}", e);
+            None
         }
     }
 }
 
-#[derive(Debug)]
-struct AudioChunk {
-    /// 16kHz mono f32 PCM samples, already resampled.
-    samples: Vec<f32>,
-    /// Absolute start position in the recording timeline (milliseconds).
-    start_ms: i64,
-    /// Duration of this chunk in milliseconds.
-    duration_ms: i64,
-    /// Monotonic index of this chunk (producer-assigned, used for segment IDs).
-    chunk_index: u64,
-    /// Which capture source produced this chunk.
-    source: AudioSource,
+/// Open a recorder for one source, logging (not failing the meeting) if the file can't be created.
+fn open_recorder(
+    audio_dir: Option<&std::path::Path>,
+    source: &AudioSource,
+) -> Option<AudioRecorder> {
+    let path = source_audio_path(audio_dir?, source.as_str());
+    match AudioRecorder::create(&path) {
+        Ok(rec) => Some(rec),
+        Err(e) => {
+            log::error!("Failed to create audio recording {:?}: {}", path, e);
+            None
+        }
+    }
+}
+
+/// Append samples to the recording. A write failure disables recording for the rest of
+/// the meeting rather than interrupting transcription.
+fn record_samples(recorder: &mut Option<AudioRecorder>, samples: &[f32]) {
+    if let Some(rec) = recorder.as_mut() {
+        if let Err(e) = rec.write(samples) {
+            log::error!("Audio recording failed, disabling: {}", e);
+            *recorder = None;
+        }
+    }
+}
+
+fn finish_recorder(recorder: Option<AudioRecorder>) {
+    if let Some(rec) = recorder {
+        if let Err(e) = rec.finish() {
+            log::error!("Failed to finalize audio recording: {}", e);
+        }
+    }
 }
 
 // ============================================================================
@@ -224,9 +558,117 @@ fn format_meeting_title() -> String {
         .to_string()
 }
 
-/// Start audio recording and transcription
+/// Channel (None = all channels mixed) and source of each input taken from one stream
+type InputTaps = Vec<(Option<u16>, AudioSource)>;
+
+/// Capture stream of a recording and the inputs taken from it
+struct CaptureStream {
+    /// Configured device (None = default input)
+    device: Option<String>,
+    /// Channel and source of each tap, in tap order
+    taps: InputTaps,
+    capture: Box<dyn CaptureSource>,
+}
+
+/// Open and start the live inputs from settings, one stream per device. The first stream is
+/// the primary one; only it is required to start.
+fn start_live_inputs(settings: &Settings) -> Result<Vec<CaptureStream>, String> {
+    // Group the inputs by device: each device is one stream, split into a tap per input.
+    // Without configured inputs, the single mic from `audio_device` is captured.
+    let mut device_groups: Vec<(Option<String>, InputTaps)> = Vec::new();
+    if settings.audio_inputs.is_empty() {
+        device_groups.push((
+            settings.audio_device.clone(),
+            vec![(None, AudioSource::Mic)],
+        ));
+    } else {
+        let sources = input_sources(&settings.audio_inputs);
+        for (input, source) in settings.audio_inputs.iter().zip(sources) {
+            match device_groups
+                .iter_mut()
+                .find(|(device, _)| *device == input.device)
+            {
+                Some((_, taps)) => taps.push((input.channel, source)),
+                None => device_groups.push((input.device.clone(), vec![(input.channel, source)])),
+            }
+        }
+    }
+    let mut device_groups = device_groups.into_iter();
+    let Some((preferred_device, primary_taps)) = device_groups.next() else {
+        return Err("No audio inputs configured".to_string());
+    };
+
+    // Initialize audio capture with selected device from settings
+    let mut audio_capture =
+        AudioCapture::new().map_err(|e| format!("Failed to initialize audio: {}", e))?;
+
+    // Apply selected audio device if configured. If it isn't plugged in, start on the
+    // default input; the producer switches over when it appears.
+    if let Some(ref device_name) = preferred_device {
+        if let Err(e) = audio_capture.select_device(Some(device_name.as_str())) {
+            log::warn!(
+                "Audio device '{}' unavailable ({}), using default input",
+                device_name,
+                e
+            );
+            audio_capture
+                .select_device(None)
+                .map_err(|e| format!("Failed to select audio device: {}", e))?;
+        }
+    }
+    audio_capture.select_format(settings.audio_input_format.clone());
+    audio_capture.select_channels(primary_taps.iter().map(|(channel, _)| *channel).collect());
+
+    audio_capture
+        .start()
+        .map_err(|e| format!("Failed to start recording: {}", e))?;
+
+    let mut streams = vec![CaptureStream {
+        device: preferred_device,
+        taps: primary_taps,
+        capture: Box::new(audio_capture),
+    }];
+
+    // Other devices get their own stream. They never fall back to the default input, which
+    // would duplicate the primary mic; an input that isn't plugged in is skipped.
+    for (device, taps) in device_groups {
+        let mut capture = match AudioCapture::new() {
+            Ok(capture) => capture,
+            Err(e) => {
+                log::warn!("Failed to initialize audio for {:?}: {}", device, e);
+                continue;
+            }
+        };
+        capture.select_channels(taps.iter().map(|(channel, _)| *channel).collect());
+        let started = capture
+            .select_device(device.as_deref())
+            .and_then(|()| capture.start());
+        match started {
+            Ok(()) => streams.push(CaptureStream {
+                device,
+                taps,
+                capture: Box::new(capture),
+            }),
+            Err(e) => log::warn!(
+                "Audio input {:?} unavailable ({}), not capturing {:?}",
+                device,
+                e,
+                taps.iter().map(|(_, s)| s.as_str()).collect::<Vec<_>>()
+            ),
+        }
+    }
+    Ok(streams)
+}
+
+/// Start audio recording and transcription.
+/// With `replay`, a file or generated signal stands in for the live inputs; the run then
+/// goes through the same pipeline and emits the same events as a live meeting.
 #[tauri::command]
-pub async fn start_recording(app: AppHandle, state: State<'_, AppState>) -> Result<String, String> {
+pub async fn start_recording(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    replay: Option<ReplayConfig>,
+) -> Result<String, String> {
     let mut is_recording = state.is_recording.lock().await;
     if *is_recording {
         return Err("Already recording".to_string());
@@ -234,7 +676,7 @@ pub async fn start_recording(app: AppHandle, state: State<'_, AppState>) -> Resu
 
     // Check if model is loaded
     {
-        let engine = state.transcription_engine.lock().await;
+        let engine = state.transcription_engine.read().await;
         if engine.is_none() {
             return Err(
                 "Transcription model not loaded. Please download a model first.".to_string(),
@@ -242,23 +684,36 @@ pub async fn start_recording(app: AppHandle, state: State<'_, AppState>) -> Resu
         }
     }
 
-    // Initialize audio capture with selected device from settings
-    let mut audio_capture =
-        AudioCapture::new().map_err(|e| format!("Failed to initialize audio: {}", e))?;
-
-    // Apply selected audio device if configured
-    {
-        let settings = state.settings.lock().await;
-        if let Some(ref device_name) = settings.audio_device {
-            audio_capture
-                .select_device(Some(device_name.as_str()))
-                .map_err(|e| format!("Failed to select audio device '{}': {}", device_name, e))?;
+    // System audio: the live monitor, or the replayed signal if one is given
+    #[cfg(any(target_os = "macos", target_os = "linux"))]
+    let system_capture: Option<Box<dyn CaptureSource>> = match replay {
+        Some(ref replay) => replay
+            .system
+            .as_ref()
+            .map(|input| input.open(replay.speed))
+            .transpose()?,
+        None => {
+            let mut capture = SystemAudioCapture::new();
+            capture.select_source(state.settings.lock().await.system_audio_source.as_deref());
+            Some(Box::new(capture))
         }
-    }
+    };
 
-    audio_capture
-        .start()
-        .map_err(|e| format!("Failed to start recording: {}", e))?;
+    let mut streams = match replay {
+        Some(ref replay) => {
+            let mut capture = replay.mic.open(replay.speed)?;
+            capture
+                .start()
+                .map_err(|e| format!("Failed to start replay: {}", e))?;
+            vec![CaptureStream {
+                device: None,
+                taps: vec![(None, AudioSource::Mic)],
+                capture,
+            }]
+        }
+        None => start_live_inputs(&*state.settings.lock().await)?,
+    };
+    let primary = streams.remove(0);
 
     // Create meeting in DB
     let meeting_id = format!("meeting-{}", Utc::now().timestamp_millis());
@@ -272,8 +727,37 @@ pub async fn start_recording(app: AppHandle, state: State<'_, AppState>) -> Resu
 
     *state.active_meeting_id.lock().await = Some(meeting_id.clone());
 
+    let (save_audio, final_pass_model) = {
+        let settings = state.settings.lock().await;
+        (
+            settings.save_meeting_audio,
+            settings.final_pass_model.clone(),
+        )
+    };
+    let saved_audio_dir = if save_audio {
+        meeting_audio_dir(&app, &state.db, &meeting_id)
+    } else {
+        None
+    };
+    // The final pass reads the session audio back; unless it is saved with the meeting, it is
+    // recorded to a temporary directory
+    let final_pass = final_pass_model
+        .as_deref()
+        .and_then(final_pass_whisper_model)
+        .map(|model| FinalPass {
+            model,
+            temporary: saved_audio_dir.is_none(),
+            audio_dir: saved_audio_dir
+                .clone()
+                .unwrap_or_else(|| final_pass_dir(&meeting_id)),
+        });
+    let audio_dir = final_pass
+        .as_ref()
+        .map(|pass| pass.audio_dir.clone())
+        .or(saved_audio_dir);
+
     // Store in state
-    *state.audio_capture.lock().await = Some(audio_capture);
+    *state.audio_capture.lock().await = Some(primary.capture);
     *state.transcript.lock().await = Vec::new();
     *state.is_paused.lock().await = false;
     *is_recording = true;
@@ -290,9 +774,65 @@ pub async fn start_recording(app: AppHandle, state: State<'_, AppState>) -> Resu
     let pending_prod = state.pending_chunks.clone();
 
     // Clone tx so the system audio producer can share the same consumer channel.
-    #[cfg(target_os = "macos")]
+    #[cfg(any(target_os = "macos", target_os = "linux"))]
     let system_chunk_tx = chunk_tx.clone();
 
+    // Echo cancellation uses the system stream as its reference, so it needs system capture
+    let echo_reference = if cfg!(any(target_os = "macos", target_os = "linux"))
+        && state.settings.lock().await.echo_cancellation
+    {
+        Some(Arc::new(EchoReference::new()))
+    } else {
+        None
+    };
+    let (dsp_config, warn_after_ms, notify_warnings, partials) = {
+        let settings = state.settings.lock().await;
+        let dsp_config = DspConfig {
+            high_pass: settings.high_pass_filter,
+            noise_suppression: settings.noise_suppression,
+            auto_gain: settings.auto_gain_control,
+        };
+        (
+            dsp_config,
+            settings.input_warning_secs as u64 * 1000,
+            settings.show_system_notifications,
+            settings.live_partials,
+        )
+    };
+
+    let config = TranscriptionConfig::default();
+    let make_lanes = |taps: InputTaps| -> Vec<InputLane> {
+        taps.into_iter()
+            .map(|(_, source)| InputLane {
+                recorder: open_recorder(audio_dir.as_deref(), &source),
+                chunker: SpeechChunker::new(&config),
+                conditioner: MicConditioner::new(echo_reference.clone(), dsp_config),
+                meter: LevelMeter::new(16000, METER_INTERVAL_MS, warn_after_ms),
+                notify_warnings,
+                partials,
+                last_partial: None,
+                fed_samples: 0,
+                source,
+            })
+            .collect()
+    };
+
+    for stream in streams {
+        let name = stream.taps[0].1.as_str().to_string();
+        tauri::async_runtime::spawn(run_audio_producer(
+            app.clone(),
+            Arc::new(Mutex::new(Some(stream.capture))),
+            is_recording_arc.clone(),
+            is_paused_arc.clone(),
+            TranscriptionConfig::default(),
+            chunk_tx.clone(),
+            pending_prod.clone(),
+            make_lanes(stream.taps),
+            InputSupervisor::new(name, stream.device, false),
+        ));
+    }
+
+    let name = primary.taps[0].1.as_str().to_string();
     tauri::async_runtime::spawn(run_audio_producer(
         app_producer,
         audio_capture_arc,
@@ -301,23 +841,31 @@ pub async fn start_recording(app: AppHandle, state: State<'_, AppState>) -> Resu
         TranscriptionConfig::default(),
         chunk_tx,
         pending_prod.clone(),
+        make_lanes(primary.taps),
+        InputSupervisor::new(name, primary.device, true),
     ));
 
-    // System audio producer (macOS only): captures all app output via ScreenCaptureKit.
+    // System audio producer (macOS/Linux): captures all app output via ScreenCaptureKit
+    // or a PulseAudio/PipeWire monitor source.
     // Shares the same consumer channel; chunks are tagged AudioSource::System.
-    #[cfg(target_os = "macos")]
-    {
+    #[cfg(any(target_os = "macos", target_os = "linux"))]
+    if let Some(system_capture) = system_capture {
         let app_sys = app.clone();
         let is_recording_sys = is_recording_arc.clone();
         let is_paused_sys = is_paused_arc.clone();
         let pending_sys = pending_prod.clone();
+        let audio_dir_sys = audio_dir.clone();
         tauri::async_runtime::spawn(run_system_audio_producer(
             app_sys,
+            system_capture,
             is_recording_sys,
             is_paused_sys,
             TranscriptionConfig::default(),
             system_chunk_tx,
             pending_sys,
+            audio_dir_sys,
+            echo_reference,
+            partials,
         ));
     }
 
@@ -326,178 +874,736 @@ pub async fn start_recording(app: AppHandle, state: State<'_, AppState>) -> Resu
     let transcript_arc = state.transcript.clone();
     let db_arc = state.db.clone();
     let emb_model_arc = state.embedding_model.clone();
+    let punct_model_arc = state.punctuation_model.clone();
     let mid = meeting_id.clone();
     let meeting_title = title.clone();
     let settings_arc = state.settings.clone();
     let app_consumer = app.clone();
     let pending_cons = state.pending_chunks.clone();
+    let worker_stats = state.worker_stats.clone();
 
-    tauri::async_runtime::spawn(run_transcription_consumer(
-        app_consumer,
-        engine_arc,
-        transcript_arc,
-        db_arc,
-        mid,
-        emb_model_arc,
-        meeting_title,
-        settings_arc,
-        chunk_rx,
-        pending_cons,
-    ));
+    // The final pass starts once the live transcript is complete
+    tauri::async_runtime::spawn(async move {
+        run_transcription_consumer(
+            app_consumer.clone(),
+            engine_arc,
+            transcript_arc,
+            db_arc.clone(),
+            mid.clone(),
+            emb_model_arc.clone(),
+            punct_model_arc.clone(),
+            meeting_title.clone(),
+            settings_arc.clone(),
+            chunk_rx,
+            pending_cons,
+            worker_stats,
+        )
+        .await;
+        if let Some(pass) = final_pass {
+            run_final_pass(
+                app_consumer,
+                db_arc,
+                emb_model_arc,
+                punct_model_arc,
+                settings_arc,
+                mid,
+                meeting_title,
+                pass,
+            )
+            .await;
+        }
+    });
 
     log::info!("Recording started with meeting {}", meeting_id);
     Ok(meeting_id)
 }
 
-/// Audio producer: captures audio, accumulates chunks, and sends them to the transcription channel.
-/// Runs independently so audio is never dropped while transcription is busy.
-async fn run_audio_producer(
-    app: AppHandle,
-    audio_capture: Arc<Mutex<Option<AudioCapture>>>,
-    is_recording: Arc<Mutex<bool>>,
-    is_paused: Arc<Mutex<bool>>,
-    config: TranscriptionConfig,
-    chunk_tx: tokio::sync::mpsc::Sender<AudioChunk>,
-    pending_chunks: Arc<AtomicUsize>,
-) {
-    use crate::asr::resample_to_16khz;
-
-    let chunk_samples = (config.chunk_duration_secs * 16000.0) as usize;
-    let mut accumulated_samples: Vec<f32> = Vec::with_capacity(chunk_samples * 2);
-    let mut chunk_index: u64 = 0;
-    let mut total_duration_ms: i64 = 0;
+/// Queue a speech chunk for transcription. Returns false once the consumer has gone away.
+/// Partial chunks don't take a chunk index; they are identified by source and start instead.
+#[allow(clippy::too_many_arguments)]
+async fn send_chunk(
+    app: &AppHandle,
+    chunk_tx: &tokio::sync::mpsc::Sender<AudioChunk>,
+    pending_chunks: &AtomicUsize,
+    speech: SpeechChunk,
+    chunk_index: &mut u64,
+    source: AudioSource,
+    partial: bool,
+    ends_at: std::time::Instant,
+) -> bool {
+    if !partial {
+        *chunk_index += 1;
+    }
+    let chunk = AudioChunk {
+        samples: speech.samples,
+        start_ms: speech.start_ms,
+        duration_ms: speech.duration_ms,
+        chunk_index: *chunk_index,
+        source,
+        partial,
+        queued_at: std::time::Instant::now(),
+        ends_at,
+    };
 
-    log::info!(
-        "Audio producer started, chunk size: {} samples",
-        chunk_samples
+    // Increment pending count and notify frontend
+    let count = pending_chunks.fetch_add(1, Ordering::SeqCst) + 1;
+    let _ = app.emit(
+        "transcription-status",
+        serde_json::json!({ "status": "queued", "pending_chunks": count }),
     );
 
-    loop {
-        // Check if still recording
-        {
-            let recording = is_recording.lock().await;
-            if !*recording {
-                break;
-            }
+    chunk_tx.send(chunk).await.is_ok()
+}
+
+/// Watches the mic stream for hot-plug events: fails over to the default input when the
+/// device disappears or the stream errors, and switches back when the configured device returns.
+struct InputSupervisor {
+    /// Source of the first input on this stream, reported in events
+    source: String,
+    /// Device from Settings::audio_device (None = system default)
+    preferred: Option<String>,
+    /// Run on the default input while the preferred device is missing
+    fallback_to_default: bool,
+    last_scan: std::time::Instant,
+    /// When the device last delivered samples before capture went down, so the gap can be
+    /// padded on the timeline
+    outage_started: Option<std::time::SystemTime>,
+}
+
+impl InputSupervisor {
+    /// How often connected devices are re-enumerated
+    const SCAN_INTERVAL: std::time::Duration = std::time::Duration::from_secs(2);
+
+    fn new(source: String, preferred: Option<String>, fallback_to_default: bool) -> Self {
+        Self {
+            source,
+            preferred,
+            fallback_to_default,
+            last_scan: std::time::Instant::now(),
+            outage_started: None,
         }
+    }
 
-        // Check if paused - drain buffer to prevent buildup
-        {
-            let paused = is_paused.lock().await;
-            if *paused {
-                let capture_guard = audio_capture.lock().await;
-                if let Some(ref capture) = *capture_guard {
-                    let _ = capture.get_samples();
-                }
-                drop(capture_guard);
-                accumulated_samples.clear();
-                tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
-                continue;
-            }
+    /// Whether devices should be enumerated and `poll`ed now, given whether capture has failed
+    fn scan_due(&mut self, failed: bool) -> bool {
+        let now = std::time::Instant::now();
+        let scan_due = now.duration_since(self.last_scan) >= Self::SCAN_INTERVAL;
+        // After the first failover attempt, retry at the scan interval rather than every tick
+        let first_failover = failed && self.outage_started.is_none();
+        if !(scan_due || first_failover) {
+            return false;
         }
+        self.last_scan = now;
+        true
+    }
 
-        // Get audio samples
-        let (samples, sample_rate) = {
-            let capture_guard = audio_capture.lock().await;
-            if let Some(ref capture) = *capture_guard {
-                (capture.get_samples(), capture.sample_rate())
-            } else {
-                (vec![], 16000)
+    /// Check device health against the connected devices (`names`) and switch inputs if
+    /// needed. Call after draining the capture buffer.
+    /// Returns the audio gap (ms) once capture is running again after a switch.
+    fn poll(
+        &mut self,
+        app: &AppHandle,
+        capture: &mut AudioCapture,
+        names: &[String],
+    ) -> Option<i64> {
+        let failed = capture.has_stream_error() || !capture.is_recording();
+        let current = capture.current_device_name();
+        let current_present = current.as_ref().is_some_and(|n| names.contains(n));
+        let preferred_present = self.preferred.as_ref().is_some_and(|n| names.contains(n));
+
+        let reason = if failed {
+            "stream-error"
+        } else if !current_present {
+            "disconnected"
+        } else if preferred_present && current != self.preferred {
+            "reconnected"
+        } else {
+            return None;
+        };
+
+        // The gap runs from the last samples the device delivered, not from when it was noticed
+        let first_notice = self.outage_started.is_none();
+        let outage_start = *self.outage_started.get_or_insert_with(|| {
+            capture
+                .last_sample_time()
+                .unwrap_or_else(std::time::SystemTime::now)
+        });
+        let target = if preferred_present {
+            self.preferred.clone()
+        } else if self.fallback_to_default || self.preferred.is_none() {
+            None
+        } else {
+            // Wait for the device to come back
+            if first_notice {
+                log::warn!("Audio input {:?} {}, waiting for it", current, reason);
+                let _ = app.emit(
+                    "audio-device-changed",
+                    AudioDeviceChangedEvent {
+                        source: self.source.clone(),
+                        device: None,
+                        previous: current,
+                        reason: reason.to_string(),
+                        is_fallback: false,
+                        gap_ms: 0,
+                    },
+                );
             }
+            return None;
         };
+        log::warn!(
+            "Audio input {:?} {}, switching to {:?}",
+            current,
+            reason,
+            target.as_deref().unwrap_or("default input")
+        );
 
-        if !samples.is_empty() {
-            let samples_16k = if sample_rate != 16000 {
-                match resample_to_16khz(&samples, sample_rate) {
-                    Ok(s) => s,
-                    Err(e) => {
-                        log::error!("Resampling failed: {}", e);
-                        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
-                        continue;
+        match capture.restart_with(target.as_deref()) {
+            Ok(()) => {
+                self.outage_started = None;
+                let device = capture.current_device_name();
+                let gap_ms = outage_start
+                    .elapsed()
+                    .map_or(0, |gap| gap.as_millis() as i64);
+                let _ = app.emit(
+                    "audio-device-changed",
+                    AudioDeviceChangedEvent {
+                        source: self.source.clone(),
+                        is_fallback: self.preferred.is_some() && device != self.preferred,
+                        device,
+                        previous: current,
+                        reason: reason.to_string(),
+                        gap_ms,
+                    },
+                );
+                Some(gap_ms)
+            }
+            Err(e) => {
+                log::error!("Failed to reopen audio input: {}", e);
+                if failed || !current_present {
+                    // Still no audio; tell the UI once per outage
+                    if first_notice {
+                        let _ = app.emit(
+                            "audio-device-changed",
+                            AudioDeviceChangedEvent {
+                                source: self.source.clone(),
+                                device: None,
+                                previous: current,
+                                reason: reason.to_string(),
+                                is_fallback: self.preferred.is_some(),
+                                gap_ms: 0,
+                            },
+                        );
                     }
+                } else {
+                    // Switching back failed but the current device still works
+                    self.outage_started = None;
                 }
-            } else {
-                samples
-            };
-            accumulated_samples.extend(samples_16k);
+                None
+            }
         }
+    }
+}
 
-        // When we have a full chunk, check silence and send to consumer
-        if accumulated_samples.len() >= chunk_samples {
-            let rms: f32 = {
-                let sum_squares: f32 = accumulated_samples[..chunk_samples]
-                    .iter()
-                    .map(|s| s * s)
-                    .sum();
-                (sum_squares / chunk_samples as f32).sqrt()
-            };
-
-            if rms >= config.silence_threshold {
-                let chunk_data: Vec<f32> = accumulated_samples.drain(..chunk_samples).collect();
-                let duration_ms = (chunk_data.len() as f32 / 16.0) as i64;
-                chunk_index += 1;
+/// Mic conditioning between resampling and chunking: echo cancellation against the system
+/// stream, then the pre-processing chain. Neither stage changes the sample count, but output
+/// may trail input until `flush`.
+struct MicConditioner {
+    echo: Option<(EchoCanceller, Arc<EchoReference>)>,
+    dsp: Option<AudioProcessor>,
+}
 
-                let chunk = AudioChunk {
-                    samples: chunk_data,
-                    start_ms: total_duration_ms,
-                    duration_ms,
-                    chunk_index,
-                    source: AudioSource::Mic,
-                };
+impl MicConditioner {
+    fn new(echo_reference: Option<Arc<EchoReference>>, dsp: DspConfig) -> Self {
+        Self {
+            echo: echo_reference.map(|reference| (EchoCanceller::new(), reference)),
+            dsp: dsp.is_enabled().then(|| AudioProcessor::new(dsp)),
+        }
+    }
+
+    fn process(&mut self, samples: Vec<f32>) -> Vec<f32> {
+        let samples = match &mut self.echo {
+            Some((canceller, reference)) => canceller.process(&samples, reference),
+            None => samples,
+        };
+        match &mut self.dsp {
+            Some(dsp) => dsp.process(&samples),
+            None => samples,
+        }
+    }
+
+    /// Everything still held back (end of recording or pause)
+    fn flush(&mut self) -> Vec<f32> {
+        let held = match &mut self.echo {
+            Some((canceller, reference)) => canceller.flush(reference),
+            None => Vec::new(),
+        };
+        match &mut self.dsp {
+            Some(dsp) => {
+                let mut out = dsp.process(&held);
+                out.extend(dsp.flush());
+                out
+            }
+            None => held,
+        }
+    }
+
+    /// Level of the processed signal, if the chain is running
+    fn level(&self) -> Option<f32> {
+        self.dsp.as_ref().map(|dsp| dsp.rms_level())
+    }
+}
+
+/// One captured input: its place on the timeline, recording and conditioning
+struct InputLane {
+    source: AudioSource,
+    chunker: SpeechChunker,
+    recorder: Option<AudioRecorder>,
+    conditioner: MicConditioner,
+    /// Levels and health of the raw input
+    meter: LevelMeter,
+    /// Also raise warnings as native notifications
+    notify_warnings: bool,
+    /// Queue the utterance in progress as partial chunks
+    partials: bool,
+    /// (start, duration) of the last partial chunk queued, in ms
+    last_partial: Option<(i64, i64)>,
+    /// Raw samples fed so far: the timeline position reached at the time of the last feed
+    fed_samples: u64,
+}
+
+/// Audio the utterance in progress must gain before it is transcribed again as a partial
+const PARTIAL_INTERVAL_MS: i64 = 1000;
+
+/// Record, condition and chunk 16 kHz samples of one input, queueing finished speech chunks.
+/// The recording gets the raw input; the conditioner delays but never drops samples, so its
+/// offsets still line up with the chunk timestamps.
+/// With `flush`, audio held back by the conditioner and the utterance in progress go out too.
+/// Returns false once the consumer has gone away.
+async fn feed_lane(
+    app: &AppHandle,
+    chunk_tx: &tokio::sync::mpsc::Sender<AudioChunk>,
+    pending_chunks: &AtomicUsize,
+    chunk_index: &mut u64,
+    lane: &mut InputLane,
+    samples: Vec<f32>,
+    flush: bool,
+) -> bool {
+    record_samples(&mut lane.recorder, &samples);
+    // Place chunk ends on the wall clock from where this input's timeline is now
+    lane.fed_samples += samples.len() as u64;
+    let now = std::time::Instant::now();
+    let fed_ms = (lane.fed_samples * 1000 / 16000) as i64;
+    let ends_at = |speech: &SpeechChunk| {
+        let behind = (fed_ms - speech.start_ms - speech.duration_ms).max(0) as u64;
+        now.checked_sub(std::time::Duration::from_millis(behind))
+            .unwrap_or(now)
+    };
+    let mut conditioned = lane.conditioner.process(samples);
+    if flush {
+        conditioned.extend(lane.conditioner.flush());
+    }
+    let mut ready = lane.chunker.push(&conditioned);
+    if flush {
+        ready.extend(lane.chunker.flush());
+    }
+    for speech in ready {
+        let ends_at = ends_at(&speech);
+        if !send_chunk(
+            app,
+            chunk_tx,
+            pending_chunks,
+            speech,
+            chunk_index,
+            lane.source.clone(),
+            false,
+            ends_at,
+        )
+        .await
+        {
+            return false;
+        }
+    }
 
-                total_duration_ms += duration_ms;
+    // Partials are best effort: skip them while the consumer is behind, so they never
+    // delay final text
+    if lane.partials && !flush && pending_chunks.load(Ordering::SeqCst) == 0 {
+        if let Some(speech) = lane.chunker.partial() {
+            let grown = match lane.last_partial {
+                Some((start, duration)) if start == speech.start_ms => {
+                    speech.duration_ms - duration
+                }
+                _ => speech.duration_ms,
+            };
+            if grown >= PARTIAL_INTERVAL_MS {
+                lane.last_partial = Some((speech.start_ms, speech.duration_ms));
+                let ends_at = ends_at(&speech);
+                return send_chunk(
+                    app,
+                    chunk_tx,
+                    pending_chunks,
+                    speech,
+                    chunk_index,
+                    lane.source.clone(),
+                    true,
+                    ends_at,
+                )
+                .await;
+            }
+        }
+    }
+    true
+}
 
-                // Increment pending count and notify frontend
-                let count = pending_chunks.fetch_add(1, Ordering::SeqCst) + 1;
+/// How often each input reports its levels
+const METER_INTERVAL_MS: u64 = 100;
+
+/// Forward meter readings and warnings of one input to the UI
+fn emit_meter_events(
+    app: &AppHandle,
+    source: &AudioSource,
+    events: Vec<MeterEvent>,
+    processed_level: Option<f32>,
+    stats: Option<&AudioCaptureStats>,
+    notify: bool,
+) {
+    for event in events {
+        match event {
+            MeterEvent::Reading(reading) => {
                 let _ = app.emit(
-                    "transcription-status",
-                    serde_json::json!({ "status": "queued", "pending_chunks": count }),
+                    "audio-meter",
+                    AudioMeterEvent {
+                        source: source.as_str().to_string(),
+                        // Same scale as get_audio_level
+                        level: (processed_level.unwrap_or(reading.rms) * 3.0).min(1.0),
+                        rms: reading.rms,
+                        peak: reading.peak,
+                        clipped_samples: reading.clipped_samples,
+                        silent_ms: reading.silent_ms,
+                        clipping_ms: reading.clipping_ms,
+                        dropped_samples: stats.map_or(0, |s| s.dropped_samples),
+                        overruns: stats.map_or(0, |s| s.overruns),
+                    },
+                );
+            }
+            MeterEvent::Warning {
+                kind,
+                active,
+                duration_ms,
+            } => {
+                log::warn!(
+                    "Input {} {:?} warning {} after {} ms",
+                    source.as_str(),
+                    kind,
+                    if active { "raised" } else { "cleared" },
+                    duration_ms
                 );
 
-                // Send to consumer - if channel is closed, stop
-                if chunk_tx.send(chunk).await.is_err() {
-                    log::info!("Chunk channel closed, stopping audio producer");
-                    break;
+                #[cfg(desktop)]
+                if active && notify {
+                    use tauri_plugin_notification::NotificationExt;
+                    let (title, body) = match kind {
+                        MeterWarning::Silent => (
+                            "No sound from microphone",
+                            format!(
+                                "'{}' has been silent for {} s. Check that it isn't muted.",
+                                source.as_str(),
+                                duration_ms / 1000
+                            ),
+                        ),
+                        MeterWarning::Clipping => (
+                            "Microphone is clipping",
+                            format!(
+                                "'{}' is too loud to transcribe well. Lower its input gain.",
+                                source.as_str()
+                            ),
+                        ),
+                    };
+                    if let Err(e) = app.notification().builder().title(title).body(&body).show() {
+                        log::warn!("Failed to send native notification: {}", e);
+                    }
+                }
+                #[cfg(not(desktop))]
+                let _ = notify;
+
+                let _ = app.emit(
+                    "audio-warning",
+                    AudioWarningEvent {
+                        source: source.as_str().to_string(),
+                        kind,
+                        active,
+                        duration_ms,
+                    },
+                );
+            }
+        }
+    }
+}
+
+/// Tell the UI a finite capture source (replay) has delivered all of its audio
+fn emit_source_ended<'a>(app: &AppHandle, sources: impl Iterator<Item = &'a str>) {
+    let sources: Vec<&str> = sources.collect();
+    log::info!("Audio source {:?} finished", sources);
+    let _ = app.emit(
+        "audio-source-ended",
+        serde_json::json!({ "sources": sources }),
+    );
+}
+
+/// Audio producer: captures audio, cuts it into speech chunks at pauses, and sends them to the
+/// transcription channel. Runs independently so audio is never dropped while transcription is busy.
+/// Each lane takes one tap of the capture stream, in the order given to `select_channels`.
+/// If a lane has a recorder, every sample that advances its timeline is also written to it, so
+/// recording offsets line up with segment timestamps.
+/// Samples are conditioned (echo cancellation, noise suppression, gain) before chunking; the
+/// recordings keep them unprocessed.
+#[allow(clippy::too_many_arguments)]
+async fn run_audio_producer(
+    app: AppHandle,
+    audio_capture: Arc<Mutex<Option<Box<dyn CaptureSource>>>>,
+    is_recording: Arc<Mutex<bool>>,
+    is_paused: Arc<Mutex<bool>>,
+    config: TranscriptionConfig,
+    chunk_tx: tokio::sync::mpsc::Sender<AudioChunk>,
+    pending_chunks: Arc<AtomicUsize>,
+    mut lanes: Vec<InputLane>,
+    mut supervisor: InputSupervisor,
+) {
+    use crate::asr::resample_to_16khz;
+
+    let mut chunk_index: u64 = 0;
+    let mut source_ended = false;
+
+    log::info!(
+        "Audio producer started for {:?}, chunks {}-{}s split on pauses",
+        lanes.iter().map(|l| l.source.as_str()).collect::<Vec<_>>(),
+        config.min_chunk_secs,
+        config.max_chunk_secs
+    );
+
+    'capture: loop {
+        // Check if still recording
+        {
+            let recording = is_recording.lock().await;
+            if !*recording {
+                break;
+            }
+        }
+
+        // Check if paused - send speech in progress, then drain buffer to prevent buildup
+        {
+            let paused = is_paused.lock().await;
+            if *paused {
+                let mut capture_guard = audio_capture.lock().await;
+                if let Some(ref mut capture) = *capture_guard {
+                    let _ = capture.read();
+                }
+                drop(capture_guard);
+                // Audio held back by the conditioner still belongs before the pause
+                for lane in lanes.iter_mut() {
+                    if !feed_lane(
+                        &app,
+                        &chunk_tx,
+                        &pending_chunks,
+                        &mut chunk_index,
+                        lane,
+                        Vec::new(),
+                        true,
+                    )
+                    .await
+                    {
+                        break 'capture;
+                    }
                 }
+                tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+                continue;
+            }
+        }
+
+        // Get audio samples, then check for hot-plug changes (the old stream is drained first)
+        let (tap_samples, sample_rate, stats, failed, finished) = {
+            let mut capture_guard = audio_capture.lock().await;
+            if let Some(ref mut capture) = *capture_guard {
+                let samples = capture.read();
+                let sample_rate = capture.sample_rate();
+                let stats = capture.stats();
+                let finished = capture.is_finished();
+                let failed = capture
+                    .device()
+                    .map(|device| device.has_stream_error() || !device.is_recording());
+                (samples, sample_rate, stats, failed, finished)
             } else {
-                // Silence: discard chunk but keep overlap for continuity
-                let keep = (config.overlap_secs * 16000.0) as usize;
-                if accumulated_samples.len() > keep {
-                    accumulated_samples.drain(..(accumulated_samples.len() - keep));
+                (vec![], 16000, None, None, false)
+            }
+        };
+        // Devices are enumerated without holding the capture lock
+        let gap_ms = match failed {
+            Some(failed) if supervisor.scan_due(failed) => {
+                let names = tokio::task::spawn_blocking(AudioCapture::input_device_names)
+                    .await
+                    .unwrap_or_default();
+                let mut capture_guard = audio_capture.lock().await;
+                capture_guard
+                    .as_mut()
+                    .and_then(|capture| capture.device())
+                    .and_then(|device| supervisor.poll(&app, device, &names))
+            }
+            _ => None,
+        };
+
+        for (i, (lane, samples)) in lanes.iter_mut().zip(tap_samples).enumerate() {
+            if samples.is_empty() {
+                continue;
+            }
+            lane.meter.set_sample_rate(sample_rate);
+            let events = lane.meter.push(&samples);
+            emit_meter_events(
+                &app,
+                &lane.source,
+                events,
+                lane.conditioner.level(),
+                stats.as_ref(),
+                lane.notify_warnings,
+            );
+            let samples_16k = if sample_rate != 16000 {
+                match resample_to_16khz(&samples, sample_rate) {
+                    Ok(s) => s,
+                    Err(e) => {
+                        log::error!("Resampling failed: {}", e);
+                        continue;
+                    }
+                }
+            } else {
+                samples
+            };
+
+            // Send to consumer - if channel is closed, stop
+            if !feed_lane(
+                &app,
+                &chunk_tx,
+                &pending_chunks,
+                &mut chunk_index,
+                lane,
+                samples_16k,
+                false,
+            )
+            .await
+            {
+                log::info!("Chunk channel closed, stopping audio producer");
+                break 'capture;
+            }
+
+            // The level meter follows the first input
+            if i == 0 {
+                if let Some(level) = lane.conditioner.level() {
+                    if let Some(ref mut capture) = *audio_capture.lock().await {
+                        capture.set_processed_level(level);
+                    }
+                }
+            }
+        }
+
+        // A replayed file or signal has played out; the run stops like a live one
+        if finished && !source_ended {
+            source_ended = true;
+            emit_source_ended(&app, lanes.iter().map(|l| l.source.as_str()));
+        }
+
+        // After a device switch, close the interrupted utterances and pad the gap with
+        // silence so later chunks keep their wall-clock position (and line up with system audio)
+        if let Some(gap_ms) = gap_ms.filter(|&ms| ms > 0) {
+            for lane in lanes.iter_mut() {
+                for (samples, flush) in [
+                    (Vec::new(), true),
+                    (vec![0.0f32; gap_ms as usize * 16], false),
+                ] {
+                    if !feed_lane(
+                        &app,
+                        &chunk_tx,
+                        &pending_chunks,
+                        &mut chunk_index,
+                        lane,
+                        samples,
+                        flush,
+                    )
+                    .await
+                    {
+                        break 'capture;
+                    }
                 }
-                total_duration_ms += (chunk_samples as f32 / 16.0) as i64;
             }
         }
 
         tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
     }
 
+    // Transcribe the utterances that were in progress when recording stopped
+    for mut lane in lanes {
+        feed_lane(
+            &app,
+            &chunk_tx,
+            &pending_chunks,
+            &mut chunk_index,
+            &mut lane,
+            Vec::new(),
+            true,
+        )
+        .await;
+        finish_recorder(lane.recorder);
+    }
+
     log::info!("Audio producer stopped after {} chunks", chunk_index);
     // Dropping chunk_tx here closes the channel, signalling the consumer to drain and stop
 }
 
-/// System audio producer (macOS only): captures all application output via ScreenCaptureKit.
+/// System audio producer (macOS/Linux): captures all application output via ScreenCaptureKit
+/// or a PulseAudio/PipeWire monitor source.
 /// Shares the same mpsc channel as the mic producer; chunks are tagged AudioSource::System.
-#[cfg(target_os = "macos")]
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+#[allow(clippy::too_many_arguments)]
 async fn run_system_audio_producer(
     app: AppHandle,
+    mut capture: Box<dyn CaptureSource>,
     is_recording: Arc<Mutex<bool>>,
     is_paused: Arc<Mutex<bool>>,
     config: TranscriptionConfig,
     chunk_tx: tokio::sync::mpsc::Sender<AudioChunk>,
     pending_chunks: Arc<AtomicUsize>,
+    audio_dir: Option<std::path::PathBuf>,
+    echo_reference: Option<Arc<EchoReference>>,
+    partials: bool,
 ) {
-    let mut capture = SystemAudioCapture::new();
-
     if let Err(e) = capture.start() {
         log::warn!("System audio capture unavailable: {e}. Remote audio will not be transcribed.");
         return;
     }
 
-    let chunk_samples = (config.chunk_duration_secs * 16000.0) as usize;
-    let mut accumulated_samples: Vec<f32> = Vec::with_capacity(chunk_samples * 2);
+    if let Some(ref reference) = echo_reference {
+        reference.set_active(true);
+    }
+
+    // System audio is transcribed as captured: no echo cancellation or pre-processing
+    let mut lane = InputLane {
+        // Only create the file once capture is actually running
+        recorder: open_recorder(audio_dir.as_deref(), &AudioSource::System),
+        chunker: SpeechChunker::new(&config),
+        conditioner: MicConditioner::new(
+            None,
+            DspConfig {
+                high_pass: false,
+                noise_suppression: false,
+                auto_gain: false,
+            },
+        ),
+        // Silence is normal on the system stream, so it only reports levels
+        meter: LevelMeter::new(capture.sample_rate(), METER_INTERVAL_MS, 0),
+        notify_warnings: false,
+        partials,
+        last_partial: None,
+        fed_samples: 0,
+        source: AudioSource::System,
+    };
     let mut chunk_index: u64 = 0;
-    let mut total_duration_ms: i64 = 0;
+    let mut source_ended = false;
 
     log::info!("System audio producer started");
 
@@ -512,401 +1618,1489 @@ async fn run_system_audio_producer(
         {
             let paused = is_paused.lock().await;
             if *paused {
-                let _ = capture.get_samples(); // drain to avoid stale audio on resume
-                accumulated_samples.clear();
+                let _ = capture.read(); // drain to avoid stale audio on resume
+                if !feed_lane(
+                    &app,
+                    &chunk_tx,
+                    &pending_chunks,
+                    &mut chunk_index,
+                    &mut lane,
+                    Vec::new(),
+                    true,
+                )
+                .await
+                {
+                    break;
+                }
                 tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
                 continue;
             }
         }
 
-        let samples = capture.get_samples();
+        let mut samples = capture.read().into_iter().next().unwrap_or_default();
+        // Live system capture is 16 kHz already; a replayed file may not be
+        let sample_rate = capture.sample_rate();
+        let events = lane.meter.push(&samples);
+        emit_meter_events(&app, &lane.source, events, None, None, false);
+        if sample_rate != 16000 && !samples.is_empty() {
+            match crate::asr::resample_to_16khz(&samples, sample_rate) {
+                Ok(resampled) => samples = resampled,
+                Err(e) => {
+                    log::error!("Resampling failed: {}", e);
+                    samples.clear();
+                }
+            }
+        }
         if !samples.is_empty() {
-            accumulated_samples.extend(samples);
+            if let Some(ref reference) = echo_reference {
+                reference.push(&samples);
+            }
+            if !feed_lane(
+                &app,
+                &chunk_tx,
+                &pending_chunks,
+                &mut chunk_index,
+                &mut lane,
+                samples,
+                false,
+            )
+            .await
+            {
+                log::info!("Chunk channel closed, stopping system audio producer");
+                break;
+            }
         }
 
-        if accumulated_samples.len() >= chunk_samples {
-            let rms: f32 = {
-                let sum: f32 = accumulated_samples[..chunk_samples]
-                    .iter()
-                    .map(|s| s * s)
-                    .sum();
-                (sum / chunk_samples as f32).sqrt()
-            };
+        if capture.is_finished() && !source_ended {
+            source_ended = true;
+            emit_source_ended(&app, [lane.source.as_str()].into_iter());
+        }
 
-            if rms >= config.silence_threshold {
-                let chunk_data: Vec<f32> = accumulated_samples.drain(..chunk_samples).collect();
-                let duration_ms = (chunk_data.len() as f32 / 16.0) as i64;
-                chunk_index += 1;
+        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+    }
 
-                let chunk = AudioChunk {
-                    samples: chunk_data,
-                    start_ms: total_duration_ms,
-                    duration_ms,
-                    chunk_index,
-                    source: AudioSource::System,
-                };
+    let _ = capture.stop();
+    if let Some(ref reference) = echo_reference {
+        reference.set_active(false);
+    }
+    feed_lane(
+        &app,
+        &chunk_tx,
+        &pending_chunks,
+        &mut chunk_index,
+        &mut lane,
+        Vec::new(),
+        true,
+    )
+    .await;
+    finish_recorder(lane.recorder);
+    log::info!("System audio producer stopped after {} chunks", chunk_index);
+}
 
-                total_duration_ms += duration_ms;
+enum EchoCheck {
+    Unique,
+    /// Mic chunk repeats an earlier system chunk (its first segment id given)
+    DropCurrent(String),
+    /// System chunk is the original of an earlier mic chunk (its segment ids given)
+    #[cfg(any(target_os = "macos", target_os = "linux"))]
+    ReplaceEarlier(Vec<String>),
+}
 
-                let count = pending_chunks.fetch_add(1, Ordering::SeqCst) + 1;
-                let _ = app.emit(
-                    "transcription-status",
-                    serde_json::json!({ "status": "queued", "pending_chunks": count }),
-                );
+/// Last line of defence against speaker echo: the same remote speech transcribed from both
+/// the system stream and the mic. The system copy is always the one kept.
+/// Chunks are compared on the wall clock (`AudioChunk::ends_at`), not on their inputs'
+/// timelines.
+#[derive(Default)]
+struct EchoDuplicateGuard {
+    /// Instant clock spans are measured from (the first chunk's end)
+    epoch: Option<std::time::Instant>,
+    /// (segment ids of the chunk, source, start ms, end ms, text)
+    recent: std::collections::VecDeque<(Vec<String>, AudioSource, i64, i64, String)>,
+}
 
-                if chunk_tx.send(chunk).await.is_err() {
-                    log::info!("Chunk channel closed, stopping system audio producer");
-                    break;
+impl EchoDuplicateGuard {
+    /// How far back segments are compared (chunks from the two sources arrive out of order)
+    const WINDOW_MS: i64 = 20_000;
+
+    /// Start and end of a chunk on the wall clock (ms from `epoch`)
+    fn clock_span(&mut self, chunk: &AudioChunk) -> (i64, i64) {
+        let epoch = *self.epoch.get_or_insert(chunk.ends_at);
+        let end = if chunk.ends_at >= epoch {
+            chunk.ends_at.duration_since(epoch).as_millis() as i64
+        } else {
+            -(epoch.duration_since(chunk.ends_at).as_millis() as i64)
+        };
+        (end - chunk.duration_ms, end)
+    }
+
+    fn check(&mut self, chunk: &AudioChunk, text: &str) -> EchoCheck {
+        let span = self.clock_span(chunk);
+        self.recent.retain(|r| r.3 >= span.0 - Self::WINDOW_MS);
+
+        let matched = self.recent.iter().position(|(_, source, s, e, t)| {
+            source.is_system() != chunk.source.is_system()
+                && crate::audio::aec::is_echo_pair((*s, *e), t, span, text)
+        });
+        let Some(i) = matched else {
+            return EchoCheck::Unique;
+        };
+        match chunk.source {
+            #[cfg(any(target_os = "macos", target_os = "linux"))]
+            AudioSource::System => {
+                let ids = self.recent.remove(i).map(|r| r.0).unwrap_or_default();
+                EchoCheck::ReplaceEarlier(ids)
+            }
+            // Any local input
+            _ => EchoCheck::DropCurrent(self.recent[i].0.first().cloned().unwrap_or_default()),
+        }
+    }
+
+    fn remember(&mut self, ids: Vec<String>, chunk: &AudioChunk, text: &str) {
+        let (start, end) = self.clock_span(chunk);
+        self.recent
+            .push_back((ids, chunk.source.clone(), start, end, text.to_string()));
+    }
+}
+
+/// ASR segments of a chunk on the recording timeline (the result is already offset to the
+/// chunk start), kept inside the chunk. Backends that don't time their output give one
+/// segment spanning the chunk.
+pub(crate) fn timeline_segments(
+    result: asr::TranscriptionResult,
+    start_ms: i64,
+    end_ms: i64,
+) -> Vec<asr::TranscriptionSegment> {
+    if result.segments.is_empty() {
+        let text = result.full_text.trim().to_string();
+        if text.is_empty() {
+            return Vec::new();
+        }
+        return vec![asr::TranscriptionSegment {
+            text,
+            start_ms,
+            end_ms,
+            words: Vec::new(),
+        }];
+    }
+    result
+        .segments
+        .into_iter()
+        .map(|segment| {
+            let start = segment.start_ms.clamp(start_ms, end_ms);
+            let words = segment
+                .words
+                .into_iter()
+                .map(|word| {
+                    let word_start = word.start_ms.clamp(start_ms, end_ms);
+                    asr::WordTiming {
+                        start_ms: word_start,
+                        end_ms: word.end_ms.clamp(word_start, end_ms),
+                        ..word
+                    }
+                })
+                .collect();
+            asr::TranscriptionSegment {
+                start_ms: start,
+                end_ms: segment.end_ms.clamp(start, end_ms),
+                text: segment.text,
+                words,
+            }
+        })
+        .collect()
+}
+
+/// Last segment of a source's previous chunk, kept to stitch the next chunk onto
+struct ChunkTail {
+    segment_id: String,
+    segment: asr::TranscriptionSegment,
+    chunk_end_ms: i64,
+    language: Option<String>,
+    language_probability: Option<f32>,
+}
+
+/// When a chunk repeats the end of the previous chunk of its source (speech split mid-way),
+/// drop the words both transcribed from the chunk's segments. Returns the previous chunk's
+/// last segment trimmed to end where this chunk takes over, if that changes it; no words left
+/// means it was entirely repeated.
+fn stitch_segments(
+    tail: Option<&ChunkTail>,
+    segments: &mut Vec<asr::TranscriptionSegment>,
+    chunk_start_ms: i64,
+) -> Option<asr::TranscriptionSegment> {
+    let tail = tail.filter(|t| t.chunk_end_ms > chunk_start_ms)?;
+    let words: Vec<asr::WordTiming> = segments
+        .iter()
+        .flat_map(|s| s.words.iter().cloned())
+        .collect();
+    if tail.segment.words.is_empty() || words.is_empty() {
+        // Without word timings, join on the text alone
+        let current: Vec<&str> = segments.iter().map(|s| s.text.as_str()).collect();
+        let join = stitch::align_text(&tail.segment.text, &current.join(" "))?;
+        stitch::drop_leading_text(segments, join.skip_current);
+        return (join.keep_previous < stitch::text_words(&tail.segment.text).len()).then(|| {
+            let mut previous = tail.segment.clone();
+            stitch::truncate_text(&mut previous, join.keep_previous);
+            previous
+        });
+    }
+    let join = stitch::align(
+        &tail.segment.words,
+        &words,
+        chunk_start_ms,
+        tail.chunk_end_ms,
+    );
+    stitch::drop_leading_words(segments, join.skip_current);
+    (join.keep_previous < tail.segment.words.len()).then(|| {
+        let mut previous = tail.segment.clone();
+        stitch::truncate_words(&mut previous, join.keep_previous);
+        previous
+    })
+}
+
+/// Embed a live segment in the background (non-blocking), replacing any earlier embedding
+fn spawn_segment_embedding(
+    embedding_model: &Arc<Mutex<Option<EmbeddingModel>>>,
+    db: &Arc<Database>,
+    meeting_title: &str,
+    segment_id: String,
+    time_label: String,
+    text: String,
+) {
+    let embedding_model = embedding_model.clone();
+    let db = db.clone();
+    let meeting_title = meeting_title.to_string();
+    tokio::spawn(async move {
+        let model_guard = embedding_model.lock().await;
+        if let Some(ref m) = *model_guard {
+            let enriched = embeddings::enrich_segment(&meeting_title, &time_label, &text);
+            match m.embed(&enriched) {
+                Ok(emb) => {
+                    if let Err(e) = db.insert_embedding(&segment_id, &emb) {
+                        log::error!("Failed to store embedding: {}", e);
+                    }
                 }
+                Err(e) => log::error!("Embedding failed: {}", e),
+            }
+        }
+    });
+}
+
+/// Persist a stitched tail from `stitch_segments`, deleting it if no text is left. Its
+/// enhanced text and embedding describe the old text, so both are dropped; the caller embeds
+/// it again.
+fn store_stitched_tail(db: &Database, segment_id: &str, segment: &asr::TranscriptionSegment) {
+    let result = if segment.text.trim().is_empty() {
+        db.delete_segment(segment_id)
+    } else {
+        db.update_segment_text(segment_id, &segment.text)
+            .and_then(|_| db.update_segment_end_ms(segment_id, segment.end_ms))
+            .and_then(|_| db.insert_segment_words(segment_id, &word_rows(&segment.words)))
+            .and_then(|_| db.update_segment_enhanced_text(segment_id, None))
+            .and_then(|_| db.delete_embedding(segment_id))
+    };
+    if let Err(e) = result {
+        log::error!("Failed to stitch segment {}: {}", segment_id, e);
+    }
+}
+
+fn word_rows(words: &[asr::WordTiming]) -> Vec<WordRow> {
+    words
+        .iter()
+        .map(|w| WordRow {
+            word: w.text.clone(),
+            start_ms: w.start_ms,
+            end_ms: w.end_ms,
+            probability: w.probability,
+        })
+        .collect()
+}
+
+/// Restore punctuation and casing in segments that came out without any (CTC models)
+pub(crate) fn restore_punctuation(
+    model: &PunctuationModel,
+    segments: &mut [asr::TranscriptionSegment],
+) {
+    for segment in segments
+        .iter_mut()
+        .filter(|s| punctuation::needs_restoration(&s.text))
+    {
+        if let Err(e) = model.restore_segment(segment) {
+            log::warn!("Punctuation restoration failed: {}", e);
+        }
+    }
+}
+
+/// Initial prompt for the next chunk of a meeting: the meeting title (unless it is still
+/// `default_title`), the vocabulary from settings and the meeting, and the preceding text of
+/// the same source
+pub(crate) fn decoder_prompt(
+    db: &Database,
+    meeting_id: &str,
+    default_title: Option<&str>,
+    settings: &Settings,
+    previous: &str,
+) -> String {
+    let mut vocabulary = settings.vocabulary.clone();
+    match db.get_meeting_vocabulary(meeting_id) {
+        Ok(terms) => vocabulary.extend(prompt::parse_terms(terms.as_deref().unwrap_or(""))),
+        Err(e) => log::warn!("Failed to read meeting vocabulary: {}", e),
+    }
+    let title = db
+        .get_meeting(meeting_id)
+        .ok()
+        .flatten()
+        .map(|m| m.title)
+        .filter(|title| Some(title.as_str()) != default_title);
+    let previous = if settings.context_prompting {
+        previous
+    } else {
+        ""
+    };
+    prompt::build_prompt(&vocabulary, title.as_deref(), previous)
+}
+
+/// Language to transcribe a chunk of `source` in (None = an imported file)
+pub(crate) fn source_language(settings: &Settings, source: Option<&str>) -> asr::LanguageOptions {
+    language::chunk_language(
+        &settings.language,
+        &settings.source_languages,
+        &settings.auto_languages,
+        source,
+    )
+}
+
+/// Transcribe with a decoder prompt, falling back to no prompt if the decoder looped or
+/// copied the prompt instead of transcribing
+pub(crate) async fn transcribe_prompted(
+    engine: &TranscriptionEngine,
+    samples: &[f32],
+    offset_ms: i64,
+    prompt: &str,
+    language: &asr::LanguageOptions,
+) -> anyhow::Result<asr::TranscriptionResult> {
+    let result = engine
+        .transcribe_with_language(samples, offset_ms, prompt, language)
+        .await?;
+    if !prompt.is_empty() && prompt::is_hallucination(&result.full_text, prompt) {
+        log::warn!(
+            "Prompted transcription looped or copied its prompt, retrying without: {:?}",
+            result.full_text
+        );
+        return engine
+            .transcribe_with_language(samples, offset_ms, "", language)
+            .await;
+    }
+    Ok(result)
+}
+
+/// Translation mode and target language from the settings ("whisper" translates into
+/// English whatever the target)
+pub(crate) fn translation_target(settings: &Settings) -> (TranslationMode, String) {
+    let mode = TranslationMode::parse(&settings.translation_mode);
+    let target = match mode {
+        TranslationMode::Whisper => "en".to_string(),
+        _ => settings.translation_language.trim().to_lowercase(),
+    };
+    (mode, target)
+}
+
+/// In "whisper" translation mode, Whisper's English translation of a chunk that isn't in
+/// English, for splitting among the chunk's segments once they are stitched. Empty otherwise.
+pub(crate) async fn whisper_translation(
+    engine: &TranscriptionEngine,
+    mode: TranslationMode,
+    samples: &[f32],
+    start_ms: i64,
+    language: &asr::LanguageOptions,
+    detected: Option<&str>,
+    segments: &[asr::TranscriptionSegment],
+) -> Vec<asr::TranscriptionSegment> {
+    if mode != TranslationMode::Whisper
+        || segments.is_empty()
+        || !translation::needs_translation(detected, "en")
+    {
+        return Vec::new();
+    }
+    // Translate from the language the transcript was in rather than detecting it again
+    let options = asr::LanguageOptions {
+        language: detected
+            .map(str::to_string)
+            .or_else(|| language.language.clone()),
+        candidates: language.candidates.clone(),
+    };
+    match engine
+        .translate_to_english(samples, start_ms, &options)
+        .await
+    {
+        Ok(Some(result)) => result.segments,
+        Ok(None) => {
+            log::debug!("The transcription backend can't translate");
+            Vec::new()
+        }
+        Err(e) => {
+            log::error!("Translation at {} ms failed: {}", start_ms, e);
+            Vec::new()
+        }
+    }
+}
+
+/// Whisper's translation of a chunk split among its segments, for passes that transcribe
+/// and commit one chunk at a time
+async fn whisper_translations(
+    engine: &RwLock<Option<TranscriptionEngine>>,
+    mode: TranslationMode,
+    samples: &[f32],
+    start_ms: i64,
+    language: &asr::LanguageOptions,
+    detected: Option<&str>,
+    segments: &[asr::TranscriptionSegment],
+) -> Vec<Option<String>> {
+    let translated = match engine.read().await.as_ref() {
+        Some(eng) => {
+            whisper_translation(eng, mode, samples, start_ms, language, detected, segments).await
+        }
+        None => return Vec::new(),
+    };
+    split_translation(segments, &translated)
+}
+
+/// Split a chunk's translation among its segments
+fn split_translation(
+    segments: &[asr::TranscriptionSegment],
+    translated: &[asr::TranscriptionSegment],
+) -> Vec<Option<String>> {
+    let spans: Vec<(i64, i64)> = segments.iter().map(|s| (s.start_ms, s.end_ms)).collect();
+    translation::assign_translations(&spans, translated)
+}
+
+/// Store a segment's translation and send it to the frontend
+fn store_translation(
+    app: &AppHandle,
+    db: &Database,
+    segment_id: String,
+    text: String,
+    language: String,
+) {
+    if let Err(e) = db.update_segment_translation(&segment_id, &text) {
+        log::error!("Failed to store translation: {}", e);
+        return;
+    }
+    let event = crate::transcription::TranslationEvent {
+        segment_id,
+        text,
+        language,
+    };
+    if let Err(e) = app.emit("segment-translation", &event) {
+        log::error!("Failed to emit translation: {}", e);
+    }
+}
+
+/// Translate a stored segment with the LLM in the background
+fn spawn_llm_translation(
+    app: AppHandle,
+    db: Arc<Database>,
+    settings: Arc<Mutex<Settings>>,
+    segment_id: String,
+    text: String,
+    target: String,
+) {
+    tokio::spawn(async move {
+        let Some(provider) = background_llm_provider(&*settings.lock().await) else {
+            log::debug!("No LLM configured, skipping translation");
+            return;
+        };
+        match LlmClient::new(provider).translate(&text, &target).await {
+            Ok(translation) if !translation.is_empty() => {
+                store_translation(&app, &db, segment_id, translation, target)
+            }
+            Ok(_) => {}
+            Err(e) => log::warn!("Translation failed (non-critical): {}", e),
+        }
+    });
+}
+
+/// ID shared by the partial results of the utterance starting at `chunk.start_ms`, so the UI
+/// can update them in place and drop them when the final segment arrives
+fn partial_segment_id(meeting_id: &str, chunk: &AudioChunk) -> String {
+    format!(
+        "{}-partial-{}-{}",
+        meeting_id,
+        chunk.source.as_str(),
+        chunk.start_ms
+    )
+}
+
+/// Transcription consumer: runs a pool of workers over the audio chunks from the channel and
+/// commits their results in the order the chunks were queued.
+/// Processes all queued chunks even after recording stops (drain-not-discard).
+/// Partial chunks are only shown; the transcript, DB and embeddings get final text only.
+#[allow(clippy::too_many_arguments)]
+async fn run_transcription_consumer(
+    app: AppHandle,
+    engine: Arc<RwLock<Option<TranscriptionEngine>>>,
+    transcript: Arc<Mutex<Vec<TranscriptSegment>>>,
+    db: Arc<Database>,
+    meeting_id: String,
+    embedding_model: Arc<Mutex<Option<EmbeddingModel>>>,
+    punctuation_model: Arc<Mutex<Option<PunctuationModel>>>,
+    meeting_title: String,
+    settings: Arc<Mutex<Settings>>,
+    chunk_rx: tokio::sync::mpsc::Receiver<AudioChunk>,
+    pending_chunks: Arc<AtomicUsize>,
+    worker_stats: Arc<std::sync::Mutex<Vec<WorkerStats>>>,
+) {
+    use crate::transcription::TranscriptionEvent;
+
+    let mut segment_counter: u64 = 0;
+    let mut echo_guard = EchoDuplicateGuard::default();
+    // Partial results on screen, waiting for their final segment
+    let mut shown_partials = std::collections::HashSet::new();
+    // Last segment of each source, for stitching the next chunk onto it
+    let mut chunk_tails: std::collections::HashMap<String, ChunkTail> =
+        std::collections::HashMap::new();
+    let previous_text = Arc::new(std::sync::Mutex::new(std::collections::HashMap::new()));
+
+    // Backends that can't decode in parallel (Parakeet) get one worker
+    let parallel = engine
+        .read()
+        .await
+        .as_ref()
+        .is_none_or(TranscriptionEngine::parallel);
+    let (workers, threads) = {
+        let settings = settings.lock().await;
+        workers::worker_plan(
+            if parallel {
+                settings.transcription_workers
             } else {
-                let keep = (config.overlap_secs * 16000.0) as usize;
-                if accumulated_samples.len() > keep {
-                    accumulated_samples.drain(..(accumulated_samples.len() - keep));
+                1
+            },
+            settings.transcription_threads,
+            num_cpus::get(),
+        )
+    };
+    if let Some(eng) = engine.write().await.as_mut() {
+        eng.set_threads(threads);
+    }
+    *worker_stats.lock().unwrap() = (0..workers)
+        .map(|worker| WorkerStats::new(worker, threads))
+        .collect();
+
+    let shedder = Arc::new(std::sync::Mutex::new(LoadShedder::new(
+        settings.lock().await.load_shedding.clone(),
+    )));
+    let spill_dir = std::env::temp_dir()
+        .join("phantomear-spill")
+        .join(&meeting_id);
+
+    let (results_tx, mut results_rx) = tokio::sync::mpsc::unbounded_channel();
+    let pool = WorkerPool {
+        app: app.clone(),
+        engine: engine.clone(),
+        fallback_engine: Arc::new(RwLock::new(None)),
+        threads,
+        db: db.clone(),
+        meeting_id: meeting_id.clone(),
+        meeting_title: meeting_title.clone(),
+        settings: settings.clone(),
+        punctuation_model,
+        queue: Arc::new(Mutex::new(ChunkQueue::new(chunk_rx, spill_dir.clone()))),
+        pending_chunks,
+        busy: Arc::new(AtomicUsize::new(0)),
+        previous_text: previous_text.clone(),
+        stats: worker_stats,
+        shedder: shedder.clone(),
+        results: results_tx,
+    };
+    for worker in 0..workers {
+        tauri::async_runtime::spawn(run_transcription_worker(pool.clone(), worker));
+    }
+    // The channel closes once every worker has finished
+    drop(pool);
+
+    log::info!(
+        "Transcription consumer started with {} workers of {} threads",
+        workers,
+        threads
+    );
+
+    let mut in_order = workers::InOrder::new();
+    while let Some((seq, transcribed)) = results_rx.recv().await {
+        // Lost chunks only hold their place in the order
+        for TranscribedChunk {
+            chunk,
+            result: transcription_result,
+            catch_up,
+            committed: _committed,
+        } in in_order.push(seq, transcribed).into_iter().flatten()
+        {
+            match transcription_result {
+                Ok(ChunkTranscript {
+                    segments: mut asr_segments,
+                    language,
+                    language_probability,
+                    translation: translated,
+                }) => {
+                    // Each ASR segment becomes its own row with its own timing, minus the words
+                    // the previous chunk of this source already has. A spilled chunk is older
+                    // than the tail; its words can't overlap it.
+                    let stitched_tail = stitch_segments(
+                        chunk_tails.get(chunk.source.as_str()).filter(|_| !catch_up),
+                        &mut asr_segments,
+                        chunk.start_ms,
+                    );
+                    asr_segments.retain(|s| !s.text.trim().is_empty());
+                    let text = asr_segments
+                        .iter()
+                        .map(|s| s.text.trim())
+                        .collect::<Vec<_>>()
+                        .join(" ");
+                    let partial_id = partial_segment_id(&meeting_id, &chunk);
+                    if chunk.partial {
+                        if !text.is_empty() {
+                            let event = TranscriptionEvent {
+                                id: partial_id.clone(),
+                                text,
+                                start_ms: chunk.start_ms,
+                                end_ms: chunk.start_ms + chunk.duration_ms,
+                                is_partial: true,
+                                source: chunk.source.as_str().to_string(),
+                                partial_id: None,
+                                language: language.clone(),
+                                language_probability,
+                            };
+                            if let Err(e) = app.emit("transcription", &event) {
+                                log::error!("Failed to emit partial transcription: {}", e);
+                            }
+                            shown_partials.insert(partial_id);
+                        }
+                        continue;
+                    }
+                    // Later chunks of this source are prompted with this text, unless it looped
+                    if prompt::is_hallucination(&text, "") {
+                        previous_text.lock().unwrap().remove(chunk.source.as_str());
+                    } else if !text.is_empty() {
+                        previous_text
+                            .lock()
+                            .unwrap()
+                            .insert(chunk.source.as_str().to_string(), text.clone());
+                    }
+
+                    // The final segment replaces the partial; if there is none, clear it
+                    let had_partial = shown_partials.remove(&partial_id);
+                    if had_partial && text.is_empty() {
+                        let _ = app.emit(
+                            "segment-removed",
+                            serde_json::json!({ "segment_id": partial_id }),
+                        );
+                    }
+                    if !text.is_empty() && settings.lock().await.echo_cancellation {
+                        match echo_guard.check(&chunk, &text) {
+                            EchoCheck::Unique => {}
+                            EchoCheck::DropCurrent(original) => {
+                                log::info!(
+                                    "Dropping mic chunk {} as echo of {}",
+                                    chunk.chunk_index,
+                                    original
+                                );
+                                if had_partial {
+                                    let _ = app.emit(
+                                        "segment-removed",
+                                        serde_json::json!({ "segment_id": partial_id }),
+                                    );
+                                }
+                                continue;
+                            }
+                            #[cfg(any(target_os = "macos", target_os = "linux"))]
+                            EchoCheck::ReplaceEarlier(echoed) => {
+                                log::info!(
+                                    "Removing segments {:?} as echo of system audio",
+                                    echoed
+                                );
+                                for id in &echoed {
+                                    if let Err(e) = db.delete_segment(id) {
+                                        log::error!("Failed to remove echoed segment: {}", e);
+                                    }
+                                    let _ = app.emit(
+                                        "segment-removed",
+                                        serde_json::json!({ "segment_id": id }),
+                                    );
+                                }
+                                transcript.lock().await.retain(|s| !echoed.contains(&s.id));
+                                chunk_tails.retain(|_, tail| !echoed.contains(&tail.segment_id));
+                            }
+                        }
+                    }
+
+                    // End the previous chunk where this one takes over
+                    if let (Some(previous), Some(tail)) =
+                        (stitched_tail, chunk_tails.get(chunk.source.as_str()))
+                    {
+                        let id = tail.segment_id.clone();
+                        store_stitched_tail(&db, &id, &previous);
+                        if previous.text.trim().is_empty() {
+                            // AI tasks still running for it find it gone by id and skip it
+                            transcript.lock().await.retain(|s| s.id != id);
+                            let _ = app
+                                .emit("segment-removed", serde_json::json!({ "segment_id": id }));
+                        } else {
+                            let mut time_label = format_time(previous.start_ms as u64);
+                            if let Some(s) = transcript.lock().await.iter_mut().find(|s| s.id == id)
+                            {
+                                s.text = previous.text.clone();
+                                s.end_ms = previous.end_ms as u64;
+                                time_label = s.time.clone();
+                            }
+                            spawn_segment_embedding(
+                                &embedding_model,
+                                &db,
+                                &meeting_title,
+                                id.clone(),
+                                time_label,
+                                previous.text.clone(),
+                            );
+                            // A final event for an existing id replaces it
+                            let _ = app.emit(
+                                "transcription",
+                                &TranscriptionEvent {
+                                    id,
+                                    text: previous.text,
+                                    start_ms: previous.start_ms,
+                                    end_ms: previous.end_ms,
+                                    is_partial: false,
+                                    source: chunk.source.as_str().to_string(),
+                                    partial_id: None,
+                                    language: tail.language.clone(),
+                                    language_probability: tail.language_probability,
+                                },
+                            );
+                        }
+                    }
+                    let last_segment = asr_segments.last().cloned();
+
+                    // Whisper translates the chunk's audio; the LLM each stored segment's text
+                    let skip_ai = shedder.lock().unwrap().is_engaged(SheddingAction::SkipAi);
+                    let (mut translation_mode, translation_language) =
+                        translation_target(&*settings.lock().await);
+                    if skip_ai {
+                        translation_mode = TranslationMode::Off;
+                    }
+                    let mut translations =
+                        split_translation(&asr_segments, &translated).into_iter();
+
+                    let mut chunk_segment_ids = Vec::new();
+                    for asr_segment in asr_segments {
+                        segment_counter += 1;
+
+                        let seg_id = format!("{}-seg-{}", meeting_id, segment_counter);
+                        let time_label = format_time(asr_segment.start_ms as u64);
+                        let text = asr_segment.text;
+
+                        let source_str = chunk.source.as_str().to_string();
+
+                        chunk_segment_ids.push(seg_id.clone());
+
+                        let event = TranscriptionEvent {
+                            id: seg_id.clone(),
+                            text: text.clone(),
+                            start_ms: asr_segment.start_ms,
+                            end_ms: asr_segment.end_ms,
+                            is_partial: false,
+                            source: source_str.clone(),
+                            partial_id: had_partial.then(|| partial_id.clone()),
+                            language: language.clone(),
+                            language_probability,
+                        };
+
+                        // Store in in-memory transcript
+                        {
+                            let mut transcript_guard = transcript.lock().await;
+                            transcript_guard.push(TranscriptSegment {
+                                id: event.id.clone(),
+                                time: time_label.clone(),
+                                text: event.text.clone(),
+                                timestamp_ms: asr_segment.start_ms as u64,
+                                end_ms: asr_segment.end_ms as u64,
+                                source: source_str.clone(),
+                                language: language.clone(),
+                                translation: None,
+                            });
+                        }
+
+                        // Persist segment to DB
+                        let seg_id_for_emb = seg_id.clone();
+                        let time_label_for_emb = time_label.clone();
+                        let text_for_emb = text.clone();
+                        if let Err(e) = db.insert_segment(&SegmentRow {
+                            id: seg_id,
+                            meeting_id: meeting_id.clone(),
+                            time_label,
+                            text: text.clone(),
+                            timestamp_ms: asr_segment.start_ms,
+                            end_ms: Some(asr_segment.end_ms),
+                            speaker_id: None,
+                            source: Some(source_str.clone()),
+                            enhanced_text: None,
+                            is_question: false,
+                            question_answer: None,
+                            language: language.clone(),
+                            language_probability,
+                            translation: None,
+                        }) {
+                            log::error!("Failed to persist segment: {}", e);
+                        } else if let Err(e) =
+                            db.insert_segment_words(&seg_id_for_emb, &word_rows(&asr_segment.words))
+                        {
+                            log::error!("Failed to persist segment words: {}", e);
+                        }
+
+                        // Generate embedding in background (non-blocking)
+                        spawn_segment_embedding(
+                            &embedding_model,
+                            &db,
+                            &meeting_title,
+                            seg_id_for_emb,
+                            time_label_for_emb,
+                            text_for_emb,
+                        );
+
+                        // AI processing: auto-title, transcript enhancement, question detection,
+                        // unless load is being shed
+                        if !skip_ai {
+                            let current_seg_counter = segment_counter;
+                            let seg_id_for_ai = event.id.clone();
+                            let settings_for_ai = settings.clone();
+                            let db_for_ai = db.clone();
+                            let mid_for_ai = meeting_id.clone();
+                            let app_for_ai = app.clone();
+                            let transcript_for_ai = transcript.clone();
+                            tokio::spawn(async move {
+                                let settings = settings_for_ai.lock().await;
+                                let enhance_transcripts = settings.enhance_transcripts;
+                                let detect_questions = settings.detect_questions;
+                                log::info!(
+                                    "AI Processing check - enhance: {}, detect_questions: {}",
+                                    enhance_transcripts,
+                                    detect_questions
+                                );
+                                drop(settings);
+
+                                let llm_provider = {
+                                    let settings = settings_for_ai.lock().await;
+                                    match settings.llm_provider.as_str() {
+                                        "openai" => settings
+                                            .openai_api_key
+                                            .clone()
+                                            .map(|key| LlmProvider::OpenAI { api_key: key }),
+                                        _ => {
+                                            let url =
+                                                settings.ollama_url.clone().unwrap_or_else(|| {
+                                                    "http://localhost:11434".to_string()
+                                                });
+                                            let model = settings
+                                                .ollama_model
+                                                .clone()
+                                                .unwrap_or_else(|| "llama3.2".to_string());
+                                            Some(LlmProvider::Ollama { url, model })
+                                        }
+                                    }
+                                };
+
+                                if let Some(provider) = llm_provider {
+                                    let client = LlmClient::new(provider);
+
+                                    // Auto-title exactly once at segment 10
+                                    if current_seg_counter == 10 {
+                                        let transcript = transcript_for_ai.lock().await;
+                                        let title_transcript: String = transcript
+                                            .iter()
+                                            .take(10)
+                                            .map(|s| s.text.clone())
+                                            .collect::<Vec<_>>()
+                                            .join(" ");
+                                        drop(transcript);
+
+                                        if let Ok(title) =
+                                            client.generate_title(&title_transcript).await
+                                        {
+                                            let title = title
+                                                .trim()
+                                                .trim_matches('"')
+                                                .trim_matches('\'')
+                                                .to_string();
+                                            if !title.is_empty() {
+                                                if let Err(e) = db_for_ai
+                                                    .update_meeting_title(&mid_for_ai, &title)
+                                                {
+                                                    log::error!(
+                                                        "Failed to auto-update meeting title: {}",
+                                                        e
+                                                    );
+                                                } else {
+                                                    log::info!("Auto-title saved: {}", title);
+                                                    let _ = app_for_ai.emit(
+                                                        "meeting-title-updated",
+                                                        serde_json::json!({
+                                                            "meeting_id": mid_for_ai,
+                                                            "title": title
+                                                        }),
+                                                    );
+                                                }
+                                            }
+                                        }
+                                    }
+
+                                    // Transcript enhancement - batch 5 segments together for better context
+                                    if enhance_transcripts && current_seg_counter.is_multiple_of(5)
+                                    {
+                                        let transcript = transcript_for_ai.lock().await;
+                                        let batch = window_around(
+                                            &transcript,
+                                            |s| &s.id,
+                                            &seg_id_for_ai,
+                                            4,
+                                            0,
+                                        );
+                                        let segments: Vec<String> =
+                                            batch.iter().map(|s| s.text.clone()).collect();
+                                        let segment_ids: Vec<String> =
+                                            batch.iter().map(|s| s.id.clone()).collect();
+                                        drop(transcript);
+
+                                        if segments.len() >= 3 {
+                                            if let Ok(enhanced_segments) =
+                                                client.enhance_batch(&segments).await
+                                            {
+                                                let combined_text = enhanced_segments.join("\n\n");
+                                                for seg_id in &segment_ids {
+                                                    let _ = db_for_ai.update_segment_enhanced_text(
+                                                        seg_id,
+                                                        Some(&combined_text),
+                                                    );
+                                                }
+                                                let _ = app_for_ai.emit(
+                                                    "segment-enhanced",
+                                                    serde_json::json!({
+                                                        "segment_ids": segment_ids,
+                                                        "enhanced_text": combined_text
+                                                    }),
+                                                );
+                                                log::info!(
+                                                    "Emitted semantic reconstruction for segments {}-{}",
+                                                    segment_ids.first().unwrap_or(&String::new()),
+                                                    segment_ids.last().unwrap_or(&String::new())
+                                                );
+                                            }
+                                        }
+                                    }
+
+                                    // Question detection - check every segment still in the
+                                    // transcript
+                                    let curr_text = if detect_questions {
+                                        transcript_for_ai
+                                            .lock()
+                                            .await
+                                            .iter()
+                                            .find(|s| s.id == seg_id_for_ai)
+                                            .map(|s| s.text.clone())
+                                    } else {
+                                        None
+                                    };
+                                    if let Some(curr_text) = curr_text {
+                                        log::info!(
+                                            "Checking for question in segment {}",
+                                            current_seg_counter
+                                        );
+
+                                        if let Ok(is_question) =
+                                            client.detect_question(&curr_text).await
+                                        {
+                                            log::info!(
+                                                "Question detection result: {}",
+                                                is_question
+                                            );
+                                            if is_question {
+                                                let transcript = transcript_for_ai.lock().await;
+                                                let context: String = window_around(
+                                                    &transcript,
+                                                    |s| &s.id,
+                                                    &seg_id_for_ai,
+                                                    5,
+                                                    5,
+                                                )
+                                                .iter()
+                                                .map(|s| s.text.clone())
+                                                .collect::<Vec<_>>()
+                                                .join("\n");
+                                                drop(transcript);
+
+                                                if let Ok(answer) = client
+                                                    .answer_question(&context, &curr_text)
+                                                    .await
+                                                {
+                                                    let _ = db_for_ai.update_segment_question(
+                                                        &seg_id_for_ai,
+                                                        true,
+                                                        Some(&answer),
+                                                    );
+                                                    let _ = app_for_ai.emit(
+                                                        "question-detected",
+                                                        serde_json::json!({
+                                                            "segment_id": seg_id_for_ai,
+                                                            "question": curr_text,
+                                                            "answer": answer
+                                                        }),
+                                                    );
+                                                    log::info!(
+                                                        "Question detected and emitted: {}",
+                                                        curr_text
+                                                    );
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                            });
+                        }
+
+                        // Emit transcription segment to frontend
+                        if let Err(e) = app.emit("transcription", &event) {
+                            log::error!("Failed to emit transcription: {}", e);
+                        }
+                        log::info!("[{}] {}", format_time(asr_segment.start_ms as u64), text);
+
+                        let whisper_translation = translations.next().flatten();
+                        match translation_mode {
+                            TranslationMode::Whisper => {
+                                if let Some(translation) = whisper_translation {
+                                    store_translation(
+                                        &app,
+                                        &db,
+                                        event.id,
+                                        translation,
+                                        translation_language.clone(),
+                                    );
+                                }
+                            }
+                            TranslationMode::Llm
+                                if translation::needs_translation(
+                                    language.as_deref(),
+                                    &translation_language,
+                                ) =>
+                            {
+                                spawn_llm_translation(
+                                    app.clone(),
+                                    db.clone(),
+                                    settings.clone(),
+                                    event.id,
+                                    text,
+                                    translation_language.clone(),
+                                );
+                            }
+                            _ => {}
+                        }
+                    }
+                    if let (Some(segment), Some(id), false) =
+                        (last_segment, chunk_segment_ids.last(), catch_up)
+                    {
+                        chunk_tails.insert(
+                            chunk.source.as_str().to_string(),
+                            ChunkTail {
+                                segment_id: id.clone(),
+                                segment,
+                                chunk_end_ms: chunk.start_ms + chunk.duration_ms,
+                                language,
+                                language_probability,
+                            },
+                        );
+                    }
+                    if !chunk_segment_ids.is_empty() {
+                        echo_guard.remember(chunk_segment_ids, &chunk, &text);
+                    }
+                }
+                Err(e) => {
+                    log::error!("Transcription error for chunk {}: {}", chunk.chunk_index, e);
+                    let partial_id = partial_segment_id(&meeting_id, &chunk);
+                    if !chunk.partial && shown_partials.remove(&partial_id) {
+                        let _ = app.emit(
+                            "segment-removed",
+                            serde_json::json!({ "segment_id": partial_id }),
+                        );
+                    }
                 }
-                total_duration_ms += (chunk_samples as f32 / 16.0) as i64;
             }
         }
+    }
+
+    let _ = std::fs::remove_dir_all(&spill_dir);
+    log::info!(
+        "Transcription consumer finished, processed {} segments",
+        segment_counter
+    );
+}
+
+/// A final pass over a recording, run once its live transcription has finished
+struct FinalPass {
+    model: WhisperModel,
+    /// Recording of each source, `<source>.wav`
+    audio_dir: std::path::PathBuf,
+    /// The recordings were made only for the final pass, and are deleted after it
+    temporary: bool,
+}
+
+/// Audio read and chunked at a time in the final pass
+const FINAL_PASS_BLOCK_MS: i64 = 30_000;
+
+/// Where recordings are kept for the final pass when meeting audio isn't saved
+fn final_pass_dir(meeting_id: &str) -> std::path::PathBuf {
+    std::env::temp_dir()
+        .join("phantomear-final")
+        .join(meeting_id)
+}
 
-        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+/// The configured final pass model, if it is a Whisper model that has been downloaded
+fn final_pass_whisper_model(name: &str) -> Option<WhisperModel> {
+    let name = name.trim();
+    if name.is_empty() {
+        return None;
+    }
+    match name.parse::<WhisperModel>() {
+        Ok(model) if asr::is_model_downloaded(model).unwrap_or(false) => Some(model),
+        Ok(_) => {
+            log::warn!(
+                "Final pass model {} is not downloaded, skipping final pass",
+                name
+            );
+            None
+        }
+        Err(e) => {
+            log::warn!("Invalid final pass model {}: {}", name, e);
+            None
+        }
     }
-
-    capture.stop();
-    log::info!("System audio producer stopped after {} chunks", chunk_index);
 }
 
-/// Transcription consumer: receives audio chunks from the channel and runs Whisper inference.
-/// Processes all queued chunks even after recording stops (drain-not-discard).
+/// Transcribe a finished recording again with the final model and replace the meeting's
+/// segments with the result, re-embedding them. The live transcript stays if anything fails.
 #[allow(clippy::too_many_arguments)]
-async fn run_transcription_consumer(
+async fn run_final_pass(
     app: AppHandle,
-    engine: Arc<Mutex<Option<TranscriptionEngine>>>,
-    transcript: Arc<Mutex<Vec<TranscriptSegment>>>,
     db: Arc<Database>,
-    meeting_id: String,
     embedding_model: Arc<Mutex<Option<EmbeddingModel>>>,
-    meeting_title: String,
+    punctuation_model: Arc<Mutex<Option<PunctuationModel>>>,
     settings: Arc<Mutex<Settings>>,
-    mut chunk_rx: tokio::sync::mpsc::Receiver<AudioChunk>,
-    pending_chunks: Arc<AtomicUsize>,
+    meeting_id: String,
+    meeting_title: String,
+    pass: FinalPass,
 ) {
-    use crate::transcription::TranscriptionEvent;
-
-    let mut segment_counter: u64 = 0;
-
-    log::info!("Transcription consumer started");
-
-    while let Some(chunk) = chunk_rx.recv().await {
-        // Atomically decrement so the producer can never observe a torn value
-        // between load and store. fetch_sub returns the old value, so subtract 1.
-        let new_count = pending_chunks
-            .fetch_sub(1, Ordering::SeqCst)
-            .saturating_sub(1);
-
+    let emit_progress = |stage: &str, processed_ms: i64, total_ms: i64, segments: u64| {
         let _ = app.emit(
-            "transcription-status",
-            serde_json::json!({ "status": "processing", "pending_chunks": new_count }),
+            "final-pass-progress",
+            ImportProgressEvent {
+                meeting_id: meeting_id.clone(),
+                stage: stage.to_string(),
+                processed_ms,
+                total_ms: Some(total_ms),
+                segments,
+                error: None,
+            },
         );
-
-        // Run Whisper inference
-        let transcription_result = {
-            let engine_guard = engine.lock().await;
-            if let Some(ref eng) = *engine_guard {
-                eng.transcribe(&chunk.samples).await
-            } else {
-                log::warn!(
-                    "No transcription engine, dropping chunk {}",
-                    chunk.chunk_index
-                );
-                let _ = app.emit(
-                    "transcription-status",
-                    serde_json::json!({ "status": "idle", "pending_chunks": new_count }),
-                );
-                continue;
-            }
-        };
-
+    };
+    let fail = |error: String| {
+        log::error!("Final pass of {} failed: {}", meeting_id, error);
         let _ = app.emit(
-            "transcription-status",
-            serde_json::json!({ "status": "idle", "pending_chunks": new_count }),
+            "final-pass-progress",
+            ImportProgressEvent {
+                meeting_id: meeting_id.clone(),
+                stage: "failed".to_string(),
+                processed_ms: 0,
+                total_ms: None,
+                segments: 0,
+                error: Some(error),
+            },
         );
+    };
 
-        match transcription_result {
-            Ok(result) => {
-                let text = result.full_text.trim().to_string();
-                if !text.is_empty() {
-                    segment_counter += 1;
+    // The meeting may have been titled since it started
+    let title = db
+        .get_meeting(&meeting_id)
+        .ok()
+        .flatten()
+        .map(|m| m.title)
+        .unwrap_or(meeting_title);
+    log::info!("Final pass of {} with {:?}", meeting_id, pass.model);
+    // Compared at the end, to find what the user changed meanwhile
+    let live_before = db.get_segments(&meeting_id).unwrap_or_default();
+
+    let transcribed = transcribe_final_pass(
+        &db,
+        &punctuation_model,
+        &settings,
+        &meeting_id,
+        &title,
+        &pass,
+        &emit_progress,
+    )
+    .await;
+    if pass.temporary {
+        let _ = std::fs::remove_dir_all(&pass.audio_dir);
+    }
+    let (mut rows, total_ms) = match transcribed {
+        Ok(transcribed) => transcribed,
+        Err(e) => return fail(e.to_string()),
+    };
 
-                    let seg_id = format!("{}-seg-{}", meeting_id, segment_counter);
-                    let time_label = format_time(chunk.start_ms as u64);
+    let live = db.get_segments(&meeting_id).unwrap_or_default();
+    if rows.is_empty() && !live.is_empty() {
+        return fail("No speech found in the recording".to_string());
+    }
+    let (keep_live, echo_cancellation) = {
+        let settings = settings.lock().await;
+        (settings.keep_live_transcript, settings.echo_cancellation)
+    };
+    if echo_cancellation {
+        let echoes = final_pass::echo_copies(rows.iter().map(|(row, _)| row));
+        if !echoes.is_empty() {
+            log::info!("Final pass dropping {} mic segments as echo", echoes.len());
+            rows.retain(|(row, _)| !echoes.contains(&row.id));
+        }
+    }
+    // Segments the user edited or deleted meanwhile stay as the user left them
+    let changes = final_pass::UserChanges::new(live_before, &live);
+    rows.retain(|(row, _)| !changes.covers(row));
+    let keep: Vec<String> = changes.edited.iter().map(|s| s.id.clone()).collect();
+    final_pass::carry_speakers(&live, rows.iter_mut().map(|(row, _)| row));
+    final_pass::carry_ai_fields(&live, rows.iter_mut().map(|(row, _)| row));
+    let segments = (rows.len() + keep.len()) as u64;
+    emit_progress("saving", total_ms, total_ms, segments);
+    if let Err(e) = db.replace_segments(&meeting_id, &rows, &keep, keep_live) {
+        return fail(format!("Failed to replace segments: {}", e));
+    }
 
-                    let source_str = chunk.source.as_str().to_string();
+    {
+        let model = embedding_model.lock().await;
+        if let Some(ref model) = *model {
+            emit_progress("embedding", total_ms, total_ms, segments);
+            if let Err(e) = embed_unembedded_segments(&db, model, &meeting_id, &title) {
+                log::error!("Failed to embed final transcript: {}", e);
+            }
+        }
+    }
 
-                    let event = TranscriptionEvent {
-                        id: seg_id.clone(),
-                        text: text.clone(),
-                        start_ms: chunk.start_ms,
-                        end_ms: chunk.start_ms + chunk.duration_ms,
-                        is_partial: false,
-                        source: source_str.clone(),
-                    };
+    let (translation_mode, translation_language) = translation_target(&*settings.lock().await);
+    if translation_mode == TranslationMode::Llm {
+        for (row, _) in rows {
+            if translation::needs_translation(row.language.as_deref(), &translation_language) {
+                spawn_llm_translation(
+                    app.clone(),
+                    db.clone(),
+                    settings.clone(),
+                    row.id,
+                    row.text,
+                    translation_language.clone(),
+                );
+            }
+        }
+    }
 
-                    // Store in in-memory transcript
-                    {
-                        let mut transcript_guard = transcript.lock().await;
-                        transcript_guard.push(TranscriptSegment {
-                            id: event.id.clone(),
-                            time: time_label.clone(),
-                            text: event.text.clone(),
-                            timestamp_ms: chunk.start_ms as u64,
-                            source: source_str.clone(),
-                        });
-                    }
+    emit_progress("done", total_ms, total_ms, segments);
+    let _ = app.emit(
+        "final-pass-complete",
+        serde_json::json!({
+            "meeting_id": &meeting_id,
+            "segments": segments,
+            "live_kept": keep_live,
+        }),
+    );
+    log::info!(
+        "Final pass replaced the transcript of {} with {} segments",
+        meeting_id,
+        segments
+    );
+}
 
-                    // Persist segment to DB
-                    let seg_id_for_emb = seg_id.clone();
-                    let time_label_for_emb = time_label.clone();
-                    let text_for_emb = text.clone();
-                    if let Err(e) = db.insert_segment(&SegmentRow {
-                        id: seg_id,
-                        meeting_id: meeting_id.clone(),
-                        time_label,
-                        text: text.clone(),
-                        timestamp_ms: chunk.start_ms,
-                        speaker_id: None,
-                        source: Some(source_str.clone()),
-                        enhanced_text: None,
-                        is_question: false,
-                        question_answer: None,
-                    }) {
-                        log::error!("Failed to persist segment: {}", e);
-                    }
+/// Transcribe each source's recording with the final model, returning the new segments (in
+/// time order) and the audio duration covered
+async fn transcribe_final_pass(
+    db: &Database,
+    punctuation_model: &Mutex<Option<PunctuationModel>>,
+    settings: &Mutex<Settings>,
+    meeting_id: &str,
+    meeting_title: &str,
+    pass: &FinalPass,
+    emit_progress: &impl Fn(&str, i64, i64, u64),
+) -> anyhow::Result<(Vec<(SegmentRow, Vec<WordRow>)>, i64)> {
+    let mut recordings = Vec::new();
+    for entry in std::fs::read_dir(&pass.audio_dir)? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == "wav") {
+            if let Some(source) = path.file_stem().map(|s| s.to_string_lossy().to_string()) {
+                let duration_ms = recorder::recording_duration_ms(&path)?;
+                recordings.push((source, path, duration_ms));
+            }
+        }
+    }
+    recordings.sort();
+    let total_ms: i64 = recordings.iter().map(|(_, _, duration)| duration).sum();
+
+    emit_progress("loading", 0, total_ms, 0);
+    let language = settings.lock().await.language.clone();
+    let model = pass.model;
+    let engine = tokio::task::spawn_blocking(move || -> anyhow::Result<TranscriptionEngine> {
+        let mut engine = TranscriptionEngine::new();
+        engine.set_language(&language);
+        // One chunk at a time, on all the cores live transcription would share
+        engine.set_threads(workers::worker_plan(1, 0, num_cpus::get()).1);
+        engine.load_model(&asr::get_model_path(model)?)?;
+        Ok(engine)
+    })
+    .await??;
+    let engine = RwLock::new(Some(engine));
+
+    let config = TranscriptionConfig::default();
+    let mut rows: Vec<(SegmentRow, Vec<WordRow>)> = Vec::new();
+    let mut done_ms = 0;
+    for (source, path, duration_ms) in &recordings {
+        let mut chunker = SpeechChunker::new(&config);
+        let mut previous_text = String::new();
+        // Last row of this source, for stitching the next chunk onto it
+        let mut tail: Option<(usize, ChunkTail)> = None;
+        let mut position = 0;
+        loop {
+            let end = (position + FINAL_PASS_BLOCK_MS).min(*duration_ms);
+            let speech = if position < *duration_ms {
+                // Reading and chunking a block is blocking work, like decoding it
+                let samples =
+                    tokio::task::block_in_place(|| recorder::read_range(path, position, end))?;
+                tokio::task::block_in_place(|| chunker.push(&samples))
+            } else {
+                chunker.flush().into_iter().collect()
+            };
 
-                    // Generate embedding in background (non-blocking)
-                    {
-                        let emb_model = embedding_model.clone();
-                        let emb_db = db.clone();
-                        let emb_title = meeting_title.clone();
-                        tokio::spawn(async move {
-                            let model_guard = emb_model.lock().await;
-                            if let Some(ref m) = *model_guard {
-                                let enriched = embeddings::enrich_segment(
-                                    &emb_title,
-                                    &time_label_for_emb,
-                                    &text_for_emb,
-                                );
-                                match m.embed(&enriched) {
-                                    Ok(emb) => {
-                                        if let Err(e) =
-                                            emb_db.insert_embedding(&seg_id_for_emb, &emb)
-                                        {
-                                            log::error!("Failed to store embedding: {}", e);
-                                        }
-                                    }
-                                    Err(e) => log::error!("Embedding failed: {}", e),
-                                }
-                            }
-                        });
+            for chunk in speech {
+                let (prompt, chunk_language) = {
+                    let settings = settings.lock().await;
+                    (
+                        decoder_prompt(
+                            db,
+                            meeting_id,
+                            Some(meeting_title),
+                            &settings,
+                            &previous_text,
+                        ),
+                        source_language(&settings, Some(source)),
+                    )
+                };
+                let result = match engine.read().await.as_ref() {
+                    Some(eng) => {
+                        transcribe_prompted(
+                            eng,
+                            &chunk.samples,
+                            chunk.start_ms,
+                            &prompt,
+                            &chunk_language,
+                        )
+                        .await
                     }
-
-                    // AI processing: auto-title, transcript enhancement, question detection
-                    let current_seg_counter = segment_counter;
-                    let settings_for_ai = settings.clone();
-                    let db_for_ai = db.clone();
-                    let mid_for_ai = meeting_id.clone();
-                    let app_for_ai = app.clone();
-                    let transcript_for_ai = transcript.clone();
-                    tokio::spawn(async move {
-                        let settings = settings_for_ai.lock().await;
-                        let enhance_transcripts = settings.enhance_transcripts;
-                        let detect_questions = settings.detect_questions;
-                        log::info!(
-                            "AI Processing check - enhance: {}, detect_questions: {}",
-                            enhance_transcripts,
-                            detect_questions
+                    None => Err(anyhow::anyhow!("No final pass engine")),
+                };
+                let result = match result {
+                    Ok(r) => r,
+                    Err(e) => {
+                        log::error!(
+                            "Final pass error for {} at {} ms: {}",
+                            source,
+                            chunk.start_ms,
+                            e
                         );
-                        drop(settings);
-
-                        let llm_provider = {
-                            let settings = settings_for_ai.lock().await;
-                            match settings.llm_provider.as_str() {
-                                "openai" => settings
-                                    .openai_api_key
-                                    .clone()
-                                    .map(|key| LlmProvider::OpenAI { api_key: key }),
-                                _ => {
-                                    let url = settings
-                                        .ollama_url
-                                        .clone()
-                                        .unwrap_or_else(|| "http://localhost:11434".to_string());
-                                    let model = settings
-                                        .ollama_model
-                                        .clone()
-                                        .unwrap_or_else(|| "llama3.2".to_string());
-                                    Some(LlmProvider::Ollama { url, model })
-                                }
-                            }
-                        };
-
-                        if let Some(provider) = llm_provider {
-                            let client = LlmClient::new(provider);
-
-                            // Auto-title exactly once at segment 10
-                            if current_seg_counter == 10 {
-                                let transcript = transcript_for_ai.lock().await;
-                                let title_transcript: String = transcript
-                                    .iter()
-                                    .take(10)
-                                    .map(|s| s.text.clone())
-                                    .collect::<Vec<_>>()
-                                    .join(" ");
-                                drop(transcript);
-
-                                if let Ok(title) = client.generate_title(&title_transcript).await {
-                                    let title = title
-                                        .trim()
-                                        .trim_matches('"')
-                                        .trim_matches('\'')
-                                        .to_string();
-                                    if !title.is_empty() {
-                                        if let Err(e) =
-                                            db_for_ai.update_meeting_title(&mid_for_ai, &title)
-                                        {
-                                            log::error!(
-                                                "Failed to auto-update meeting title: {}",
-                                                e
-                                            );
-                                        } else {
-                                            log::info!("Auto-title saved: {}", title);
-                                            let _ = app_for_ai.emit(
-                                                "meeting-title-updated",
-                                                serde_json::json!({
-                                                    "meeting_id": mid_for_ai,
-                                                    "title": title
-                                                }),
-                                            );
-                                        }
-                                    }
-                                }
-                            }
-
-                            // Transcript enhancement - batch 5 segments together for better context
-                            if enhance_transcripts && current_seg_counter.is_multiple_of(5) {
-                                let transcript = transcript_for_ai.lock().await;
-                                let start_idx = (current_seg_counter as usize).saturating_sub(5);
-                                let segments: Vec<String> = transcript
-                                    [start_idx..current_seg_counter as usize]
-                                    .iter()
-                                    .map(|s| s.text.clone())
-                                    .collect();
-                                let segment_ids: Vec<String> = (start_idx
-                                    ..current_seg_counter as usize)
-                                    .map(|i| format!("{}-seg-{}", mid_for_ai, i + 1))
-                                    .collect();
-                                drop(transcript);
-
-                                if segments.len() >= 3 {
-                                    if let Ok(enhanced_segments) =
-                                        client.enhance_batch(&segments).await
-                                    {
-                                        let combined_text = enhanced_segments.join("\n\n");
-                                        for seg_id in &segment_ids {
-                                            let _ = db_for_ai.update_segment_enhanced_text(
-                                                seg_id,
-                                                Some(&combined_text),
-                                            );
-                                        }
-                                        let _ = app_for_ai.emit(
-                                            "segment-enhanced",
-                                            serde_json::json!({
-                                                "segment_ids": segment_ids,
-                                                "enhanced_text": combined_text
-                                            }),
-                                        );
-                                        log::info!(
-                                            "Emitted semantic reconstruction for segments {}-{}",
-                                            segment_ids.first().unwrap_or(&String::new()),
-                                            segment_ids.last().unwrap_or(&String::new())
-                                        );
-                                    }
-                                }
-                            }
-
-                            // Question detection - check every segment
-                            if detect_questions {
-                                let transcript = transcript_for_ai.lock().await;
-                                let idx = (current_seg_counter as usize).saturating_sub(1);
-                                let curr_text = transcript
-                                    .get(idx)
-                                    .map(|s| s.text.clone())
-                                    .unwrap_or_default();
-                                drop(transcript);
-
-                                log::info!(
-                                    "Checking for question in segment {}",
-                                    current_seg_counter
-                                );
-
-                                if let Ok(is_question) = client.detect_question(&curr_text).await {
-                                    log::info!("Question detection result: {}", is_question);
-                                    if is_question {
-                                        let transcript = transcript_for_ai.lock().await;
-                                        let ctx_start = idx.saturating_sub(5);
-                                        let ctx_end = (idx + 6).min(transcript.len());
-                                        let context: String = transcript[ctx_start..ctx_end]
-                                            .iter()
-                                            .map(|s| s.text.clone())
-                                            .collect::<Vec<_>>()
-                                            .join("\n");
-                                        drop(transcript);
-
-                                        if let Ok(answer) =
-                                            client.answer_question(&context, &curr_text).await
-                                        {
-                                            let seg_id = format!(
-                                                "{}-seg-{}",
-                                                mid_for_ai, current_seg_counter
-                                            );
-                                            let _ = db_for_ai.update_segment_question(
-                                                &seg_id,
-                                                true,
-                                                Some(&answer),
-                                            );
-                                            let _ = app_for_ai.emit(
-                                                "question-detected",
-                                                serde_json::json!({
-                                                    "segment_id": seg_id,
-                                                    "question": curr_text,
-                                                    "answer": answer
-                                                }),
-                                            );
-                                            log::info!(
-                                                "Question detected and emitted: {}",
-                                                curr_text
-                                            );
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    });
+                        continue;
+                    }
+                };
+                if prompt::is_hallucination(&result.full_text, "") {
+                    previous_text.clear();
+                } else if !result.full_text.trim().is_empty() {
+                    previous_text = result.full_text.trim().to_string();
+                }
 
-                    // Emit transcription segment to frontend
-                    if let Err(e) = app.emit("transcription", &event) {
-                        log::error!("Failed to emit transcription: {}", e);
+                let end_ms = chunk.start_ms + chunk.duration_ms;
+                let (language, language_probability) =
+                    (result.language.clone(), result.language_probability);
+                let mut segments = timeline_segments(result, chunk.start_ms, end_ms);
+                if let Some(previous) = stitch_segments(
+                    tail.as_ref().map(|(_, tail)| tail),
+                    &mut segments,
+                    chunk.start_ms,
+                ) {
+                    // Rows left without text are dropped at the end
+                    if let Some((index, _)) = tail {
+                        let (row, words) = &mut rows[index];
+                        row.text = previous.text.clone();
+                        row.end_ms = Some(previous.end_ms);
+                        *words = word_rows(&previous.words);
+                    }
+                }
+                segments.retain(|s| !s.text.trim().is_empty());
+                if settings.lock().await.restore_punctuation {
+                    if let Some(ref model) = *punctuation_model.lock().await {
+                        tokio::task::block_in_place(|| restore_punctuation(model, &mut segments));
                     }
-                    log::info!("[{}] {}", format_time(chunk.start_ms as u64), text);
+                }
+
+                let (translation_mode, _) = translation_target(&*settings.lock().await);
+                let mut translations = whisper_translations(
+                    &engine,
+                    translation_mode,
+                    &chunk.samples,
+                    chunk.start_ms,
+                    &chunk_language,
+                    language.as_deref(),
+                    &segments,
+                )
+                .await
+                .into_iter();
+                for segment in segments {
+                    let id = format!("{}-final-{}", meeting_id, rows.len() + 1);
+                    tail = Some((
+                        rows.len(),
+                        ChunkTail {
+                            segment_id: id.clone(),
+                            segment: segment.clone(),
+                            chunk_end_ms: end_ms,
+                            language: language.clone(),
+                            language_probability,
+                        },
+                    ));
+                    let words = word_rows(&segment.words);
+                    rows.push((
+                        SegmentRow {
+                            id,
+                            meeting_id: meeting_id.to_string(),
+                            time_label: format_time(segment.start_ms as u64),
+                            text: segment.text,
+                            timestamp_ms: segment.start_ms,
+                            end_ms: Some(segment.end_ms),
+                            source: Some(source.clone()),
+                            language: language.clone(),
+                            language_probability,
+                            translation: translations.next().flatten(),
+                            ..Default::default()
+                        },
+                        words,
+                    ));
                 }
             }
-            Err(e) => {
-                log::error!("Transcription error for chunk {}: {}", chunk.chunk_index, e);
+
+            emit_progress("transcribing", done_ms + end, total_ms, rows.len() as u64);
+            if position >= *duration_ms {
+                break;
             }
+            position = end;
         }
+        done_ms += duration_ms;
     }
 
-    log::info!(
-        "Transcription consumer finished, processed {} segments",
-        segment_counter
-    );
+    rows.retain(|(row, _)| !row.text.trim().is_empty());
+    rows.sort_by_key(|(row, _)| row.timestamp_ms);
+    Ok((rows, total_ms))
 }
 
 /// Stop recording and finalize transcript
@@ -929,6 +3123,13 @@ pub async fn stop_recording(state: State<'_, AppState>) -> Result<Vec<Transcript
         capture
             .stop()
             .map_err(|e| format!("Failed to stop: {}", e))?;
+        if let Some(stats) = capture.stats().filter(|s| s.overruns > 0) {
+            log::warn!(
+                "Audio capture dropped {} samples in {} overruns",
+                stats.dropped_samples,
+                stats.overruns
+            );
+        }
     }
     *capture_guard = None;
 
@@ -939,8 +3140,9 @@ pub async fn stop_recording(state: State<'_, AppState>) -> Result<Vec<Transcript
     if let Some(mid) = &meeting_id {
         let ended_at = Utc::now().to_rfc3339();
         let duration_ms = transcript
-            .last()
-            .map(|s| s.timestamp_ms as i64)
+            .iter()
+            .map(|s| s.end_ms as i64)
+            .max()
             .unwrap_or(0);
         if let Err(e) = state.db.update_meeting_ended(mid, &ended_at, duration_ms) {
             log::error!("Failed to update meeting ended: {}", e);
@@ -1096,7 +3298,10 @@ pub async fn get_meeting(
             time: s.time_label,
             text: s.text,
             timestamp_ms: s.timestamp_ms as u64,
+            end_ms: s.end_ms.unwrap_or(s.timestamp_ms) as u64,
             source: s.source.unwrap_or_else(|| "mic".to_string()),
+            language: s.language,
+            translation: s.translation,
         })
         .collect();
 
@@ -1111,6 +3316,32 @@ pub async fn get_meeting(
     })
 }
 
+/// The live transcript of a meeting, kept for comparison when a final pass replaced it
+/// (empty if it wasn't kept)
+#[tauri::command]
+pub async fn get_live_transcript(
+    meeting_id: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<TranscriptSegment>, String> {
+    let segments = state
+        .db
+        .get_live_segments(&meeting_id)
+        .map_err(|e| format!("DB error: {}", e))?;
+    Ok(segments
+        .into_iter()
+        .map(|s| TranscriptSegment {
+            id: s.id,
+            time: s.time_label,
+            text: s.text,
+            timestamp_ms: s.timestamp_ms as u64,
+            end_ms: s.end_ms.unwrap_or(s.timestamp_ms) as u64,
+            source: s.source.unwrap_or_else(|| "mic".to_string()),
+            language: s.language,
+            translation: s.translation,
+        })
+        .collect())
+}
+
 #[tauri::command]
 pub async fn rename_meeting(
     id: String,
@@ -1135,6 +3366,31 @@ pub async fn update_meeting_tags(
         .map_err(|e| format!("DB error: {}", e))
 }
 
+/// Get the terms specific to a meeting (comma or newline separated)
+#[tauri::command]
+pub async fn get_meeting_vocabulary(
+    id: String,
+    state: State<'_, AppState>,
+) -> Result<Option<String>, String> {
+    state
+        .db
+        .get_meeting_vocabulary(&id)
+        .map_err(|e| format!("DB error: {}", e))
+}
+
+/// Set the terms specific to a meeting; a recording in progress uses them from its next chunk
+#[tauri::command]
+pub async fn update_meeting_vocabulary(
+    id: String,
+    vocabulary: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    state
+        .db
+        .update_meeting_vocabulary(&id, vocabulary.as_deref())
+        .map_err(|e| format!("DB error: {}", e))
+}
+
 #[tauri::command]
 pub async fn toggle_pin_meeting(id: String, state: State<'_, AppState>) -> Result<(), String> {
     let meeting = state
@@ -1150,10 +3406,177 @@ pub async fn toggle_pin_meeting(id: String, state: State<'_, AppState>) -> Resul
 
 #[tauri::command]
 pub async fn delete_meeting(id: String, state: State<'_, AppState>) -> Result<(), String> {
+    let audio_dir = state
+        .db
+        .get_meeting_audio_dir(&id)
+        .map_err(|e| format!("DB error: {}", e))?;
+
     state
         .db
         .delete_meeting(&id)
-        .map_err(|e| format!("DB error: {}", e))
+        .map_err(|e| format!("DB error: {}", e))?;
+
+    if let Some(dir) = audio_dir {
+        if let Err(e) = std::fs::remove_dir_all(&dir) {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                log::warn!("Failed to delete meeting audio {}: {}", dir, e);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Decode a range of a meeting's recording and return it as a 16-bit PCM WAV
+/// (raw bytes, so the frontend can play it from a Blob and seek within it).
+fn read_meeting_audio(
+    db: &Database,
+    meeting_id: &str,
+    source: &str,
+    start_ms: i64,
+    end_ms: i64,
+) -> Result<tauri::ipc::Response, String> {
+    if source.is_empty()
+        || !source
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(format!("Invalid audio source '{}'", source));
+    }
+    let audio_dir = db
+        .get_meeting_audio_dir(meeting_id)
+        .map_err(|e| format!("DB error: {}", e))?
+        .ok_or_else(|| "No audio recorded for this meeting".to_string())?;
+    let path = source_audio_path(std::path::Path::new(&audio_dir), source);
+    if !path.exists() {
+        return Err(format!("No {} audio recorded for this meeting", source));
+    }
+
+    let samples = recorder::read_range(&path, start_ms, end_ms)
+        .map_err(|e| format!("Failed to read meeting audio: {}", e))?;
+    let wav = recorder::encode_wav_pcm16(&samples)
+        .map_err(|e| format!("Failed to encode audio: {}", e))?;
+    Ok(tauri::ipc::Response::new(wav))
+}
+
+/// Get a range of a meeting's recorded audio for one source ("mic" / "system") as WAV bytes
+#[tauri::command]
+pub async fn get_meeting_audio(
+    meeting_id: String,
+    source: String,
+    start_ms: i64,
+    end_ms: i64,
+    state: State<'_, AppState>,
+) -> Result<tauri::ipc::Response, String> {
+    if end_ms <= start_ms {
+        return Err("end_ms must be greater than start_ms".to_string());
+    }
+    read_meeting_audio(&state.db, &meeting_id, &source, start_ms, end_ms)
+}
+
+/// Get the recorded audio behind a transcript segment as WAV bytes
+#[tauri::command]
+pub async fn get_segment_audio(
+    segment_id: String,
+    state: State<'_, AppState>,
+) -> Result<tauri::ipc::Response, String> {
+    let segment = state
+        .db
+        .get_segment(&segment_id)
+        .map_err(|e| format!("DB error: {}", e))?
+        .ok_or_else(|| "Segment not found".to_string())?;
+
+    let source = segment.source.as_deref().unwrap_or("mic");
+    let end_ms = match segment.end_ms {
+        Some(end_ms) => end_ms,
+        None => {
+            // Older rows span one speech chunk: they end at the next segment from the same
+            // source, and never run past the maximum chunk length
+            let max_chunk_ms = (TranscriptionConfig::default().max_chunk_secs * 1000.0) as i64;
+            let next_start = state
+                .db
+                .get_segments(&segment.meeting_id)
+                .map_err(|e| format!("DB error: {}", e))?
+                .iter()
+                .filter(|s| s.source.as_deref().unwrap_or("mic") == source)
+                .map(|s| s.timestamp_ms)
+                .find(|&ts| ts > segment.timestamp_ms);
+            next_start
+                .unwrap_or(i64::MAX)
+                .min(segment.timestamp_ms + max_chunk_ms)
+        }
+    };
+
+    if end_ms < segment.timestamp_ms {
+        return Err(format!("Segment {} ends before it starts", segment_id));
+    }
+    read_meeting_audio(
+        &state.db,
+        &segment.meeting_id,
+        source,
+        segment.timestamp_ms,
+        end_ms,
+    )
+}
+
+/// Get the total duration (ms) of each recorded source for a meeting
+#[tauri::command]
+pub async fn get_meeting_audio_info(
+    meeting_id: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<MeetingAudioSourceInfo>, String> {
+    let Some(audio_dir) = state
+        .db
+        .get_meeting_audio_dir(&meeting_id)
+        .map_err(|e| format!("DB error: {}", e))?
+    else {
+        return Ok(vec![]);
+    };
+
+    let mut sources = Vec::new();
+    let entries = match std::fs::read_dir(&audio_dir) {
+        Ok(entries) => entries,
+        Err(_) => return Ok(vec![]),
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("wav") {
+            continue;
+        }
+        let Some(source) = path.file_stem().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        match recorder::recording_duration_ms(&path) {
+            Ok(duration_ms) => sources.push(MeetingAudioSourceInfo {
+                source: source.to_string(),
+                duration_ms,
+                size_bytes: entry.metadata().map(|m| m.len()).unwrap_or(0),
+            }),
+            Err(e) => log::warn!("Unreadable meeting audio {:?}: {}", path, e),
+        }
+    }
+    sources.sort_by(|a, b| a.source.cmp(&b.source));
+    Ok(sources)
+}
+
+#[derive(Debug, Serialize)]
+pub struct MeetingAudioSourceInfo {
+    pub source: String,
+    pub duration_ms: i64,
+    pub size_bytes: u64,
+}
+
+/// Total size of the files in a meeting's audio directory
+fn audio_dir_size(dir: &str) -> u64 {
+    std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter_map(|e| e.metadata().ok())
+                .filter(|m| m.is_file())
+                .map(|m| m.len())
+                .sum()
+        })
+        .unwrap_or(0)
 }
 
 #[tauri::command]
@@ -1164,16 +3587,49 @@ pub async fn search_meetings(
     if query.trim().is_empty() {
         return Ok(vec![]);
     }
-    state
-        .db
-        .search_transcripts(&query, 50)
-        .map_err(|e| format!("Search error: {}", e))
+    state
+        .db
+        .search_transcripts(&query, 50)
+        .map_err(|e| format!("Search error: {}", e))
+}
+
+/// Which texts an export carries
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ExportTrack {
+    Original,
+    /// The translation, or the original text where there is none
+    Translation,
+    /// The original with its translation under it
+    Both,
+}
+
+impl ExportTrack {
+    fn parse(track: Option<&str>) -> Self {
+        match track {
+            Some("translation") => ExportTrack::Translation,
+            Some("both") => ExportTrack::Both,
+            _ => ExportTrack::Original,
+        }
+    }
+
+    /// Main text of a segment, and the translation shown under it
+    fn texts(self, seg: &SegmentRow) -> (&str, Option<&str>) {
+        let translation = seg.translation.as_deref().filter(|t| !t.trim().is_empty());
+        match self {
+            ExportTrack::Original => (&seg.text, None),
+            ExportTrack::Translation => (translation.unwrap_or(&seg.text), None),
+            ExportTrack::Both => (&seg.text, translation),
+        }
+    }
 }
 
+/// Export a meeting as "markdown", "srt" or plain text. `track` picks the texts:
+/// "original" (default), "translation" or "both".
 #[tauri::command]
 pub async fn export_meeting(
     id: String,
     format: String,
+    track: Option<String>,
     state: State<'_, AppState>,
 ) -> Result<String, String> {
     let meeting = state
@@ -1186,6 +3642,7 @@ pub async fn export_meeting(
         .db
         .get_segments(&id)
         .map_err(|e| format!("DB error: {}", e))?;
+    let track = ExportTrack::parse(track.as_deref());
 
     match format.as_str() {
         "markdown" => {
@@ -1193,7 +3650,16 @@ pub async fn export_meeting(
             md.push_str(&format!("**Date:** {}\n\n", meeting.created_at));
             md.push_str("## Transcript\n\n");
             for seg in &segments {
-                md.push_str(&format!("**[{}]** {}\n\n", seg.time_label, seg.text));
+                let (text, translation) = track.texts(seg);
+                md.push_str(&format!(
+                    "**[{} - {}]** {}\n\n",
+                    seg.time_label,
+                    format_time(export_end_ms(seg) as u64),
+                    text
+                ));
+                if let Some(translation) = translation {
+                    md.push_str(&format!("> {}\n\n", translation));
+                }
             }
             Ok(md)
         }
@@ -1203,10 +3669,17 @@ pub async fn export_meeting(
             for (i, seg) in segments.iter().enumerate() {
                 // Convert timestamp_ms to SRT format (HH:MM:SS,mmm)
                 let start_time = format_srt_timestamp(seg.timestamp_ms);
-                let end_time = format_srt_timestamp(seg.timestamp_ms + 5000); // Assume 5s duration
+                let end_time = format_srt_timestamp(export_end_ms(seg));
                 srt.push_str(&format!("{}\n", i + 1));
                 srt.push_str(&format!("{} --> {}\n", start_time, end_time));
-                srt.push_str(&format!("{}\n\n", seg.text));
+                // A translation is the cue's second line
+                let (text, translation) = track.texts(seg);
+                srt.push_str(text);
+                if let Some(translation) = translation {
+                    srt.push('\n');
+                    srt.push_str(translation);
+                }
+                srt.push_str("\n\n");
             }
             Ok(srt)
         }
@@ -1214,13 +3687,30 @@ pub async fn export_meeting(
             // Default: plain text
             let mut txt = format!("{}\n{}\n\n", meeting.title, meeting.created_at);
             for seg in &segments {
-                txt.push_str(&format!("[{}] {}\n", seg.time_label, seg.text));
+                let (text, translation) = track.texts(seg);
+                txt.push_str(&format!(
+                    "[{} - {}] {}\n",
+                    seg.time_label,
+                    format_time(export_end_ms(seg) as u64),
+                    text
+                ));
+                if let Some(translation) = translation {
+                    txt.push_str(&format!("    {}\n", translation));
+                }
             }
             Ok(txt)
         }
     }
 }
 
+/// Length shown for segments stored before end times were, one chunk of the old pipeline
+const LEGACY_SEGMENT_MS: i64 = 5000;
+
+/// End time of a segment in exports
+fn export_end_ms(seg: &SegmentRow) -> i64 {
+    seg.end_ms.unwrap_or(seg.timestamp_ms + LEGACY_SEGMENT_MS)
+}
+
 /// Format milliseconds to SRT timestamp format (HH:MM:SS,mmm)
 fn format_srt_timestamp(ms: i64) -> String {
     let total_secs = ms / 1000;
@@ -1237,10 +3727,11 @@ pub async fn export_meeting_to_file(
     app: AppHandle,
     id: String,
     format: String,
+    track: Option<String>,
     state: State<'_, AppState>,
 ) -> Result<bool, String> {
     // First get the content (clone values to avoid borrow issues)
-    let content = export_meeting(id.clone(), format.clone(), State::clone(&state)).await?;
+    let content = export_meeting(id.clone(), format.clone(), track, State::clone(&state)).await?;
 
     // Determine file extension and filter
     let (extension, filter_name) = match format.as_str() {
@@ -1291,6 +3782,371 @@ pub async fn export_meeting_to_file(
     }
 }
 
+// ============================================================================
+// Import Commands
+// ============================================================================
+
+/// Progress of a file import ("import-progress") or of a final pass ("final-pass-progress")
+#[derive(Debug, Serialize, Clone)]
+pub struct ImportProgressEvent {
+    pub meeting_id: String,
+    /// "transcribing" | "embedding" | "summarizing" | "done" | "failed";
+    /// a final pass also reports "loading" and "saving"
+    pub stage: String,
+    /// Position reached in the file (ms)
+    pub processed_ms: i64,
+    /// File duration, if the container records it
+    pub total_ms: Option<i64>,
+    pub segments: u64,
+    pub error: Option<String>,
+}
+
+/// File extensions accepted by import_audio_file (for the open dialog filter)
+#[tauri::command]
+pub fn get_import_extensions() -> Vec<String> {
+    audio_file::SUPPORTED_EXTENSIONS
+        .iter()
+        .map(|e| e.to_string())
+        .collect()
+}
+
+/// LLM provider from settings for unattended work; None if OpenAI is selected without a key.
+fn background_llm_provider(settings: &Settings) -> Option<LlmProvider> {
+    match settings.llm_provider.as_str() {
+        "openai" => match settings.openai_api_key.clone() {
+            Some(key) if !key.is_empty() => Some(LlmProvider::OpenAI { api_key: key }),
+            _ => None,
+        },
+        _ => {
+            let url = settings
+                .ollama_url
+                .clone()
+                .unwrap_or_else(|| "http://localhost:11434".to_string());
+            let model = settings
+                .ollama_model
+                .clone()
+                .unwrap_or_else(|| "llama3.2".to_string());
+            Some(LlmProvider::Ollama { url, model })
+        }
+    }
+}
+
+/// Import an existing recording (audio file or the audio track of a video) as a new meeting.
+/// The file is decoded and resampled on a blocking thread while speech chunks are transcribed
+/// with the loaded engine. Segment timestamps are positions in the file, and the meeting is
+/// dated from the file's modification time. Emits "import-progress" throughout.
+#[tauri::command]
+pub async fn import_audio_file(
+    app: AppHandle,
+    path: String,
+    summarize: bool,
+    state: State<'_, AppState>,
+) -> Result<String, String> {
+    use crate::asr::resample_to_16khz;
+
+    if *state.is_recording.lock().await {
+        return Err("Stop the current recording before importing".to_string());
+    }
+    if state.transcription_engine.read().await.is_none() {
+        return Err("No transcription model loaded".to_string());
+    }
+
+    let path = std::path::PathBuf::from(path);
+    let reader = AudioFileReader::open(&path)
+        .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let total_ms = reader.duration_ms();
+
+    // A recording's file is last written when it ends
+    let ended = std::fs::metadata(&path)
+        .and_then(|m| m.modified())
+        .map(chrono::DateTime::<Utc>::from)
+        .unwrap_or_else(|_| Utc::now());
+    let started = ended - chrono::Duration::milliseconds(total_ms.unwrap_or(0));
+
+    let meeting_id = format!("meeting-{}", Utc::now().timestamp_millis());
+    let title = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(format_meeting_title);
+    state
+        .db
+        .create_meeting(&meeting_id, &title, &started.to_rfc3339())
+        .map_err(|e| format!("Failed to create meeting: {}", e))?;
+
+    let mut recorder = if state.settings.lock().await.save_meeting_audio {
+        open_recorder(
+            meeting_audio_dir(&app, &state.db, &meeting_id).as_deref(),
+            &AudioSource::Mic,
+        )
+    } else {
+        None
+    };
+
+    log::info!(
+        "Importing {} as meeting {} ({} Hz)",
+        path.display(),
+        meeting_id,
+        reader.sample_rate()
+    );
+
+    // Decode + resample off the async runtime. Blocks are resampled ~30 s at a time so
+    // resampler edge effects stay rare without holding the whole file in memory.
+    let (block_tx, mut block_rx) = tokio::sync::mpsc::channel::<anyhow::Result<Vec<f32>>>(4);
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut reader = reader;
+        let rate = reader.sample_rate();
+        let batch = rate as usize * 30;
+        let mut pending = Vec::with_capacity(batch);
+        loop {
+            let block = match reader.read_block() {
+                Ok(block) => block,
+                Err(e) => {
+                    let _ = block_tx.blocking_send(Err(e));
+                    return;
+                }
+            };
+            let done = block.is_none();
+            pending.extend(block.unwrap_or_default());
+            if pending.len() >= batch || (done && !pending.is_empty()) {
+                let resampled = resample_to_16khz(&pending, rate);
+                pending.clear();
+                if block_tx.blocking_send(resampled).is_err() {
+                    return;
+                }
+            }
+            if done {
+                return;
+            }
+        }
+    });
+
+    let emit_progress = |stage: &str, processed_ms: i64, segments: u64, error: Option<String>| {
+        let _ = app.emit(
+            "import-progress",
+            ImportProgressEvent {
+                meeting_id: meeting_id.clone(),
+                stage: stage.to_string(),
+                processed_ms,
+                total_ms,
+                segments,
+                error,
+            },
+        );
+    };
+
+    let config = TranscriptionConfig::default();
+    let mut chunker = SpeechChunker::new(&config);
+    let mut segment_counter: u64 = 0;
+    let mut failure = None;
+    let mut previous_text = String::new();
+    let mut chunk_tail: Option<ChunkTail> = None;
+    emit_progress("transcribing", 0, 0, None);
+
+    'decode: loop {
+        let (speech, end_of_file) = match block_rx.recv().await {
+            Some(Ok(samples)) => {
+                record_samples(&mut recorder, &samples);
+                (chunker.push(&samples), false)
+            }
+            Some(Err(e)) => {
+                failure = Some(format!("Decoding failed: {}", e));
+                (chunker.flush().into_iter().collect(), true)
+            }
+            None => (chunker.flush().into_iter().collect(), true),
+        };
+
+        for chunk in speech {
+            let prompt = decoder_prompt(
+                &state.db,
+                &meeting_id,
+                None,
+                &*state.settings.lock().await,
+                &previous_text,
+            );
+            let chunk_language = source_language(&*state.settings.lock().await, None);
+            let result = {
+                let engine = state.transcription_engine.read().await;
+                match engine.as_ref() {
+                    Some(eng) => {
+                        transcribe_prompted(
+                            eng,
+                            &chunk.samples,
+                            chunk.start_ms,
+                            &prompt,
+                            &chunk_language,
+                        )
+                        .await
+                    }
+                    None => {
+                        failure = Some("Transcription model was unloaded".to_string());
+                        break 'decode;
+                    }
+                }
+            };
+            let result = match result {
+                Ok(r) => r,
+                Err(e) => {
+                    log::error!("Import transcription error at {} ms: {}", chunk.start_ms, e);
+                    continue;
+                }
+            };
+            if prompt::is_hallucination(&result.full_text, "") {
+                previous_text.clear();
+            } else if !result.full_text.trim().is_empty() {
+                previous_text = result.full_text.trim().to_string();
+            }
+            let end_ms = chunk.start_ms + chunk.duration_ms;
+            let (language, language_probability) =
+                (result.language.clone(), result.language_probability);
+            let mut segments = timeline_segments(result, chunk.start_ms, end_ms);
+            if let (Some(previous), Some(tail)) = (
+                stitch_segments(chunk_tail.as_ref(), &mut segments, chunk.start_ms),
+                &chunk_tail,
+            ) {
+                store_stitched_tail(&state.db, &tail.segment_id, &previous);
+            }
+            segments.retain(|s| !s.text.trim().is_empty());
+            if state.settings.lock().await.restore_punctuation {
+                if let Some(ref model) = *state.punctuation_model.lock().await {
+                    restore_punctuation(model, &mut segments);
+                }
+            }
+            if let Some(last) = segments.last() {
+                chunk_tail = Some(ChunkTail {
+                    segment_id: format!(
+                        "{}-seg-{}",
+                        meeting_id,
+                        segment_counter + segments.len() as u64
+                    ),
+                    segment: last.clone(),
+                    chunk_end_ms: end_ms,
+                    language: language.clone(),
+                    language_probability,
+                });
+            }
+            let (translation_mode, translation_language) =
+                translation_target(&*state.settings.lock().await);
+            let mut translations = whisper_translations(
+                &state.transcription_engine,
+                translation_mode,
+                &chunk.samples,
+                chunk.start_ms,
+                &chunk_language,
+                language.as_deref(),
+                &segments,
+            )
+            .await
+            .into_iter();
+            for segment in segments {
+                segment_counter += 1;
+                let seg_id = format!("{}-seg-{}", meeting_id, segment_counter);
+                if let Err(e) = state.db.insert_segment(&SegmentRow {
+                    id: seg_id.clone(),
+                    meeting_id: meeting_id.clone(),
+                    time_label: format_time(segment.start_ms as u64),
+                    text: segment.text.clone(),
+                    timestamp_ms: segment.start_ms,
+                    end_ms: Some(segment.end_ms),
+                    speaker_id: None,
+                    source: Some(AudioSource::Mic.as_str().to_string()),
+                    enhanced_text: None,
+                    is_question: false,
+                    question_answer: None,
+                    language: language.clone(),
+                    language_probability,
+                    translation: translations.next().flatten(),
+                }) {
+                    log::error!("Failed to persist imported segment: {}", e);
+                } else if let Err(e) = state
+                    .db
+                    .insert_segment_words(&seg_id, &word_rows(&segment.words))
+                {
+                    log::error!("Failed to persist imported segment words: {}", e);
+                }
+                if translation_mode == TranslationMode::Llm
+                    && translation::needs_translation(language.as_deref(), &translation_language)
+                {
+                    spawn_llm_translation(
+                        app.clone(),
+                        state.db.clone(),
+                        state.settings.clone(),
+                        seg_id,
+                        segment.text,
+                        translation_language.clone(),
+                    );
+                }
+            }
+        }
+
+        emit_progress("transcribing", chunker.position_ms(), segment_counter, None);
+        if end_of_file {
+            break;
+        }
+    }
+    finish_recorder(recorder);
+
+    let duration_ms = chunker.position_ms();
+    let ended_at = started + chrono::Duration::milliseconds(duration_ms);
+    if let Err(e) = state
+        .db
+        .update_meeting_ended(&meeting_id, &ended_at.to_rfc3339(), duration_ms)
+    {
+        log::error!("Failed to update imported meeting: {}", e);
+    }
+
+    if let Some(error) = failure {
+        // Keep whatever was transcribed before the failure
+        emit_progress("failed", duration_ms, segment_counter, Some(error.clone()));
+        return Err(error);
+    }
+
+    {
+        let model = state.embedding_model.lock().await;
+        if let Some(ref model) = *model {
+            emit_progress("embedding", duration_ms, segment_counter, None);
+            if let Err(e) = embed_unembedded_segments(&state.db, model, &meeting_id, &title) {
+                log::error!("Failed to embed imported meeting: {}", e);
+            }
+        }
+    }
+
+    if summarize && segment_counter > 0 {
+        emit_progress("summarizing", duration_ms, segment_counter, None);
+        let provider = background_llm_provider(&*state.settings.lock().await);
+        let transcript_text = state
+            .db
+            .get_segments(&meeting_id)
+            .map(|segments| {
+                segments
+                    .iter()
+                    .map(|s| format!("[{}] {}", s.time_label, s.text))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            })
+            .unwrap_or_default();
+        match provider {
+            Some(provider) => match LlmClient::new(provider).summarize(&transcript_text).await {
+                Ok(summary) => {
+                    if let Err(e) = state.db.save_meeting_summary(&meeting_id, &summary) {
+                        log::error!("Failed to save import summary: {}", e);
+                    }
+                }
+                Err(e) => log::warn!("Import summary failed (non-critical): {}", e),
+            },
+            None => log::warn!("No LLM configured, skipping import summary"),
+        }
+    }
+
+    emit_progress("done", duration_ms, segment_counter, None);
+    log::info!(
+        "Imported {} segments from {} into {}",
+        segment_counter,
+        path.display(),
+        meeting_id
+    );
+    Ok(meeting_id)
+}
+
 // ============================================================================
 // Segment Editing Commands
 // ============================================================================
@@ -1330,6 +4186,41 @@ pub async fn delete_segment(segment_id: String, state: State<'_, AppState>) -> R
         .map_err(|e| format!("Failed to delete segment: {}", e))
 }
 
+/// Words below this probability are flagged for review by default
+const DEFAULT_CONFIDENCE_THRESHOLD: f32 = 0.5;
+
+/// Get the word timings and confidences of a segment
+#[tauri::command]
+pub async fn get_segment_words(
+    segment_id: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<WordRow>, String> {
+    state
+        .db
+        .get_segment_words(&segment_id)
+        .map_err(|e| format!("DB error: {}", e))
+}
+
+/// List the segments of a meeting with words the ASR was unsure of
+#[tauri::command]
+pub async fn get_low_confidence_segments(
+    meeting_id: String,
+    threshold: Option<f32>,
+    state: State<'_, AppState>,
+) -> Result<Vec<LowConfidenceSegment>, String> {
+    let threshold = threshold.unwrap_or(DEFAULT_CONFIDENCE_THRESHOLD);
+    if !(0.0..=1.0).contains(&threshold) {
+        return Err(format!(
+            "Threshold must be between 0 and 1, got {}",
+            threshold
+        ));
+    }
+    state
+        .db
+        .get_low_confidence_segments(&meeting_id, threshold)
+        .map_err(|e| format!("DB error: {}", e))
+}
+
 // ============================================================================
 // Speaker Commands
 // ============================================================================
@@ -1689,11 +4580,12 @@ pub async fn phomy_ask(question: String, state: State<'_, AppState>) -> Result<S
 
             if is_recording {
                 let transcript = state.transcript.lock().await;
-                if let Some(latest) = transcript.last() {
-                    let cutoff = (latest.timestamp_ms as i64) - (mins * 60 * 1000);
+                if let Some(latest) = transcript.iter().map(|s| s.end_ms).max() {
+                    // Everything spoken in the window, including speech that began before it
+                    let cutoff = (latest as i64) - (mins * 60 * 1000);
                     let filtered: Vec<_> = transcript
                         .iter()
-                        .filter(|s| s.timestamp_ms as i64 >= cutoff)
+                        .filter(|s| s.end_ms as i64 >= cutoff)
                         .collect();
                     filtered
                         .iter()
@@ -1704,15 +4596,15 @@ pub async fn phomy_ask(question: String, state: State<'_, AppState>) -> Result<S
                     String::new()
                 }
             } else if let Some(mid) = active_mid {
-                let segs = state
+                let latest = state
                     .db
-                    .get_segments(&mid)
+                    .get_transcript_end_ms(&mid)
                     .map_err(|e| format!("DB error: {}", e))?;
-                if let Some(latest) = segs.last() {
-                    let cutoff = latest.timestamp_ms - (mins * 60 * 1000);
-                    let filtered: Vec<_> =
-                        segs.iter().filter(|s| s.timestamp_ms >= cutoff).collect();
-                    filtered
+                if let Some(latest) = latest {
+                    state
+                        .db
+                        .get_segments_in_range(&mid, latest - (mins * 60 * 1000), latest)
+                        .map_err(|e| format!("DB error: {}", e))?
                         .iter()
                         .map(|s| format!("[{}] {}", s.time_label, s.text))
                         .collect::<Vec<_>>()
@@ -2221,6 +5113,11 @@ pub async fn get_settings(state: State<'_, AppState>) -> Result<Settings, String
 /// Save settings (also persists to DB)
 #[tauri::command]
 pub async fn save_settings(settings: Settings, state: State<'_, AppState>) -> Result<(), String> {
+    // A new decoder setup (e.g. a language model file) is checked before it is saved
+    if state.settings.lock().await.ctc_decoder != settings.ctc_decoder {
+        swap_decoder(&state.transcription_engine, &settings.ctc_decoder).await?;
+    }
+
     // Persist to DB
     let json = serde_json::to_string(&settings).map_err(|e| format!("Serialize error: {}", e))?;
     state
@@ -2314,10 +5211,6 @@ pub async fn download_model(
             .parse()
             .map_err(|e: anyhow::Error| e.to_string())?;
 
-        if !model.is_ctc() {
-            return Err("Only CTC Parakeet models are currently supported".to_string());
-        }
-
         let model_path = models::download_parakeet_model(&app, model)
             .await
             .map_err(|e| format!("Download failed: {}", e))?;
@@ -2325,11 +5218,12 @@ pub async fn download_model(
         let mut engine = TranscriptionEngine::with_backend(AsrBackendType::Parakeet)
             .map_err(|e| e.to_string())?;
         engine.set_language(&language);
+        apply_decoder_options(&mut engine, &state.settings.lock().await.ctc_decoder);
         engine
             .load_model(&model_path)
             .map_err(|e| format!("Failed to load model: {}", e))?;
 
-        *state.transcription_engine.lock().await = Some(engine);
+        *state.transcription_engine.write().await = Some(engine);
         log::info!("Parakeet model {} loaded and ready", model_name);
         return Ok(());
     }
@@ -2357,7 +5251,7 @@ pub async fn download_model(
         .load_model(&model_path)
         .map_err(|e| format!("Failed to load model: {}", e))?;
 
-    *state.transcription_engine.lock().await = Some(engine);
+    *state.transcription_engine.write().await = Some(engine);
     log::info!(
         "Whisper model {} loaded and ready with language '{}'",
         model_name,
@@ -2366,6 +5260,50 @@ pub async fn download_model(
     Ok(())
 }
 
+/// Set up the running engine's decoder. A language model can take long to load, so the
+/// decoder is built off the async runtime and without holding the engine, which is only locked
+/// to swap it in.
+#[cfg(feature = "parakeet")]
+async fn swap_decoder(
+    engine: &RwLock<Option<TranscriptionEngine>>,
+    options: &asr::DecoderOptions,
+) -> Result<(), String> {
+    let has_decoder = engine
+        .read()
+        .await
+        .as_ref()
+        .is_some_and(|e| e.backend_type() == AsrBackendType::Parakeet);
+    if !has_decoder {
+        return Ok(());
+    }
+    let options = options.clone();
+    let decoder = tokio::task::spawn_blocking(move || asr::ctc_decoder::CtcDecoder::new(&options))
+        .await
+        .map_err(|e| format!("Failed to set up decoding: {}", e))?
+        .map_err(|e| format!("Failed to set up decoding: {}", e))?;
+    if let Some(engine) = engine.write().await.as_mut() {
+        engine.set_decoder(decoder);
+    }
+    Ok(())
+}
+
+/// Only Parakeet has a decoder to set up
+#[cfg(not(feature = "parakeet"))]
+async fn swap_decoder(
+    _engine: &RwLock<Option<TranscriptionEngine>>,
+    _options: &asr::DecoderOptions,
+) -> Result<(), String> {
+    Ok(())
+}
+
+/// Set up a freshly created engine's decoder, staying with greedy decoding if that fails
+#[cfg(feature = "parakeet")]
+fn apply_decoder_options(engine: &mut TranscriptionEngine, options: &asr::DecoderOptions) {
+    if let Err(e) = engine.set_decoder_options(options) {
+        log::warn!("Failed to set up decoding, using greedy decoding: {}", e);
+    }
+}
+
 /// Load an already-downloaded model into memory (Whisper or Parakeet)
 #[tauri::command]
 pub async fn load_model(model_name: String, state: State<'_, AppState>) -> Result<(), String> {
@@ -2397,11 +5335,12 @@ pub async fn load_model(model_name: String, state: State<'_, AppState>) -> Resul
         let mut engine = TranscriptionEngine::with_backend(AsrBackendType::Parakeet)
             .map_err(|e| e.to_string())?;
         engine.set_language(&language);
+        apply_decoder_options(&mut engine, &state.settings.lock().await.ctc_decoder);
         engine
             .load_model(&model_path)
             .map_err(|e| format!("Failed to load model: {}", e))?;
 
-        *state.transcription_engine.lock().await = Some(engine);
+        *state.transcription_engine.write().await = Some(engine);
         log::info!("Parakeet model {} loaded and ready", model_name);
         return Ok(());
     }
@@ -2438,7 +5377,7 @@ pub async fn load_model(model_name: String, state: State<'_, AppState>) -> Resul
         .load_model(&model_path)
         .map_err(|e| format!("Failed to load model: {}", e))?;
 
-    *state.transcription_engine.lock().await = Some(engine);
+    *state.transcription_engine.write().await = Some(engine);
     log::info!(
         "Model {} loaded and ready with language '{}'",
         model_name,
@@ -2514,7 +5453,7 @@ pub async fn import_model(
         format!("File copied but failed to load model: {}", e)
     })?;
 
-    *state.transcription_engine.lock().await = Some(engine);
+    *state.transcription_engine.write().await = Some(engine);
     log::info!("Model {} imported and loaded successfully", model_name);
     Ok(())
 }
@@ -2549,6 +5488,25 @@ pub async fn list_audio_devices() -> Result<Vec<AudioDeviceInfo>, String> {
         .map(|d| AudioDeviceInfo {
             name: d.name,
             is_default: d.is_default,
+            configs: d.configs,
+            default_format: d.default_format,
+        })
+        .collect())
+}
+
+/// List available system audio sources (Linux monitor sources; empty on macOS,
+/// where ScreenCaptureKit always records the full system mix)
+#[tauri::command]
+pub async fn list_system_audio_sources() -> Result<Vec<SystemAudioSourceInfo>, String> {
+    let sources = crate::audio::SystemAudioCapture::list_sources()
+        .map_err(|e| format!("Failed to list system audio sources: {}", e))?;
+
+    Ok(sources
+        .into_iter()
+        .map(|s| SystemAudioSourceInfo {
+            name: s.name,
+            description: s.description,
+            is_default: s.is_default,
         })
         .collect())
 }
@@ -2579,8 +5537,9 @@ pub async fn get_model_recommendation() -> Result<ModelRecommendation, String> {
 
 /// Get available ASR backends
 #[tauri::command]
-pub async fn get_asr_backends() -> Result<Vec<BackendInfo>, String> {
-    Ok(get_available_backends())
+pub async fn get_asr_backends(state: State<'_, AppState>) -> Result<Vec<BackendInfo>, String> {
+    let model_name = state.settings.lock().await.whisper_model.clone();
+    Ok(get_available_backends(&model_name))
 }
 
 // ============================================================================
@@ -2660,17 +5619,28 @@ pub async fn embed_meeting(meeting_id: String, state: State<'_, AppState>) -> Re
         .map_err(|e| format!("DB error: {}", e))?
         .ok_or("Meeting not found")?;
 
-    let unembedded = state
-        .db
-        .get_unembedded_segment_ids(&meeting_id)
+    let count = embed_unembedded_segments(&state.db, model, &meeting_id, &meeting.title)
         .map_err(|e| format!("DB error: {}", e))?;
 
+    log::info!("Embedded {} segments for meeting {}", count, meeting_id);
+    Ok(count)
+}
+
+/// Embed every segment of a meeting that doesn't have an embedding yet. Returns how many were stored.
+fn embed_unembedded_segments(
+    db: &Database,
+    model: &EmbeddingModel,
+    meeting_id: &str,
+    meeting_title: &str,
+) -> anyhow::Result<u64> {
+    let unembedded = db.get_unembedded_segment_ids(meeting_id)?;
+
     let mut count = 0u64;
     for (seg_id, time_label, text) in &unembedded {
-        let enriched = embeddings::enrich_segment(&meeting.title, time_label, text);
+        let enriched = embeddings::enrich_segment(meeting_title, time_label, text);
         match model.embed(&enriched) {
             Ok(emb) => {
-                if let Err(e) = state.db.insert_embedding(seg_id, &emb) {
+                if let Err(e) = db.insert_embedding(seg_id, &emb) {
                     log::error!("Failed to store embedding for {}: {}", seg_id, e);
                 } else {
                     count += 1;
@@ -2681,8 +5651,6 @@ pub async fn embed_meeting(meeting_id: String, state: State<'_, AppState>) -> Re
             }
         }
     }
-
-    log::info!("Embedded {} segments for meeting {}", count, meeting_id);
     Ok(count)
 }
 
@@ -2745,16 +5713,140 @@ pub async fn import_embedding_model(
     Ok(())
 }
 
+// ============================================================================
+// Punctuation Commands
+// ============================================================================
+
+/// Download and load the punctuation model
+#[tauri::command]
+pub async fn download_punctuation_model_cmd(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let model_dir = models::download_punctuation_model(&app)
+        .await
+        .map_err(|e| format!("Download failed: {}", e))?;
+
+    let model = PunctuationModel::load(&model_dir)
+        .map_err(|e| format!("Failed to load punctuation model: {}", e))?;
+
+    *state.punctuation_model.lock().await = Some(model);
+    log::info!("Punctuation model loaded and ready");
+    Ok(())
+}
+
+/// Load an already-downloaded punctuation model
+#[tauri::command]
+pub async fn load_punctuation_model(state: State<'_, AppState>) -> Result<(), String> {
+    if !models::is_punctuation_model_downloaded() {
+        return Err("Punctuation model not downloaded".to_string());
+    }
+    let model_dir = models::get_punctuation_model_dir()
+        .map_err(|e| format!("Failed to get model dir: {}", e))?;
+
+    let model = PunctuationModel::load(&model_dir)
+        .map_err(|e| format!("Failed to load punctuation model: {}", e))?;
+
+    *state.punctuation_model.lock().await = Some(model);
+    log::info!("Punctuation model loaded");
+    Ok(())
+}
+
+/// Check if the punctuation model is downloaded
+#[tauri::command]
+pub async fn is_punctuation_model_downloaded() -> Result<bool, String> {
+    Ok(models::is_punctuation_model_downloaded())
+}
+
+/// Restore punctuation and casing in the segments of a meeting that have none (meetings
+/// transcribed before the model was loaded). Returns how many segments changed.
+#[tauri::command]
+pub async fn repunctuate_meeting(
+    meeting_id: String,
+    state: State<'_, AppState>,
+) -> Result<u64, String> {
+    let model_guard = state.punctuation_model.lock().await;
+    let model = model_guard.as_ref().ok_or("Punctuation model not loaded")?;
+
+    let segments = state
+        .db
+        .get_segments(&meeting_id)
+        .map_err(|e| format!("DB error: {}", e))?;
+
+    let mut count = 0u64;
+    for row in segments
+        .into_iter()
+        .filter(|s| punctuation::needs_restoration(&s.text))
+    {
+        let words = state
+            .db
+            .get_segment_words(&row.id)
+            .map_err(|e| format!("DB error: {}", e))?;
+        let mut segment = asr::TranscriptionSegment {
+            end_ms: export_end_ms(&row),
+            text: row.text,
+            start_ms: row.timestamp_ms,
+            words: words
+                .into_iter()
+                .map(|w| asr::WordTiming {
+                    text: w.word,
+                    start_ms: w.start_ms,
+                    end_ms: w.end_ms,
+                    probability: w.probability,
+                })
+                .collect(),
+        };
+        if let Err(e) = model.restore_segment(&mut segment) {
+            log::error!("Punctuation restoration failed for {}: {}", row.id, e);
+            continue;
+        }
+        state
+            .db
+            .update_segment_text(&row.id, &segment.text)
+            .and_then(|_| {
+                state
+                    .db
+                    .insert_segment_words(&row.id, &word_rows(&segment.words))
+            })
+            .map_err(|e| format!("DB error: {}", e))?;
+        count += 1;
+    }
+
+    log::info!(
+        "Restored punctuation in {} segments of meeting {}",
+        count,
+        meeting_id
+    );
+    Ok(count)
+}
+
 /// Get current audio level (RMS) for visualization
 #[tauri::command]
 pub async fn get_audio_level(state: State<'_, AppState>) -> Result<f32, String> {
     let audio = state.audio_capture.lock().await;
     match audio.as_ref() {
-        Some(capture) => Ok(capture.get_rms_level()),
+        Some(capture) => Ok(capture.rms_level()),
         None => Ok(0.0),
     }
 }
 
+/// Get capture ring buffer statistics (overruns, dropped samples) for the active recording
+#[tauri::command]
+pub async fn get_audio_capture_stats(
+    state: State<'_, AppState>,
+) -> Result<Option<AudioCaptureStats>, String> {
+    let audio = state.audio_capture.lock().await;
+    Ok(audio.as_ref().and_then(|capture| capture.stats()))
+}
+
+/// Get the real-time factor of each transcription worker of the current (or last) recording
+#[tauri::command]
+pub async fn get_transcription_worker_stats(
+    state: State<'_, AppState>,
+) -> Result<Vec<WorkerStats>, String> {
+    Ok(state.worker_stats.lock().unwrap().clone())
+}
+
 // ============================================================================
 // Meeting Detection Commands
 // ============================================================================
@@ -2985,9 +6077,22 @@ pub async fn get_meeting_stats(
         .get_meeting_stats(from_date.as_deref(), to_date.as_deref())
         .map_err(|e| format!("Failed to get stats: {}", e))?;
 
+    let audio_storage: Vec<MeetingStorage> = state
+        .db
+        .get_meeting_audio_dirs(from_date.as_deref(), to_date.as_deref())
+        .map_err(|e| format!("Failed to get stats: {}", e))?
+        .into_iter()
+        .map(|(meeting_id, dir)| MeetingStorage {
+            meeting_id,
+            audio_bytes: audio_dir_size(&dir),
+        })
+        .collect();
+
     Ok(MeetingStats {
         meeting_count: count,
         total_duration_ms: duration_ms,
+        total_audio_bytes: audio_storage.iter().map(|m| m.audio_bytes).sum(),
+        audio_storage,
     })
 }
 
@@ -2995,6 +6100,15 @@ pub async fn get_meeting_stats(
 pub struct MeetingStats {
     pub meeting_count: i64,
     pub total_duration_ms: i64,
+    /// Disk used by recorded meeting audio
+    pub total_audio_bytes: u64,
+    pub audio_storage: Vec<MeetingStorage>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MeetingStorage {
+    pub meeting_id: String,
+    pub audio_bytes: u64,
 }
 
 /// Extract metadata from a meeting (topics, action items, decisions)
@@ -23,7 +23,7 @@ use tauri::{
     tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
     Emitter, Manager, RunEvent, WindowEvent,
 };
-use tokio::sync::Mutex;
+use tokio::sync::{Mutex, RwLock};
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
@@ -57,22 +57,33 @@ pub fn run() {
             commands::get_model_download_url,
             commands::get_models_info,
             commands::list_audio_devices,
+            commands::list_system_audio_sources,
             commands::get_device_specs,
             commands::get_model_recommendation,
             commands::get_asr_backends,
             // Meeting persistence commands
             commands::list_meetings,
             commands::get_meeting,
+            commands::get_live_transcript,
             commands::rename_meeting,
             commands::update_meeting_tags,
             commands::toggle_pin_meeting,
+            commands::get_meeting_vocabulary,
+            commands::update_meeting_vocabulary,
             commands::delete_meeting,
+            commands::get_meeting_audio,
+            commands::get_segment_audio,
+            commands::get_meeting_audio_info,
+            commands::import_audio_file,
+            commands::get_import_extensions,
             commands::search_meetings,
             commands::export_meeting,
             commands::export_meeting_to_file,
             // Segment editing commands
             commands::update_segment,
             commands::delete_segment,
+            commands::get_segment_words,
+            commands::get_low_confidence_segments,
             // Speaker commands
             commands::list_speakers,
             commands::create_speaker,
@@ -90,7 +101,14 @@ pub fn run() {
             commands::is_embedding_model_downloaded,
             commands::get_embedding_model_download_urls,
             commands::import_embedding_model,
+            // Punctuation restoration
+            commands::download_punctuation_model_cmd,
+            commands::load_punctuation_model,
+            commands::is_punctuation_model_downloaded,
+            commands::repunctuate_meeting,
             commands::get_audio_level,
+            commands::get_audio_capture_stats,
+            commands::get_transcription_worker_stats,
             // AI features
             commands::get_meeting_stats,
             commands::extract_meeting_metadata,
@@ -144,7 +162,7 @@ pub fn run() {
 
             let state = AppState {
                 audio_capture: Arc::new(Mutex::new(None)),
-                transcription_engine: Arc::new(Mutex::new(None)),
+                transcription_engine: Arc::new(RwLock::new(None)),
                 transcript: Arc::new(Mutex::new(Vec::new())),
                 is_recording: Arc::new(Mutex::new(false)),
                 is_paused: Arc::new(Mutex::new(false)),
@@ -152,9 +170,11 @@ pub fn run() {
                 db,
                 active_meeting_id: Arc::new(Mutex::new(None)),
                 embedding_model: Arc::new(Mutex::new(None)),
+                punctuation_model: Arc::new(Mutex::new(None)),
                 meeting_detector: Arc::new(Mutex::new(MeetingDetector::new())),
                 detection_running: Arc::new(AtomicBool::new(false)),
                 pending_chunks: Arc::new(AtomicUsize::new(0)),
+                worker_stats: Arc::new(std::sync::Mutex::new(Vec::new())),
             };
 
             // Auto-start meeting detection if enabled in settings
@@ -291,6 +291,19 @@ impl LlmClient {
         Ok(vec![result.trim().to_string()])
     }
 
+    /// Translate a transcript segment into `target_language` (a language code such as "en")
+    pub async fn translate(&self, text: &str, target_language: &str) -> Result<String> {
+        let system = "You are a translator for meeting transcripts. Translate the given text into \
+                      the requested language, keeping names, numbers and technical terms as they are. \
+                      Return ONLY the translation, nothing else.";
+        let user = format!(
+            "Translate into the language with code \"{}\":\n\n{}",
+            target_language, text
+        );
+        let result = self.complete(system, &user).await?;
+        Ok(result.trim().to_string())
+    }
+
     /// Detect if text contains a question
     pub async fn detect_question(&self, text: &str) -> Result<bool> {
         let system = "You are a helpful assistant. Determine if the given text contains a question being asked. \
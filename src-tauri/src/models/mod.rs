@@ -330,62 +330,32 @@ fn emit_progress(
     }
 }
 
-/// Download a Parakeet ONNX model (and its vocab file) with progress events
-#[cfg(feature = "parakeet")]
-pub async fn download_parakeet_model(app: &AppHandle, model: ParakeetModel) -> Result<PathBuf> {
-    let model_path = get_parakeet_model_path(model)?;
-    let model_name = format!("parakeet-{:?}", model).to_lowercase();
-
-    // Check if already downloaded
-    if model_path.exists() {
-        log::info!(
-            "Parakeet model {} already downloaded at {:?}",
-            model_name,
-            model_path
-        );
-        emit_progress(app, &model_name, 0, 0, 100.0, DownloadStatus::Completed);
-        return Ok(model_path);
-    }
-
-    log::info!(
-        "Starting download of Parakeet model {} from {}",
-        model_name,
-        model.download_url()
-    );
-    emit_progress(
-        app,
-        &model_name,
-        0,
-        model.size_mb() * 1024 * 1024,
-        0.0,
-        DownloadStatus::Starting,
-    );
-
-    let client = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(3600))
-        .build()?;
-
-    // Download the ONNX model
-    let response = client.get(model.download_url()).send().await?;
-
+/// Stream `url` into `path` through a temp file renamed into place once complete, so an
+/// interrupted download never leaves a truncated file that passes for a downloaded one.
+/// Progress is reported for `model_name` with `offset` of `total` bytes already done.
+/// Returns the size of the file.
+async fn download_file(
+    app: &AppHandle,
+    client: &reqwest::Client,
+    url: &str,
+    path: &Path,
+    model_name: &str,
+    offset: u64,
+    total: u64,
+) -> Result<u64> {
+    let response = client.get(url).send().await?;
     if !response.status().is_success() {
-        let status = response.status();
-        emit_progress(app, &model_name, 0, 0, 0.0, DownloadStatus::Failed);
-        return Err(anyhow!("Download failed with status: {}", status));
+        emit_progress(app, model_name, 0, 0, 0.0, DownloadStatus::Failed);
+        return Err(anyhow!(
+            "Download of {} failed with status: {}",
+            url,
+            response.status()
+        ));
     }
+    let total = total.max(offset + response.content_length().unwrap_or(0));
 
-    let total_size = response
-        .content_length()
-        .unwrap_or(model.size_mb() * 1024 * 1024);
-
-    // Ensure models directory exists
-    let models_dir = get_parakeet_models_dir()?;
-    std::fs::create_dir_all(&models_dir)?;
-
-    // Download to temp file
-    let temp_path = model_path.with_extension("tmp");
+    let temp_path = path.with_extension("tmp");
     let mut file = tokio::fs::File::create(&temp_path).await?;
-
     let mut downloaded: u64 = 0;
     let mut stream = response.bytes_stream();
     let mut last_progress_update = std::time::Instant::now();
@@ -396,13 +366,13 @@ pub async fn download_parakeet_model(app: &AppHandle, model: ParakeetModel) -> R
         downloaded += chunk.len() as u64;
 
         if last_progress_update.elapsed() >= std::time::Duration::from_millis(100) {
-            let percentage = (downloaded as f32 / total_size as f32) * 100.0;
+            let done = (offset + downloaded).min(total);
             emit_progress(
                 app,
-                &model_name,
-                downloaded,
-                total_size,
-                percentage,
+                model_name,
+                done,
+                total,
+                (done as f32 / total.max(1) as f32) * 100.0,
                 DownloadStatus::Downloading,
             );
             last_progress_update = std::time::Instant::now();
@@ -411,8 +381,65 @@ pub async fn download_parakeet_model(app: &AppHandle, model: ParakeetModel) -> R
 
     file.flush().await?;
     drop(file);
-    tokio::fs::rename(&temp_path, &model_path).await?;
+    tokio::fs::rename(&temp_path, path).await?;
+    Ok(downloaded)
+}
 
+/// Download a Parakeet ONNX model (with a transducer's decoder and joiner, and the vocab file)
+/// with progress events
+#[cfg(feature = "parakeet")]
+pub async fn download_parakeet_model(app: &AppHandle, model: ParakeetModel) -> Result<PathBuf> {
+    let model_path = get_parakeet_model_path(model)?;
+    let model_name = format!("parakeet-{}", model.name());
+    let models_dir = get_parakeet_models_dir()?;
+
+    // Check if already downloaded
+    if is_parakeet_model_downloaded(model)? {
+        log::info!(
+            "Parakeet model {} already downloaded at {:?}",
+            model_name,
+            model_path
+        );
+        emit_progress(app, &model_name, 0, 0, 100.0, DownloadStatus::Completed);
+        return Ok(model_path);
+    }
+
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(3600))
+        .build()?;
+
+    // Download the encoder (or the whole CTC model), then the transducer's prediction and
+    // joint networks; the model size covers all of them
+    let mut total_size = model.size_mb() * 1024 * 1024;
+    let mut downloaded: u64 = 0;
+    emit_progress(
+        app,
+        &model_name,
+        0,
+        total_size,
+        0.0,
+        DownloadStatus::Starting,
+    );
+    let files =
+        std::iter::once((model.filename(), model.download_url())).chain(model.transducer_files());
+    for (filename, url) in files {
+        let path = models_dir.join(&filename);
+        if path.exists() {
+            continue;
+        }
+        log::info!("Downloading {} for {} from {}", filename, model_name, url);
+        downloaded += download_file(
+            app,
+            &client,
+            &url,
+            &path,
+            &model_name,
+            downloaded,
+            total_size,
+        )
+        .await?;
+    }
+    total_size = total_size.max(downloaded);
     log::info!("Parakeet model {} downloaded successfully", model_name);
 
     // Also try to download the vocab file (non-fatal if it fails)
@@ -480,19 +507,21 @@ pub fn get_all_models_status() -> Result<Vec<ModelInfo>> {
         })
         .collect::<Result<Vec<_>>>()?;
 
-    // Add Parakeet CTC models only when feature is enabled
+    // Add Parakeet models only when feature is enabled
     #[cfg(feature = "parakeet")]
     {
         let parakeet_models = vec![
             (ParakeetModel::Ctc06b, false),
             (ParakeetModel::Ctc11b, false),
+            (ParakeetModel::Rnnt06b, false),
+            (ParakeetModel::Rnnt11b, false),
+            (ParakeetModel::Tdt06bV2, false),
+            (ParakeetModel::Tdt06bV3, false),
         ];
 
         for (model, recommended) in parakeet_models {
             models.push(ModelInfo {
-                name: format!("parakeet-{:?}", model)
-                    .to_lowercase()
-                    .replace("ctc", "ctc-"),
+                name: format!("parakeet-{}", model.name()),
                 size_mb: model.size_mb(),
                 downloaded: is_parakeet_model_downloaded(model).unwrap_or(false),
                 recommended,
@@ -751,6 +780,88 @@ fn extract_embedding_from_zip(zip_path: &Path, target_dir: &Path) -> Result<()>
     Ok(())
 }
 
+// ============================================================================
+// Punctuation Model Download (BERT punctuation and casing restoration)
+// ============================================================================
+
+const PUNCTUATION_MODEL_DIR: &str = "bert-restore-punctuation";
+const PUNCTUATION_MODEL_URL: &str =
+    "https://huggingface.co/felflare/bert-restore-punctuation/resolve/main/onnx/model.onnx";
+/// Small files next to the model: tokenizer and label map
+const PUNCTUATION_SIDE_FILES: [(&str, &str); 2] = [
+    (
+        "tokenizer.json",
+        "https://huggingface.co/felflare/bert-restore-punctuation/resolve/main/tokenizer.json",
+    ),
+    (
+        "config.json",
+        "https://huggingface.co/felflare/bert-restore-punctuation/resolve/main/config.json",
+    ),
+];
+
+/// Get the directory where the punctuation model is stored
+pub fn get_punctuation_model_dir() -> Result<PathBuf> {
+    let models_dir = crate::asr::get_models_dir()?;
+    Ok(models_dir.join(PUNCTUATION_MODEL_DIR))
+}
+
+/// Check if the punctuation model is downloaded (model.onnx, tokenizer.json and config.json)
+pub fn is_punctuation_model_downloaded() -> bool {
+    match get_punctuation_model_dir() {
+        Ok(dir) => {
+            dir.join("model.onnx").exists()
+                && PUNCTUATION_SIDE_FILES
+                    .iter()
+                    .all(|(name, _)| dir.join(name).exists())
+        }
+        Err(_) => false,
+    }
+}
+
+/// Download the punctuation model with progress events
+pub async fn download_punctuation_model(app: &AppHandle) -> Result<PathBuf> {
+    let model_dir = get_punctuation_model_dir()?;
+    std::fs::create_dir_all(&model_dir)?;
+    let model_name = PUNCTUATION_MODEL_DIR;
+
+    if is_punctuation_model_downloaded() {
+        log::info!("Punctuation model already downloaded at {:?}", model_dir);
+        emit_progress(app, model_name, 0, 0, 100.0, DownloadStatus::Completed);
+        return Ok(model_dir);
+    }
+
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(3600))
+        .build()?;
+
+    // model.onnx (~430MB), then the tokenizer and config
+    let total_size: u64 = 430 * 1024 * 1024;
+    let mut downloaded: u64 = 0;
+    emit_progress(
+        app,
+        model_name,
+        0,
+        total_size,
+        0.0,
+        DownloadStatus::Starting,
+    );
+    let files =
+        std::iter::once(("model.onnx", PUNCTUATION_MODEL_URL)).chain(PUNCTUATION_SIDE_FILES);
+    for (name, url) in files {
+        let path = model_dir.join(name);
+        if path.exists() {
+            continue;
+        }
+        downloaded +=
+            download_file(app, &client, url, &path, model_name, downloaded, total_size).await?;
+        log::info!("Punctuation {} downloaded", name);
+    }
+
+    log::info!("Punctuation model fully downloaded at {:?}", model_dir);
+    emit_progress(app, model_name, 0, 0, 100.0, DownloadStatus::Completed);
+    Ok(model_dir)
+}
+
 /// Get total disk space used by models
 pub fn get_models_disk_usage() -> Result<u64> {
     let models_dir = get_models_dir()?;
@@ -55,6 +55,9 @@ pub struct SegmentRow {
     pub time_label: String,
     pub text: String,
     pub timestamp_ms: i64,
+    /// End of the speech on the recording timeline. None for rows stored before end times were.
+    #[serde(default)]
+    pub end_ms: Option<i64>,
     pub speaker_id: Option<String>,
     /// "mic" = local user, "system" = remote via SCK. Defaults to "mic" for old rows.
     #[serde(default)]
@@ -66,6 +69,39 @@ pub struct SegmentRow {
     pub is_question: bool,
     #[serde(default)]
     pub question_answer: Option<String>,
+    /// Language the segment was transcribed in. None for older rows.
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Detection probability of `language` (None = set for the source, not detected)
+    #[serde(default)]
+    pub language_probability: Option<f32>,
+    /// Second text in another language, when translation is on
+    #[serde(default)]
+    pub translation: Option<String>,
+}
+
+/// A word of a segment with its timing and ASR confidence
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct WordRow {
+    pub word: String,
+    pub start_ms: i64,
+    pub end_ms: i64,
+    pub probability: f32,
+}
+
+/// A segment with words the ASR was unsure of, for review
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LowConfidenceSegment {
+    pub segment_id: String,
+    pub text: String,
+    pub time_label: String,
+    pub timestamp_ms: i64,
+    pub end_ms: Option<i64>,
+    pub source: Option<String>,
+    /// Mean probability of all the segment's words
+    pub confidence: f32,
+    /// Words below the threshold, in order
+    pub uncertain_words: Vec<WordRow>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -261,6 +297,88 @@ impl Database {
             log::info!("Added source column to transcript_segments table");
         }
 
+        // Migration: add audio_dir column to meetings (directory holding per-source recordings)
+        let has_audio_dir: bool = {
+            let mut stmt = conn.prepare(
+                "SELECT COUNT(*) FROM pragma_table_info('meetings') WHERE name='audio_dir'",
+            )?;
+            let count: i64 = stmt.query_row([], |row| row.get(0))?;
+            count > 0
+        };
+        if !has_audio_dir {
+            conn.execute_batch("ALTER TABLE meetings ADD COLUMN audio_dir TEXT;")?;
+            log::info!("Added audio_dir column to meetings table");
+        }
+
+        // Migration: add vocabulary column to meetings (terms to bias transcription toward)
+        let has_vocabulary: bool = {
+            let mut stmt = conn.prepare(
+                "SELECT COUNT(*) FROM pragma_table_info('meetings') WHERE name='vocabulary'",
+            )?;
+            let count: i64 = stmt.query_row([], |row| row.get(0))?;
+            count > 0
+        };
+        if !has_vocabulary {
+            conn.execute_batch("ALTER TABLE meetings ADD COLUMN vocabulary TEXT;")?;
+            log::info!("Added vocabulary column to meetings table");
+        }
+
+        // Migration: add end_ms column to transcript_segments (NULL for older rows)
+        let has_end_ms: bool = {
+            let mut stmt = conn.prepare(
+                "SELECT COUNT(*) FROM pragma_table_info('transcript_segments') WHERE name='end_ms'",
+            )?;
+            let count: i64 = stmt.query_row([], |row| row.get(0))?;
+            count > 0
+        };
+        if !has_end_ms {
+            conn.execute_batch("ALTER TABLE transcript_segments ADD COLUMN end_ms INTEGER;")?;
+            log::info!("Added end_ms column to transcript_segments table");
+        }
+
+        // Migration: add language columns to transcript_segments (NULL for older rows)
+        let has_language: bool = {
+            let mut stmt = conn.prepare(
+                "SELECT COUNT(*) FROM pragma_table_info('transcript_segments') WHERE name='language'",
+            )?;
+            let count: i64 = stmt.query_row([], |row| row.get(0))?;
+            count > 0
+        };
+        if !has_language {
+            conn.execute_batch(
+                "ALTER TABLE transcript_segments ADD COLUMN language TEXT;
+                 ALTER TABLE transcript_segments ADD COLUMN language_probability REAL;",
+            )?;
+            log::info!("Added language columns to transcript_segments table");
+        }
+
+        // Migration: add translation column to transcript_segments
+        let has_translation: bool = {
+            let mut stmt = conn.prepare(
+                "SELECT COUNT(*) FROM pragma_table_info('transcript_segments') WHERE name='translation'",
+            )?;
+            let count: i64 = stmt.query_row([], |row| row.get(0))?;
+            count > 0
+        };
+        if !has_translation {
+            conn.execute_batch("ALTER TABLE transcript_segments ADD COLUMN translation TEXT;")?;
+            log::info!("Added translation column to transcript_segments table");
+        }
+
+        // Word timings and confidences of each segment
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS segment_words (
+                segment_id TEXT NOT NULL,
+                position INTEGER NOT NULL,
+                word TEXT NOT NULL,
+                start_ms INTEGER NOT NULL,
+                end_ms INTEGER NOT NULL,
+                probability REAL NOT NULL,
+                PRIMARY KEY (segment_id, position),
+                FOREIGN KEY (segment_id) REFERENCES transcript_segments(id) ON DELETE CASCADE
+            );",
+        )?;
+
         // Create speakers table if it doesn't exist
         conn.execute_batch(
             "CREATE TABLE IF NOT EXISTS speakers (
@@ -271,6 +389,36 @@ impl Database {
             );",
         )?;
 
+        // Live transcripts kept for comparison after a final pass replaced them
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS live_segments (
+                id TEXT PRIMARY KEY,
+                meeting_id TEXT NOT NULL,
+                time_label TEXT NOT NULL,
+                text TEXT NOT NULL,
+                timestamp_ms INTEGER NOT NULL,
+                end_ms INTEGER,
+                source TEXT,
+                FOREIGN KEY (meeting_id) REFERENCES meetings(id) ON DELETE CASCADE
+            );",
+        )?;
+
+        // Migration: add language and translation columns to live_segments
+        let has_live_language: bool = {
+            let mut stmt = conn.prepare(
+                "SELECT COUNT(*) FROM pragma_table_info('live_segments') WHERE name='language'",
+            )?;
+            let count: i64 = stmt.query_row([], |row| row.get(0))?;
+            count > 0
+        };
+        if !has_live_language {
+            conn.execute_batch(
+                "ALTER TABLE live_segments ADD COLUMN language TEXT;
+                 ALTER TABLE live_segments ADD COLUMN translation TEXT;",
+            )?;
+            log::info!("Added language columns to live_segments table");
+        }
+
         // Create meeting_conversations table for storing Q&A history
         conn.execute_batch(
             "CREATE TABLE IF NOT EXISTS meeting_conversations (
@@ -428,6 +576,29 @@ impl Database {
         Ok(())
     }
 
+    /// Terms specific to this meeting, as the user entered them
+    pub fn get_meeting_vocabulary(&self, id: &str) -> Result<Option<String>> {
+        let conn = self.conn.lock().unwrap();
+        let vocabulary = conn
+            .query_row(
+                "SELECT vocabulary FROM meetings WHERE id = ?1",
+                params![id],
+                |row| row.get(0),
+            )
+            .optional()?
+            .flatten();
+        Ok(vocabulary)
+    }
+
+    pub fn update_meeting_vocabulary(&self, id: &str, vocabulary: Option<&str>) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE meetings SET vocabulary = ?1 WHERE id = ?2",
+            params![vocabulary, id],
+        )?;
+        Ok(())
+    }
+
     pub fn set_meeting_pinned(&self, id: &str, pinned: bool) -> Result<()> {
         let conn = self.conn.lock().unwrap();
         conn.execute(
@@ -457,6 +628,53 @@ impl Database {
         }
     }
 
+    pub fn set_meeting_audio_dir(&self, id: &str, audio_dir: Option<&str>) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE meetings SET audio_dir = ?1 WHERE id = ?2",
+            params![audio_dir, id],
+        )?;
+        Ok(())
+    }
+
+    pub fn get_meeting_audio_dir(&self, id: &str) -> Result<Option<String>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare("SELECT audio_dir FROM meetings WHERE id = ?1")?;
+        let mut rows = stmt.query_map(params![id], |row| row.get::<_, Option<String>>(0))?;
+        match rows.next() {
+            Some(Ok(val)) => Ok(val),
+            Some(Err(e)) => Err(e.into()),
+            None => Ok(None),
+        }
+    }
+
+    /// (meeting_id, audio_dir) for meetings with recorded audio, optionally within a date range
+    pub fn get_meeting_audio_dirs(
+        &self,
+        from_date: Option<&str>,
+        to_date: Option<&str>,
+    ) -> Result<Vec<(String, String)>> {
+        let conn = self.conn.lock().unwrap();
+        let map_row = |row: &rusqlite::Row| -> rusqlite::Result<(String, String)> {
+            Ok((row.get(0)?, row.get(1)?))
+        };
+        let mut stmt;
+        let rows = if let (Some(from), Some(to)) = (from_date, to_date) {
+            stmt = conn.prepare(
+                "SELECT id, audio_dir FROM meetings
+                 WHERE audio_dir IS NOT NULL AND created_at >= ?1 AND created_at <= ?2",
+            )?;
+            stmt.query_map(params![from, to], map_row)?
+                .collect::<std::result::Result<Vec<_>, _>>()?
+        } else {
+            stmt =
+                conn.prepare("SELECT id, audio_dir FROM meetings WHERE audio_dir IS NOT NULL")?;
+            stmt.query_map([], map_row)?
+                .collect::<std::result::Result<Vec<_>, _>>()?
+        };
+        Ok(rows)
+    }
+
     // ========================================================================
     // Conversations (Q&A history per meeting)
     // ========================================================================
@@ -521,7 +739,8 @@ impl Database {
         Ok(rows)
     }
 
-    /// Get segments within a timestamp range for a meeting (for time-based queries)
+    /// Get segments overlapping a time range of a meeting (for time-based queries).
+    /// Rows without an end time count as instants at their start.
     pub fn get_segments_in_range(
         &self,
         meeting_id: &str,
@@ -530,9 +749,9 @@ impl Database {
     ) -> Result<Vec<SegmentRow>> {
         let conn = self.conn.lock().unwrap();
         let mut stmt = conn.prepare(
-            "SELECT id, meeting_id, time_label, text, timestamp_ms, speaker_id
+            "SELECT id, meeting_id, time_label, text, timestamp_ms, end_ms, speaker_id, source
              FROM transcript_segments
-             WHERE meeting_id = ?1 AND timestamp_ms >= ?2 AND timestamp_ms <= ?3
+             WHERE meeting_id = ?1 AND COALESCE(end_ms, timestamp_ms) >= ?2 AND timestamp_ms <= ?3
              ORDER BY timestamp_ms ASC",
         )?;
         let segments = stmt
@@ -543,22 +762,37 @@ impl Database {
                     time_label: row.get(2)?,
                     text: row.get(3)?,
                     timestamp_ms: row.get(4)?,
-                    speaker_id: row.get(5)?,
-                    source: None,
+                    end_ms: row.get(5)?,
+                    speaker_id: row.get(6)?,
+                    source: row.get(7)?,
                     enhanced_text: None,
                     is_question: false,
                     question_answer: None,
+                    language: None,
+                    language_probability: None,
+                    translation: None,
                 })
             })?
             .collect::<std::result::Result<Vec<_>, _>>()?;
         Ok(segments)
     }
 
+    /// Where the last speech of a meeting ends (start of the last segment for older rows)
+    pub fn get_transcript_end_ms(&self, meeting_id: &str) -> Result<Option<i64>> {
+        let conn = self.conn.lock().unwrap();
+        let end = conn.query_row(
+            "SELECT MAX(COALESCE(end_ms, timestamp_ms)) FROM transcript_segments WHERE meeting_id = ?1",
+            params![meeting_id],
+            |row| row.get(0),
+        )?;
+        Ok(end)
+    }
+
     /// Get the last N segments of a meeting (for "what did they just say")
     pub fn get_last_segments(&self, meeting_id: &str, limit: usize) -> Result<Vec<SegmentRow>> {
         let conn = self.conn.lock().unwrap();
         let mut stmt = conn.prepare(
-            "SELECT id, meeting_id, time_label, text, timestamp_ms, speaker_id
+            "SELECT id, meeting_id, time_label, text, timestamp_ms, end_ms, speaker_id
              FROM transcript_segments
              WHERE meeting_id = ?1
              ORDER BY timestamp_ms DESC
@@ -572,11 +806,15 @@ impl Database {
                     time_label: row.get(2)?,
                     text: row.get(3)?,
                     timestamp_ms: row.get(4)?,
-                    speaker_id: row.get(5)?,
+                    end_ms: row.get(5)?,
+                    speaker_id: row.get(6)?,
                     enhanced_text: None,
                     is_question: false,
                     question_answer: None,
                     source: None,
+                    language: None,
+                    language_probability: None,
+                    translation: None,
                 })
             })?
             .collect::<std::result::Result<Vec<_>, _>>()?;
@@ -598,8 +836,8 @@ impl Database {
     pub fn insert_segment(&self, seg: &SegmentRow) -> Result<()> {
         let conn = self.conn.lock().unwrap();
         conn.execute(
-            "INSERT INTO transcript_segments (id, meeting_id, time_label, text, timestamp_ms, speaker_id, source) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
-            params![seg.id, seg.meeting_id, seg.time_label, seg.text, seg.timestamp_ms, seg.speaker_id, seg.source],
+            "INSERT INTO transcript_segments (id, meeting_id, time_label, text, timestamp_ms, end_ms, speaker_id, source, language, language_probability, translation) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            params![seg.id, seg.meeting_id, seg.time_label, seg.text, seg.timestamp_ms, seg.end_ms, seg.speaker_id, seg.source, seg.language, seg.language_probability, seg.translation],
         )?;
         Ok(())
     }
@@ -607,7 +845,7 @@ impl Database {
     pub fn get_segments(&self, meeting_id: &str) -> Result<Vec<SegmentRow>> {
         let conn = self.conn.lock().unwrap();
         let mut stmt = conn.prepare(
-            "SELECT id, meeting_id, time_label, text, timestamp_ms, speaker_id, source, enhanced_text, is_question, question_answer FROM transcript_segments WHERE meeting_id = ?1 ORDER BY timestamp_ms ASC",
+            "SELECT id, meeting_id, time_label, text, timestamp_ms, speaker_id, source, enhanced_text, is_question, question_answer, end_ms, language, language_probability, translation FROM transcript_segments WHERE meeting_id = ?1 ORDER BY timestamp_ms ASC",
         )?;
         let segments = stmt
             .query_map(params![meeting_id], |row| {
@@ -622,12 +860,248 @@ impl Database {
                     enhanced_text: row.get(7)?,
                     is_question: row.get::<_, i32>(8)? != 0,
                     question_answer: row.get(9)?,
+                    end_ms: row.get(10)?,
+                    language: row.get(11)?,
+                    language_probability: row.get(12)?,
+                    translation: row.get(13)?,
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(segments)
+    }
+
+    pub fn get_segment(&self, segment_id: &str) -> Result<Option<SegmentRow>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, meeting_id, time_label, text, timestamp_ms, speaker_id, source, enhanced_text, is_question, question_answer, end_ms, language, language_probability, translation FROM transcript_segments WHERE id = ?1",
+        )?;
+        let segment = stmt
+            .query_row(params![segment_id], |row| {
+                Ok(SegmentRow {
+                    id: row.get(0)?,
+                    meeting_id: row.get(1)?,
+                    time_label: row.get(2)?,
+                    text: row.get(3)?,
+                    timestamp_ms: row.get(4)?,
+                    speaker_id: row.get(5)?,
+                    source: row.get(6)?,
+                    enhanced_text: row.get(7)?,
+                    is_question: row.get::<_, i32>(8)? != 0,
+                    question_answer: row.get(9)?,
+                    end_ms: row.get(10)?,
+                    language: row.get(11)?,
+                    language_probability: row.get(12)?,
+                    translation: row.get(13)?,
+                })
+            })
+            .optional()?;
+        Ok(segment)
+    }
+
+    /// Replace the segments of a meeting (with their words) in one transaction, except those
+    /// in `keep`, which stay as they are. Embeddings of the old segments go with them; the
+    /// full-text index follows through its triggers.
+    /// With `keep_live`, the old segments are kept in `live_segments` for comparison.
+    pub fn replace_segments(
+        &self,
+        meeting_id: &str,
+        segments: &[(SegmentRow, Vec<WordRow>)],
+        keep: &[String],
+        keep_live: bool,
+    ) -> Result<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        if keep_live {
+            tx.execute(
+                "DELETE FROM live_segments WHERE meeting_id = ?1",
+                params![meeting_id],
+            )?;
+            tx.execute(
+                "INSERT INTO live_segments (id, meeting_id, time_label, text, timestamp_ms, end_ms, source, language, translation) SELECT id, meeting_id, time_label, text, timestamp_ms, end_ms, source, language, translation FROM transcript_segments WHERE meeting_id = ?1",
+                params![meeting_id],
+            )?;
+        }
+        let old: Vec<String> = {
+            let mut stmt =
+                tx.prepare("SELECT id FROM transcript_segments WHERE meeting_id = ?1")?;
+            let ids = stmt
+                .query_map(params![meeting_id], |row| row.get(0))?
+                .collect::<std::result::Result<Vec<String>, _>>()?;
+            ids.into_iter().filter(|id| !keep.contains(id)).collect()
+        };
+        for id in &old {
+            tx.execute(
+                "DELETE FROM segment_embeddings WHERE segment_id = ?1",
+                params![id],
+            )?;
+            tx.execute(
+                "DELETE FROM segment_words WHERE segment_id = ?1",
+                params![id],
+            )?;
+            tx.execute("DELETE FROM transcript_segments WHERE id = ?1", params![id])?;
+        }
+        {
+            let mut insert_segment = tx.prepare(
+                "INSERT INTO transcript_segments (id, meeting_id, time_label, text, timestamp_ms, end_ms, speaker_id, source, enhanced_text, is_question, question_answer, language, language_probability, translation) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
+            )?;
+            let mut insert_word = tx.prepare(
+                "INSERT INTO segment_words (segment_id, position, word, start_ms, end_ms, probability) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            )?;
+            for (seg, words) in segments {
+                insert_segment.execute(params![
+                    seg.id,
+                    seg.meeting_id,
+                    seg.time_label,
+                    seg.text,
+                    seg.timestamp_ms,
+                    seg.end_ms,
+                    seg.speaker_id,
+                    seg.source,
+                    seg.enhanced_text,
+                    seg.is_question,
+                    seg.question_answer,
+                    seg.language,
+                    seg.language_probability,
+                    seg.translation
+                ])?;
+                for (i, w) in words.iter().enumerate() {
+                    insert_word.execute(params![
+                        seg.id,
+                        i as i64,
+                        w.word,
+                        w.start_ms,
+                        w.end_ms,
+                        w.probability
+                    ])?;
+                }
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// The live transcript of a meeting whose segments a final pass replaced, if it was kept
+    pub fn get_live_segments(&self, meeting_id: &str) -> Result<Vec<SegmentRow>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, meeting_id, time_label, text, timestamp_ms, end_ms, source, language, translation FROM live_segments WHERE meeting_id = ?1 ORDER BY timestamp_ms ASC",
+        )?;
+        let segments = stmt
+            .query_map(params![meeting_id], |row| {
+                Ok(SegmentRow {
+                    id: row.get(0)?,
+                    meeting_id: row.get(1)?,
+                    time_label: row.get(2)?,
+                    text: row.get(3)?,
+                    timestamp_ms: row.get(4)?,
+                    end_ms: row.get(5)?,
+                    source: row.get(6)?,
+                    language: row.get(7)?,
+                    translation: row.get(8)?,
+                    ..Default::default()
                 })
             })?
             .collect::<std::result::Result<Vec<_>, _>>()?;
         Ok(segments)
     }
 
+    /// Store the words of a segment, replacing any stored before
+    pub fn insert_segment_words(&self, segment_id: &str, words: &[WordRow]) -> Result<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        tx.execute(
+            "DELETE FROM segment_words WHERE segment_id = ?1",
+            params![segment_id],
+        )?;
+        {
+            let mut stmt = tx.prepare(
+                "INSERT INTO segment_words (segment_id, position, word, start_ms, end_ms, probability) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            )?;
+            for (i, w) in words.iter().enumerate() {
+                stmt.execute(params![
+                    segment_id,
+                    i as i64,
+                    w.word,
+                    w.start_ms,
+                    w.end_ms,
+                    w.probability
+                ])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub fn get_segment_words(&self, segment_id: &str) -> Result<Vec<WordRow>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT word, start_ms, end_ms, probability FROM segment_words WHERE segment_id = ?1 ORDER BY position ASC",
+        )?;
+        let words = stmt
+            .query_map(params![segment_id], |row| {
+                Ok(WordRow {
+                    word: row.get(0)?,
+                    start_ms: row.get(1)?,
+                    end_ms: row.get(2)?,
+                    probability: row.get(3)?,
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(words)
+    }
+
+    /// Segments of a meeting with at least one word below `threshold`, in transcript order
+    pub fn get_low_confidence_segments(
+        &self,
+        meeting_id: &str,
+        threshold: f32,
+    ) -> Result<Vec<LowConfidenceSegment>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT ts.id, ts.text, ts.time_label, ts.timestamp_ms, ts.end_ms, ts.source,
+                    AVG(sw.probability)
+             FROM transcript_segments ts
+             JOIN segment_words sw ON sw.segment_id = ts.id
+             WHERE ts.meeting_id = ?1
+             GROUP BY ts.id
+             HAVING MIN(sw.probability) < ?2
+             ORDER BY ts.timestamp_ms ASC",
+        )?;
+        let mut segments = stmt
+            .query_map(params![meeting_id, threshold], |row| {
+                Ok(LowConfidenceSegment {
+                    segment_id: row.get(0)?,
+                    text: row.get(1)?,
+                    time_label: row.get(2)?,
+                    timestamp_ms: row.get(3)?,
+                    end_ms: row.get(4)?,
+                    source: row.get(5)?,
+                    confidence: row.get::<_, f64>(6)? as f32,
+                    uncertain_words: Vec::new(),
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        let mut word_stmt = conn.prepare(
+            "SELECT word, start_ms, end_ms, probability FROM segment_words
+             WHERE segment_id = ?1 AND probability < ?2
+             ORDER BY position ASC",
+        )?;
+        for segment in &mut segments {
+            segment.uncertain_words = word_stmt
+                .query_map(params![segment.segment_id, threshold], |row| {
+                    Ok(WordRow {
+                        word: row.get(0)?,
+                        start_ms: row.get(1)?,
+                        end_ms: row.get(2)?,
+                        probability: row.get(3)?,
+                    })
+                })?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+        }
+        Ok(segments)
+    }
+
     /// Update segment text
     pub fn update_segment_text(&self, segment_id: &str, text: &str) -> Result<()> {
         let conn = self.conn.lock().unwrap();
@@ -638,6 +1112,24 @@ impl Database {
         Ok(())
     }
 
+    pub fn update_segment_translation(&self, segment_id: &str, translation: &str) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE transcript_segments SET translation = ?1 WHERE id = ?2",
+            params![translation, segment_id],
+        )?;
+        Ok(())
+    }
+
+    pub fn update_segment_end_ms(&self, segment_id: &str, end_ms: i64) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE transcript_segments SET end_ms = ?1 WHERE id = ?2",
+            params![end_ms, segment_id],
+        )?;
+        Ok(())
+    }
+
     /// Update segment enhanced text
     pub fn update_segment_enhanced_text(
         &self,
@@ -697,11 +1189,15 @@ impl Database {
     /// Delete a segment
     pub fn delete_segment(&self, segment_id: &str) -> Result<()> {
         let conn = self.conn.lock().unwrap();
-        // Also delete embedding if exists
+        // Also delete embedding and words if they exist
         conn.execute(
             "DELETE FROM segment_embeddings WHERE segment_id = ?1",
             params![segment_id],
         )?;
+        conn.execute(
+            "DELETE FROM segment_words WHERE segment_id = ?1",
+            params![segment_id],
+        )?;
         conn.execute(
             "DELETE FROM transcript_segments WHERE id = ?1",
             params![segment_id],
@@ -832,6 +1328,16 @@ impl Database {
         Ok(())
     }
 
+    /// Remove a segment's embedding (its text changed)
+    pub fn delete_embedding(&self, segment_id: &str) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "DELETE FROM segment_embeddings WHERE segment_id = ?1",
+            params![segment_id],
+        )?;
+        Ok(())
+    }
+
     /// Cosine similarity search across embeddings, optionally scoped to one meeting
     pub fn search_semantic(
         &self,
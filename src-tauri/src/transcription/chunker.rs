@@ -0,0 +1,333 @@
+// Speech-boundary chunker
+// Turns a continuous 16 kHz stream into transcription chunks that start just before speech
+// and end on a pause, using the voice activity detector.
+//
+// Every sample pushed advances the timeline, whether or not it ends up in a chunk, so
+// `start_ms` stays exact across skipped silence. Chunks ending on a pause share no audio; a
+// chunk split off mid-speech is followed by one starting `overlap_secs` before the split.
+
+use super::TranscriptionConfig;
+use crate::audio::vad::{frame_energy_db, VoiceActivityDetector};
+
+/// A speech region ready for transcription
+#[derive(Debug)]
+pub struct SpeechChunk {
+    /// 16 kHz mono samples
+    pub samples: Vec<f32>,
+    /// Start position on the recording timeline (milliseconds)
+    pub start_ms: i64,
+    pub duration_ms: i64,
+}
+
+pub struct SpeechChunker {
+    vad: VoiceActivityDetector,
+    frame_samples: usize,
+    min_chunk_samples: usize,
+    max_chunk_samples: usize,
+    /// Frames repeated at the start of the chunk after a split in speech
+    overlap_frames: usize,
+    min_speech_frames: usize,
+    end_pause_frames: usize,
+    /// A short chunk is still emitted once the pause gets this long
+    long_pause_frames: usize,
+    pre_roll_frames: usize,
+    /// Trailing silence kept on an emitted chunk so final consonants aren't clipped
+    tail_frames: usize,
+    /// Samples not yet forming a full frame
+    pending: Vec<f32>,
+    /// Current chunk (or pre-roll while idle), whole frames only
+    buffer: Vec<f32>,
+    /// Energy of each frame in `buffer`, used to pick cut points
+    frame_energies: Vec<f32>,
+    /// Timeline position of `buffer[0]` in samples
+    buffer_start: u64,
+    in_speech: bool,
+    speech_frames: usize,
+    silence_frames: usize,
+}
+
+fn ms_to_frames(ms: u32, frame_ms: u32) -> usize {
+    ms.div_ceil(frame_ms) as usize
+}
+
+fn samples_to_ms(samples: u64) -> i64 {
+    (samples / 16) as i64
+}
+
+impl SpeechChunker {
+    pub fn new(config: &TranscriptionConfig) -> Self {
+        let frame_ms = config.vad.frame_ms;
+        let frame_samples = config.vad.frame_samples();
+        let max_chunk_samples = ((config.max_chunk_secs * 16000.0) as usize).max(frame_samples * 4);
+        Self {
+            vad: VoiceActivityDetector::new(config.vad.clone()),
+            frame_samples,
+            min_chunk_samples: (config.min_chunk_secs * 16000.0) as usize,
+            max_chunk_samples,
+            // At most half a chunk, so every split still moves the timeline forward
+            overlap_frames: ms_to_frames((config.overlap_secs * 1000.0) as u32, frame_ms)
+                .min(max_chunk_samples / frame_samples / 2),
+            min_speech_frames: ms_to_frames(config.min_speech_ms, frame_ms),
+            end_pause_frames: ms_to_frames(config.end_pause_ms, frame_ms).max(1),
+            long_pause_frames: ms_to_frames(config.end_pause_ms * 3, frame_ms).max(1),
+            pre_roll_frames: ms_to_frames(config.pre_roll_ms, frame_ms),
+            tail_frames: ms_to_frames(config.pre_roll_ms, frame_ms),
+            pending: Vec::with_capacity(frame_samples),
+            buffer: Vec::new(),
+            frame_energies: Vec::new(),
+            buffer_start: 0,
+            in_speech: false,
+            speech_frames: 0,
+            silence_frames: 0,
+        }
+    }
+
+    /// Timeline position after everything pushed so far (milliseconds)
+    pub fn position_ms(&self) -> i64 {
+        samples_to_ms(self.buffer_start + (self.buffer.len() + self.pending.len()) as u64)
+    }
+
+    /// Feed samples; returns any chunks completed by them.
+    pub fn push(&mut self, samples: &[f32]) -> Vec<SpeechChunk> {
+        let mut chunks = Vec::new();
+        let mut input = samples;
+        while !input.is_empty() {
+            let take = (self.frame_samples - self.pending.len()).min(input.len());
+            self.pending.extend_from_slice(&input[..take]);
+            input = &input[take..];
+            if self.pending.len() == self.frame_samples {
+                let frame = std::mem::take(&mut self.pending);
+                if let Some(chunk) = self.process_frame(&frame) {
+                    chunks.push(chunk);
+                }
+                self.pending = frame;
+                self.pending.clear();
+            }
+        }
+        chunks
+    }
+
+    /// Emit whatever speech is buffered (end of recording / pause). The timeline is kept.
+    pub fn flush(&mut self) -> Option<SpeechChunk> {
+        let buffered = self.buffer.len() + self.pending.len();
+        let chunk = if self.in_speech && self.speech_frames >= self.min_speech_frames {
+            let mut samples = std::mem::take(&mut self.buffer);
+            samples.extend_from_slice(&self.pending);
+            Some(self.make_chunk(samples))
+        } else {
+            None
+        };
+        self.buffer_start += buffered as u64;
+        self.buffer.clear();
+        self.pending.clear();
+        self.frame_energies.clear();
+        self.in_speech = false;
+        self.speech_frames = 0;
+        self.silence_frames = 0;
+        self.vad.reset();
+        chunk
+    }
+
+    /// Speech in progress, for interim results. Its `start_ms` is the start of the chunk it
+    /// will end up in. None while idle or before there is enough speech to transcribe.
+    pub fn partial(&self) -> Option<SpeechChunk> {
+        if !self.in_speech || self.speech_frames < self.min_speech_frames {
+            return None;
+        }
+        Some(self.make_chunk(self.buffer.clone()))
+    }
+
+    fn make_chunk(&self, samples: Vec<f32>) -> SpeechChunk {
+        SpeechChunk {
+            start_ms: samples_to_ms(self.buffer_start),
+            duration_ms: samples_to_ms(samples.len() as u64),
+            samples,
+        }
+    }
+
+    /// Remove the first `frames` frames of the buffer, returning their samples.
+    fn take_frames(&mut self, frames: usize) -> Vec<f32> {
+        let n = frames * self.frame_samples;
+        self.frame_energies.drain(..frames);
+        self.buffer_start += n as u64;
+        self.buffer.drain(..n).collect()
+    }
+
+    fn trim_to_pre_roll(&mut self) {
+        let frames = self.frame_energies.len();
+        if frames > self.pre_roll_frames {
+            self.take_frames(frames - self.pre_roll_frames);
+        }
+    }
+
+    fn process_frame(&mut self, frame: &[f32]) -> Option<SpeechChunk> {
+        let is_speech = self.vad.process_frame(frame);
+        self.buffer.extend_from_slice(frame);
+        self.frame_energies.push(frame_energy_db(frame));
+
+        if !self.in_speech {
+            if is_speech {
+                self.in_speech = true;
+                self.speech_frames = 1;
+                self.silence_frames = 0;
+            } else {
+                self.trim_to_pre_roll();
+            }
+            return None;
+        }
+
+        if is_speech {
+            self.speech_frames += 1;
+            self.silence_frames = 0;
+        } else {
+            self.silence_frames += 1;
+        }
+
+        // End on a pause once the chunk is long enough (or the pause is long)
+        if self.silence_frames >= self.end_pause_frames
+            && (self.buffer.len() >= self.min_chunk_samples
+                || self.silence_frames >= self.long_pause_frames)
+        {
+            let total = self.frame_energies.len();
+            let keep_tail = self.tail_frames.min(self.silence_frames);
+            let end = total - self.silence_frames + keep_tail;
+            let enough_speech = self.speech_frames >= self.min_speech_frames;
+            let samples = self.take_frames(end);
+            let chunk_start = self.buffer_start - samples.len() as u64;
+
+            self.in_speech = false;
+            self.speech_frames = 0;
+            self.silence_frames = 0;
+            self.trim_to_pre_roll();
+
+            if !enough_speech {
+                // A click or cough: too short to be worth transcribing
+                return None;
+            }
+            return Some(SpeechChunk {
+                start_ms: samples_to_ms(chunk_start),
+                duration_ms: samples_to_ms(samples.len() as u64),
+                samples,
+            });
+        }
+
+        // Hit the maximum length mid-speech: cut at the quietest frame in the last
+        // quarter so the split lands between words rather than inside one
+        if self.buffer.len() >= self.max_chunk_samples {
+            let total = self.frame_energies.len();
+            let search_from = total - (total / 4).max(1);
+            let cut = (search_from..total)
+                .min_by(|&a, &b| self.frame_energies[a].total_cmp(&self.frame_energies[b]))
+                .unwrap_or(total - 1)
+                + 1;
+            let chunk_start = self.buffer_start;
+            let samples = self.buffer[..cut * self.frame_samples].to_vec();
+            // The next chunk starts a little before the split, repeating its last words
+            self.take_frames(cut - self.overlap_frames.min(cut - 1));
+            // The remainder continues the same utterance
+            self.speech_frames = self.frame_energies.len();
+            return Some(SpeechChunk {
+                start_ms: samples_to_ms(chunk_start),
+                duration_ms: samples_to_ms(samples.len() as u64),
+                samples,
+            });
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tone(ms: usize, amp: f32) -> Vec<f32> {
+        (0..ms * 16)
+            .map(|i| amp * (2.0 * std::f32::consts::PI * 440.0 * i as f32 / 16000.0).sin())
+            .collect()
+    }
+
+    fn noise(ms: usize, amp: f32) -> Vec<f32> {
+        // Deterministic low-level hiss
+        let mut x: u32 = 12345;
+        (0..ms * 16)
+            .map(|_| {
+                x = x.wrapping_mul(1664525).wrapping_add(1013904223);
+                amp * ((x >> 8) as f32 / (1u32 << 24) as f32 - 0.5)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_chunks_end_on_pauses_with_accurate_start() {
+        let config = TranscriptionConfig::default();
+        let mut chunker = SpeechChunker::new(&config);
+
+        let mut chunks = Vec::new();
+        chunks.extend(chunker.push(&noise(3000, 0.001)));
+        chunks.extend(chunker.push(&tone(2500, 0.3)));
+        chunks.extend(chunker.push(&noise(2000, 0.001)));
+        chunks.extend(chunker.push(&tone(3000, 0.05)));
+        chunks.extend(chunker.push(&noise(2000, 0.001)));
+        chunks.extend(chunker.flush());
+
+        assert_eq!(chunks.len(), 2);
+        // Speech starts at 3000 ms and 7500 ms; chunks include a short pre-roll
+        assert!(
+            (2600..=3000).contains(&chunks[0].start_ms),
+            "{}",
+            chunks[0].start_ms
+        );
+        assert!(
+            (7100..=7500).contains(&chunks[1].start_ms),
+            "{}",
+            chunks[1].start_ms
+        );
+        assert!(chunks[0].duration_ms >= 2500 && chunks[0].duration_ms < 3500);
+        assert_eq!(chunker.position_ms(), 12500);
+    }
+
+    #[test]
+    fn test_partial_grows_and_matches_final_chunk() {
+        let config = TranscriptionConfig::default();
+        let mut chunker = SpeechChunker::new(&config);
+
+        assert!(chunker.push(&noise(1000, 0.001)).is_empty());
+        assert!(chunker.partial().is_none());
+        assert!(chunker.push(&tone(1000, 0.3)).is_empty());
+        let first = chunker.partial().unwrap();
+        assert!(chunker.push(&tone(1000, 0.3)).is_empty());
+        let second = chunker.partial().unwrap();
+        assert_eq!(first.start_ms, second.start_ms);
+        assert!(second.duration_ms >= first.duration_ms + 1000);
+
+        let chunks = chunker.push(&noise(2000, 0.001));
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].start_ms, first.start_ms);
+        assert!(chunker.partial().is_none());
+    }
+
+    #[test]
+    fn test_long_speech_is_split_at_max_length() {
+        let config = TranscriptionConfig::default();
+        let max_ms = (config.max_chunk_secs * 1000.0) as i64;
+        let mut chunker = SpeechChunker::new(&config);
+
+        let mut chunks = chunker.push(&tone(max_ms as usize * 2 + 1000, 0.3));
+        chunks.extend(chunker.flush());
+
+        assert!(chunks.len() >= 3);
+        assert_eq!(chunks[0].start_ms, 0);
+        let overlap_ms = (config.overlap_secs * 1000.0) as i64;
+        for pair in chunks.windows(2) {
+            assert!(pair[0].duration_ms <= max_ms);
+            // Each chunk repeats the last `overlap_secs` of the one before (whole frames)
+            let shared = pair[0].start_ms + pair[0].duration_ms - pair[1].start_ms;
+            assert!(
+                (overlap_ms..overlap_ms + 30).contains(&shared),
+                "{}",
+                shared
+            );
+        }
+    }
+}
@@ -0,0 +1,253 @@
+// Final pass
+// Live captions need a model fast enough to keep up, which is rarely the most accurate one.
+// With a final model configured, the session audio is kept while recording and transcribed
+// again with it once the recording stops; the result replaces the meeting's segments.
+//
+// The recordings hold the raw input, without the echo cancellation and processing the live
+// pass applied to the mic, so local-input segments that copy system audio are dropped again.
+//
+// Speakers, enhanced text and detected questions of the live segments carry over to the final
+// segments they overlap most. Segments the user edits or deletes while the pass runs are left
+// as the user made them.
+
+use crate::audio::aec;
+use crate::storage::SegmentRow;
+use std::collections::{HashMap, HashSet};
+
+/// Source label of the remote side's audio
+const SYSTEM_SOURCE: &str = "system";
+
+/// Overlap of two time ranges (ms)
+fn overlap_ms(a: (i64, i64), b: (i64, i64)) -> i64 {
+    (a.1.min(b.1) - a.0.max(b.0)).max(0)
+}
+
+fn span(segment: &SegmentRow) -> (i64, i64) {
+    (
+        segment.timestamp_ms,
+        segment.end_ms.unwrap_or(segment.timestamp_ms),
+    )
+}
+
+/// The segment among `candidates` from the same source as `segment` that overlaps it most
+fn most_overlapping<'a>(
+    candidates: impl IntoIterator<Item = &'a SegmentRow>,
+    segment: &SegmentRow,
+) -> Option<&'a SegmentRow> {
+    candidates
+        .into_iter()
+        .filter(|c| c.source == segment.source)
+        .map(|c| (overlap_ms(span(c), span(segment)), c))
+        .filter(|(overlap, _)| *overlap > 0)
+        .max_by_key(|(overlap, _)| *overlap)
+        .map(|(_, c)| c)
+}
+
+/// Give each final segment the speaker of the live segment from the same source that overlaps
+/// it most
+pub fn carry_speakers<'a>(
+    live: &[SegmentRow],
+    segments: impl IntoIterator<Item = &'a mut SegmentRow>,
+) {
+    for segment in segments {
+        segment.speaker_id =
+            most_overlapping(live.iter().filter(|l| l.speaker_id.is_some()), segment)
+                .and_then(|l| l.speaker_id.clone());
+    }
+}
+
+/// Carry the LLM's work on the live segments over: each final segment gets the enhanced text
+/// of the live segment (same source) overlapping it most, and each detected question marks the
+/// one final segment that overlaps it most
+pub fn carry_ai_fields<'a>(
+    live: &[SegmentRow],
+    segments: impl IntoIterator<Item = &'a mut SegmentRow>,
+) {
+    let mut segments: Vec<&mut SegmentRow> = segments.into_iter().collect();
+    for segment in segments.iter_mut() {
+        segment.enhanced_text =
+            most_overlapping(live.iter().filter(|l| l.enhanced_text.is_some()), segment)
+                .and_then(|l| l.enhanced_text.clone());
+        segment.is_question = false;
+        segment.question_answer = None;
+    }
+    for question in live.iter().filter(|l| l.is_question) {
+        let best = segments
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| s.source == question.source)
+            .map(|(i, s)| (overlap_ms(span(s), span(question)), i))
+            .filter(|(overlap, _)| *overlap > 0)
+            .max_by_key(|(overlap, _)| *overlap);
+        if let Some((_, i)) = best {
+            segments[i].is_question = true;
+            segments[i].question_answer = question.question_answer.clone();
+        }
+    }
+}
+
+/// Ids of the local-input segments whose text copies a system audio segment at about the same
+/// time: remote speech the mic picked up from the speakers. The system copy is the one kept.
+pub fn echo_copies<'a>(segments: impl IntoIterator<Item = &'a SegmentRow>) -> HashSet<String> {
+    let (system, local): (Vec<&SegmentRow>, Vec<&SegmentRow>) = segments
+        .into_iter()
+        .partition(|s| s.source.as_deref() == Some(SYSTEM_SOURCE));
+    local
+        .into_iter()
+        .filter(|l| {
+            system
+                .iter()
+                .any(|s| aec::is_echo_pair(span(l), &l.text, span(s), &s.text))
+        })
+        .map(|l| l.id.clone())
+        .collect()
+}
+
+/// What the user changed in the live transcript while the final pass ran
+pub struct UserChanges {
+    /// Live segments when the pass started
+    before: Vec<SegmentRow>,
+    /// Ids of those the user edited or deleted since
+    changed: HashSet<String>,
+    /// The edited segments as they are now; they stay in place of the final pass's
+    pub edited: Vec<SegmentRow>,
+}
+
+impl UserChanges {
+    /// Compare the live segments from the start of the pass with those at its end
+    pub fn new(before: Vec<SegmentRow>, after: &[SegmentRow]) -> Self {
+        let after: HashMap<&str, &SegmentRow> = after.iter().map(|s| (s.id.as_str(), s)).collect();
+        let mut changed = HashSet::new();
+        let mut edited = Vec::new();
+        for segment in &before {
+            match after.get(segment.id.as_str()) {
+                None => {
+                    changed.insert(segment.id.clone());
+                }
+                Some(now) if now.text != segment.text => {
+                    changed.insert(segment.id.clone());
+                    edited.push((*now).clone());
+                }
+                Some(_) => {}
+            }
+        }
+        Self {
+            before,
+            changed,
+            edited,
+        }
+    }
+
+    /// Whether a final segment covers speech the user changed: the live segment it overlaps
+    /// most was edited or deleted
+    pub fn covers(&self, segment: &SegmentRow) -> bool {
+        most_overlapping(&self.before, segment).is_some_and(|l| self.changed.contains(&l.id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(source: &str, start: i64, end: i64, speaker: Option<&str>) -> SegmentRow {
+        SegmentRow {
+            timestamp_ms: start,
+            end_ms: Some(end),
+            source: Some(source.to_string()),
+            speaker_id: speaker.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    fn text_row(id: &str, source: &str, start: i64, end: i64, text: &str) -> SegmentRow {
+        SegmentRow {
+            id: id.to_string(),
+            text: text.to_string(),
+            ..row(source, start, end, None)
+        }
+    }
+
+    #[test]
+    fn test_carry_speakers() {
+        let live = [
+            row("system", 0, 4000, Some("ana")),
+            row("system", 4000, 9000, Some("ben")),
+            row("mic", 0, 9000, Some("me")),
+            row("system", 9000, 12000, None),
+        ];
+        let mut segments = [
+            // Mostly Ben's
+            row("system", 3000, 8000, None),
+            row("mic", 1000, 2000, None),
+            // Only a live segment without a speaker
+            row("system", 9500, 11000, Some("stale")),
+        ];
+        carry_speakers(&live, segments.iter_mut());
+        assert_eq!(segments[0].speaker_id.as_deref(), Some("ben"));
+        assert_eq!(segments[1].speaker_id.as_deref(), Some("me"));
+        assert_eq!(segments[2].speaker_id, None);
+    }
+
+    #[test]
+    fn test_carry_ai_fields() {
+        let live = [
+            SegmentRow {
+                enhanced_text: Some("Cleaned up.".to_string()),
+                ..row("system", 0, 4000, None)
+            },
+            SegmentRow {
+                is_question: true,
+                question_answer: Some("Friday".to_string()),
+                ..row("system", 4000, 8000, None)
+            },
+        ];
+        let mut segments = [
+            row("system", 0, 3000, None),
+            row("system", 3000, 5000, None),
+            // Most of the question
+            row("system", 5000, 8000, None),
+            row("mic", 4000, 8000, None),
+        ];
+        carry_ai_fields(&live, segments.iter_mut());
+        assert_eq!(segments[0].enhanced_text.as_deref(), Some("Cleaned up."));
+        assert!(!segments[1].is_question);
+        assert!(segments[2].is_question);
+        assert_eq!(segments[2].question_answer.as_deref(), Some("Friday"));
+        assert!(!segments[3].is_question);
+        assert_eq!(segments[3].enhanced_text, None);
+    }
+
+    #[test]
+    fn test_echo_copies() {
+        let text = "we should ship the release on friday";
+        let segments = [
+            text_row("s1", "system", 1000, 4000, text),
+            text_row("m1", "mic", 1300, 4300, text),
+            // Same words much later: said again, not an echo
+            text_row("m2", "mic", 60_000, 63_000, text),
+            text_row("m3", "mic", 5000, 7000, "sounds good to me"),
+        ];
+        let echoes = echo_copies(&segments);
+        assert_eq!(echoes, HashSet::from(["m1".to_string()]));
+    }
+
+    #[test]
+    fn test_user_changes() {
+        let before = vec![
+            text_row("a", "mic", 0, 4000, "hello there"),
+            text_row("b", "mic", 4000, 8000, "meet on fryday"),
+            text_row("c", "mic", 8000, 12000, "um"),
+        ];
+        let after = [
+            before[0].clone(),
+            text_row("b", "mic", 4000, 8000, "meet on Friday"),
+        ];
+        let changes = UserChanges::new(before, &after);
+        assert_eq!(changes.edited.len(), 1);
+        assert_eq!(changes.edited[0].text, "meet on Friday");
+        assert!(!changes.covers(&row("mic", 500, 3500, None)));
+        assert!(changes.covers(&row("mic", 4200, 7900, None)));
+        assert!(changes.covers(&row("mic", 8500, 11000, None)));
+        assert!(!changes.covers(&row("system", 4200, 7900, None)));
+    }
+}
@@ -0,0 +1,68 @@
+// Chunk languages
+// A single language setting transcribes half of a bilingual meeting in the wrong language.
+// Each source can have a language of its own (the local user speaks English, the remote side
+// Spanish), and auto-detection can be restricted to the languages a team actually speaks, so
+// a short or accented chunk isn't detected as an unrelated language.
+
+use std::collections::HashMap;
+
+use crate::asr::LanguageOptions;
+
+/// Normalize a user-entered language code ("EN " -> "en"); None if empty
+fn language_code(code: &str) -> Option<String> {
+    let code = code.trim().to_lowercase();
+    (!code.is_empty()).then_some(code)
+}
+
+/// Language options for a chunk: the language of its source when one is set, else `language`,
+/// with auto-detection restricted to `auto_languages`. Imported files have no source.
+pub fn chunk_language(
+    language: &str,
+    source_languages: &HashMap<String, String>,
+    auto_languages: &[String],
+    source: Option<&str>,
+) -> LanguageOptions {
+    let language = source
+        .and_then(|source| source_languages.get(source))
+        .and_then(|code| language_code(code))
+        .or_else(|| language_code(language))
+        .unwrap_or_else(|| "auto".to_string());
+    let mut candidates: Vec<String> = Vec::new();
+    for code in auto_languages.iter().filter_map(|code| language_code(code)) {
+        if code != "auto" && !candidates.contains(&code) {
+            candidates.push(code);
+        }
+    }
+    LanguageOptions {
+        language: Some(language),
+        candidates,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunk_language() {
+        let sources = HashMap::from([
+            ("mic".to_string(), "en".to_string()),
+            ("system".to_string(), " ES".to_string()),
+            ("Room".to_string(), String::new()),
+        ]);
+        let auto = ["en".to_string(), "ES".to_string(), "es".to_string()];
+
+        let mic = chunk_language("auto", &sources, &auto, Some("mic"));
+        assert_eq!(mic.language.as_deref(), Some("en"));
+        let system = chunk_language("auto", &sources, &auto, Some("system"));
+        assert_eq!(system.language.as_deref(), Some("es"));
+
+        // No language of its own: the global one, detection restricted and deduplicated
+        let room = chunk_language("auto", &sources, &auto, Some("Room"));
+        assert_eq!(room.language.as_deref(), Some("auto"));
+        assert_eq!(room.candidates, vec!["en".to_string(), "es".to_string()]);
+        let import = chunk_language("de", &sources, &[], None);
+        assert_eq!(import.language.as_deref(), Some("de"));
+        assert!(import.candidates.is_empty());
+    }
+}
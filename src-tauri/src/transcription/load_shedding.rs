@@ -0,0 +1,591 @@
+// Load shedding
+// A model too large for the machine falls further behind with every chunk, and a transcript
+// minutes behind the meeting is no use live. The shedder watches how many chunks are queued
+// and how long each takes from capture to text. While transcription keeps falling behind it
+// engages the configured measures one at a time, in the order they are listed; once the queue
+// has stayed empty for a while it releases them again, last engaged first.
+//
+// Spilled chunks are written to disk as raw 16 kHz f32 samples and transcribed in a catch-up
+// pass, when spilling is released or the recording stops. Merged chunks take the chunks queued
+// behind them from the same source, up to one Whisper window.
+
+use crate::transcription::workers::WorkerPool;
+use crate::transcription::AudioChunk;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+/// Longest merged chunk: one Whisper window
+pub const MAX_MERGED_MS: i64 = 30_000;
+
+const SAMPLE_RATE: i64 = 16_000;
+
+/// A measure taken while transcription is behind
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SheddingAction {
+    /// Skip post-processing of new segments: LLM enhancement, question detection, titles
+    /// and translation
+    SkipAi,
+    /// Transcribe queued chunks of a source together, up to one Whisper window
+    MergeChunks,
+    /// Transcribe with `LoadSheddingOptions::fallback_model`
+    SmallerModel,
+    /// Write chunks to disk and transcribe them once the queue has caught up
+    SpillToDisk,
+}
+
+impl SheddingAction {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "skip_ai" => Some(SheddingAction::SkipAi),
+            "merge_chunks" => Some(SheddingAction::MergeChunks),
+            "smaller_model" => Some(SheddingAction::SmallerModel),
+            "spill_to_disk" => Some(SheddingAction::SpillToDisk),
+            _ => None,
+        }
+    }
+}
+
+/// Load shedding configuration
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct LoadSheddingOptions {
+    /// Measures to take, in the order they are engaged: "skip_ai", "merge_chunks",
+    /// "smaller_model", "spill_to_disk" (empty = never shed load)
+    pub actions: Vec<String>,
+    /// Behind when more chunks than this are queued...
+    pub max_queue: usize,
+    /// ...or a chunk took longer than this from capture to text
+    pub max_latency_ms: u64,
+    /// Chunks in a row that must be behind (or caught up) before a measure is engaged
+    /// (or released)
+    pub patience: u32,
+    /// Whisper model for "smaller_model"; it must be downloaded
+    pub fallback_model: String,
+}
+
+impl Default for LoadSheddingOptions {
+    fn default() -> Self {
+        Self {
+            actions: Vec::new(),
+            max_queue: 4,
+            max_latency_ms: 15_000,
+            patience: 3,
+            fallback_model: "base".to_string(),
+        }
+    }
+}
+
+/// A measure engaged or released
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SheddingDecision {
+    pub action: SheddingAction,
+    pub engaged: bool,
+    pub reason: String,
+}
+
+/// Decides when to engage and release the configured measures
+#[derive(Debug)]
+pub struct LoadShedder {
+    options: LoadSheddingOptions,
+    actions: Vec<SheddingAction>,
+    /// The first `engaged` actions are in effect
+    engaged: usize,
+    behind: u32,
+    caught_up: u32,
+}
+
+impl LoadShedder {
+    pub fn new(options: LoadSheddingOptions) -> Self {
+        let mut actions = Vec::new();
+        for name in &options.actions {
+            match SheddingAction::parse(name) {
+                Some(action) if !actions.contains(&action) => actions.push(action),
+                Some(_) => {}
+                None => log::warn!("Unknown load shedding action: {}", name),
+            }
+        }
+        Self {
+            options,
+            actions,
+            engaged: 0,
+            behind: 0,
+            caught_up: 0,
+        }
+    }
+
+    pub fn is_engaged(&self, action: SheddingAction) -> bool {
+        self.actions[..self.engaged].contains(&action)
+    }
+
+    /// Record a chunk taken with `queued` chunks still waiting, `latency_ms` after it was
+    /// captured; returns the measure engaged or released, if any
+    pub fn observe(&mut self, queued: usize, latency_ms: u64) -> Option<SheddingDecision> {
+        let behind = queued > self.options.max_queue || latency_ms > self.options.max_latency_ms;
+        // Half the latency limit, so a measure isn't released right at the edge
+        let caught_up = queued == 0 && latency_ms <= self.options.max_latency_ms / 2;
+        if behind {
+            self.behind += 1;
+            self.caught_up = 0;
+        } else if caught_up {
+            self.caught_up += 1;
+            self.behind = 0;
+        } else {
+            self.behind = 0;
+            self.caught_up = 0;
+        }
+
+        let patience = self.options.patience.max(1);
+        if self.behind >= patience && self.engaged < self.actions.len() {
+            self.behind = 0;
+            self.engaged += 1;
+            Some(SheddingDecision {
+                action: self.actions[self.engaged - 1],
+                engaged: true,
+                reason: format!(
+                    "{} chunks queued, last one {:.1}s behind",
+                    queued,
+                    latency_ms as f32 / 1000.0
+                ),
+            })
+        } else if self.caught_up >= patience && self.engaged > 0 {
+            self.caught_up = 0;
+            self.engaged -= 1;
+            Some(SheddingDecision {
+                action: self.actions[self.engaged],
+                engaged: false,
+                reason: "Queue caught up".to_string(),
+            })
+        } else {
+            None
+        }
+    }
+}
+
+/// Join chunk audio (start time and samples, in time order) into one buffer, with silence
+/// for the gaps between chunks. Overlapping audio is taken from the earlier chunk.
+pub fn merge_audio<'a>(parts: impl IntoIterator<Item = (i64, &'a [f32])>) -> Vec<f32> {
+    let mut merged: Vec<f32> = Vec::new();
+    let mut first_start = None;
+    for (start_ms, samples) in parts {
+        let first = *first_start.get_or_insert(start_ms);
+        let offset = ((start_ms - first).max(0) * SAMPLE_RATE / 1000) as usize;
+        if offset > merged.len() {
+            merged.resize(offset, 0.0);
+        }
+        let skip = merged.len() - offset;
+        merged.extend(samples.iter().skip(skip));
+    }
+    merged
+}
+
+/// Write spilled chunk audio
+pub fn write_spill(path: &Path, samples: &[f32]) -> std::io::Result<()> {
+    let mut bytes = Vec::with_capacity(samples.len() * 4);
+    for sample in samples {
+        bytes.extend_from_slice(&sample.to_le_bytes());
+    }
+    std::fs::File::create(path)?.write_all(&bytes)
+}
+
+/// Read spilled chunk audio back
+pub fn read_spill(path: &Path) -> std::io::Result<Vec<f32>> {
+    let mut bytes = Vec::new();
+    std::fs::File::open(path)?.read_to_end(&mut bytes)?;
+    Ok(bytes
+        .chunks_exact(4)
+        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect())
+}
+
+/// A chunk whose audio was spilled to disk, waiting for the catch-up pass
+pub struct SpilledChunk {
+    /// Order the chunk was spilled in
+    number: u64,
+    path: PathBuf,
+    /// The chunk without its samples
+    chunk: AudioChunk,
+}
+
+/// Chunks spilled to disk. Workers write their files without the queue locked, so they can
+/// finish out of order; the chunks are still read back in the order they were spilled.
+pub struct SpillQueue {
+    dir: PathBuf,
+    count: u64,
+    spilled: VecDeque<SpilledChunk>,
+}
+
+impl SpillQueue {
+    pub fn new(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            count: 0,
+            spilled: VecDeque::new(),
+        }
+    }
+
+    /// Number and file of the next chunk to spill
+    pub fn next_file(&mut self) -> (u64, PathBuf) {
+        self.count += 1;
+        (self.count, self.dir.join(format!("{}.pcm", self.count)))
+    }
+
+    pub fn push(&mut self, spilled: SpilledChunk) {
+        let index = self
+            .spilled
+            .partition_point(|earlier| earlier.number < spilled.number);
+        self.spilled.insert(index, spilled);
+    }
+
+    /// The oldest spilled chunk
+    pub fn pop(&mut self) -> Option<SpilledChunk> {
+        self.spilled.pop_front()
+    }
+}
+
+/// Write a chunk's audio to `path`; gives the chunk back if that fails. Runs on a blocking
+/// thread, without the queue locked.
+pub async fn spill_chunk(
+    (number, path): (u64, PathBuf),
+    mut chunk: AudioChunk,
+) -> Result<SpilledChunk, AudioChunk> {
+    let samples = std::mem::take(&mut chunk.samples);
+    let written = tokio::task::spawn_blocking(move || {
+        let result = path
+            .parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|_| write_spill(&path, &samples));
+        (path, samples, result)
+    })
+    .await;
+    match written {
+        Ok((path, _, Ok(()))) => Ok(SpilledChunk {
+            number,
+            path,
+            chunk,
+        }),
+        Ok((_, samples, Err(e))) => {
+            log::error!("Failed to spill chunk {}: {}", chunk.chunk_index, e);
+            chunk.samples = samples;
+            Err(chunk)
+        }
+        Err(e) => {
+            log::error!("Spilling chunk {} panicked: {}", chunk.chunk_index, e);
+            Err(chunk)
+        }
+    }
+}
+
+/// Read a spilled chunk's audio back, on a blocking thread
+pub async fn unspill_chunk(
+    SpilledChunk {
+        path, mut chunk, ..
+    }: SpilledChunk,
+) -> Option<AudioChunk> {
+    let read = tokio::task::spawn_blocking(move || {
+        let samples = read_spill(&path);
+        let _ = std::fs::remove_file(&path);
+        samples
+    })
+    .await;
+    match read {
+        Ok(Ok(samples)) => {
+            chunk.samples = samples;
+            chunk.queued_at = std::time::Instant::now();
+            Some(chunk)
+        }
+        Ok(Err(e)) => {
+            log::error!("Failed to read spilled chunk {}: {}", chunk.chunk_index, e);
+            None
+        }
+        Err(e) => {
+            log::error!(
+                "Reading spilled chunk {} panicked: {}",
+                chunk.chunk_index,
+                e
+            );
+            None
+        }
+    }
+}
+
+/// Take the next chunk for a worker, numbered for committing in order, and whether it is a
+/// spilled one. Spilled chunks are taken when the queue is empty and spilling has been
+/// released, or once recording has stopped. Partial chunks are stale while load is shed, and
+/// are dropped. Spill files are written and read without the queue locked.
+pub async fn next_chunk(pool: &WorkerPool) -> Option<(u64, AudioChunk, bool)> {
+    use tokio::sync::mpsc::error::TryRecvError;
+
+    loop {
+        let mut queue = pool.queue.lock().await;
+        let (spill, merge) = {
+            let shedder = pool.shedder.lock().unwrap();
+            (
+                shedder.is_engaged(SheddingAction::SpillToDisk),
+                shedder.is_engaged(SheddingAction::MergeChunks),
+            )
+        };
+        let chunk = match queue.held.pop_front() {
+            Some(chunk) => chunk,
+            None => {
+                let chunk = match queue.rx.try_recv() {
+                    Ok(chunk) => chunk,
+                    Err(TryRecvError::Empty) => {
+                        if !spill {
+                            if let Some(spilled) = queue.spilled.pop() {
+                                drop(queue);
+                                if let Some(chunk) = unspill_chunk(spilled).await {
+                                    return Some((pool.queue.lock().await.number(), chunk, true));
+                                }
+                                continue;
+                            }
+                        }
+                        // Wait for audio, looking at the spilled chunks again now and then
+                        let wait = std::time::Duration::from_secs(1);
+                        match tokio::time::timeout(wait, queue.rx.recv()).await {
+                            Ok(Some(chunk)) => chunk,
+                            _ => continue,
+                        }
+                    }
+                    Err(TryRecvError::Disconnected) => {
+                        let spilled = queue.spilled.pop()?;
+                        drop(queue);
+                        if let Some(chunk) = unspill_chunk(spilled).await {
+                            return Some((pool.queue.lock().await.number(), chunk, true));
+                        }
+                        continue;
+                    }
+                };
+                pool.take_pending();
+                chunk
+            }
+        };
+
+        if chunk.partial && (spill || merge) {
+            continue;
+        }
+        if spill {
+            let file = queue.spilled.next_file();
+            drop(queue);
+            match spill_chunk(file, chunk).await {
+                Ok(spilled) => {
+                    pool.queue.lock().await.spilled.push(spilled);
+                    pool.observe_load(0);
+                    continue;
+                }
+                Err(chunk) => return Some((pool.queue.lock().await.number(), chunk, false)),
+            }
+        }
+        let chunk = if merge {
+            let queue = &mut *queue;
+            merge_queued(chunk, &mut queue.held, || {
+                let next = queue.rx.try_recv().ok()?;
+                pool.take_pending();
+                Some(next)
+            })
+        } else {
+            chunk
+        };
+        return Some((queue.number(), chunk, false));
+    }
+}
+
+/// Merge the chunks queued after `chunk` (taken from `next`) into the latest chunk of their
+/// source, held or `chunk` itself, each up to one Whisper window. Chunks of other sources are
+/// held rather than ending the merge, which stops at the first chunk of `chunk`'s source that
+/// won't fit. Partial chunks are dropped.
+pub fn merge_queued(
+    mut chunk: AudioChunk,
+    held: &mut VecDeque<AudioChunk>,
+    mut next: impl FnMut() -> Option<AudioChunk>,
+) -> AudioChunk {
+    while let Some(next) = next() {
+        if next.partial {
+            continue;
+        }
+        let latest = held.iter().rposition(|held| held.source == next.source);
+        let left = match latest {
+            Some(index) => append_chunk(&mut held[index], next),
+            None if next.source == chunk.source => {
+                if let Err(next) = append_chunk(&mut chunk, next) {
+                    held.push_back(next);
+                    break;
+                }
+                Ok(())
+            }
+            None => Err(next),
+        };
+        if let Err(next) = left {
+            held.push_back(next);
+        }
+    }
+    chunk
+}
+
+/// Append `next` to `chunk` when it is of the same source and fits in one Whisper window with
+/// it; gives it back otherwise
+pub fn append_chunk(chunk: &mut AudioChunk, next: AudioChunk) -> Result<(), AudioChunk> {
+    let end_ms = next.start_ms + next.duration_ms;
+    if next.source != chunk.source
+        || next.start_ms < chunk.start_ms
+        || end_ms - chunk.start_ms > MAX_MERGED_MS
+    {
+        return Err(next);
+    }
+    chunk.samples = merge_audio([
+        (chunk.start_ms, chunk.samples.as_slice()),
+        (next.start_ms, next.samples.as_slice()),
+    ]);
+    chunk.duration_ms = chunk.duration_ms.max(end_ms - chunk.start_ms);
+    chunk.ends_at = chunk.ends_at.max(next.ends_at);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transcription::AudioSource;
+
+    fn chunk(source: AudioSource, start_ms: i64, duration_ms: i64) -> AudioChunk {
+        let now = std::time::Instant::now();
+        AudioChunk {
+            samples: vec![0.5; (duration_ms * SAMPLE_RATE / 1000) as usize],
+            start_ms,
+            duration_ms,
+            chunk_index: 0,
+            source,
+            partial: false,
+            queued_at: now,
+            ends_at: now,
+        }
+    }
+
+    fn shedder(actions: &[&str]) -> LoadShedder {
+        LoadShedder::new(LoadSheddingOptions {
+            actions: actions.iter().map(|a| a.to_string()).collect(),
+            patience: 2,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_engage_and_release() {
+        let mut shedder = shedder(&["skip_ai", "bogus", "spill_to_disk", "skip_ai"]);
+        assert_eq!(shedder.observe(6, 1000), None);
+        let first = shedder.observe(6, 1000).unwrap();
+        assert_eq!(first.action, SheddingAction::SkipAi);
+        assert!(first.engaged);
+        // Latency alone is enough to be behind
+        assert_eq!(shedder.observe(0, 20_000), None);
+        let second = shedder.observe(0, 20_000).unwrap();
+        assert_eq!(second.action, SheddingAction::SpillToDisk);
+        assert!(shedder.is_engaged(SheddingAction::SkipAi));
+        // Nothing left to engage
+        assert_eq!(shedder.observe(9, 0), None);
+        assert_eq!(shedder.observe(9, 0), None);
+
+        // Short but not empty queue neither engages nor releases
+        assert_eq!(shedder.observe(2, 0), None);
+        assert_eq!(shedder.observe(0, 0), None);
+        let released = shedder.observe(0, 0).unwrap();
+        assert_eq!(released.action, SheddingAction::SpillToDisk);
+        assert!(!released.engaged);
+        assert!(!shedder.is_engaged(SheddingAction::SpillToDisk));
+        assert!(shedder.is_engaged(SheddingAction::SkipAi));
+    }
+
+    #[test]
+    fn test_no_actions() {
+        let mut shedder = shedder(&[]);
+        for _ in 0..5 {
+            assert_eq!(shedder.observe(100, 100_000), None);
+        }
+    }
+
+    #[test]
+    fn test_merge_audio() {
+        let a = vec![1.0; 16];
+        let b = vec![2.0; 16];
+        // 1 ms of silence between a (0-1 ms) and b (2-3 ms)
+        let merged = merge_audio([(0, a.as_slice()), (2, b.as_slice())]);
+        assert_eq!(merged.len(), 48);
+        assert_eq!(merged[20], 0.0);
+        assert_eq!(merged[40], 2.0);
+        // Overlap is not repeated
+        let merged = merge_audio([(0, b.as_slice()), (0, a.as_slice())]);
+        assert_eq!(merged, b);
+    }
+
+    #[test]
+    fn test_spill_roundtrip() {
+        let path =
+            std::env::temp_dir().join(format!("phantomear-spill-{}.pcm", std::process::id()));
+        let samples = vec![0.25, -1.0, 0.5];
+        write_spill(&path, &samples).unwrap();
+        assert_eq!(read_spill(&path).unwrap(), samples);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_merge_queued() {
+        let mic = || AudioSource::Mic;
+        let other = || AudioSource::Input("desk".to_string());
+        let mut queued = vec![
+            chunk(other(), 0, 8_000),
+            chunk(mic(), 10_000, 10_000),
+            chunk(other(), 9_000, 8_000),
+            chunk(other(), 18_000, 8_000),
+            // Would take the mic chunk past one window
+            chunk(mic(), 21_000, 10_000),
+            chunk(mic(), 32_000, 4_000),
+        ]
+        .into_iter();
+        let mut held = VecDeque::new();
+        let merged = merge_queued(chunk(mic(), 0, 9_000), &mut held, || queued.next());
+
+        assert_eq!(merged.source, mic());
+        assert_eq!((merged.start_ms, merged.duration_ms), (0, 20_000));
+        assert_eq!(merged.samples.len(), 20 * SAMPLE_RATE as usize);
+        // The merge stops at the mic chunk that didn't fit
+        assert_eq!(queued.next().map(|c| c.start_ms), Some(32_000));
+
+        let held: Vec<(AudioSource, i64, i64)> = held
+            .into_iter()
+            .map(|c| (c.source, c.start_ms, c.duration_ms))
+            .collect();
+        assert_eq!(
+            held,
+            [(other(), 0, 26_000), (mic(), 21_000, 10_000)],
+            "other sources merge among themselves, each within one window"
+        );
+    }
+
+    #[test]
+    fn test_spilled_chunks_come_back_in_order() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        let dir =
+            std::env::temp_dir().join(format!("phantomear-spill-order-{}", std::process::id()));
+        let mut queue = SpillQueue::new(dir.clone());
+        let files: Vec<(u64, PathBuf)> = (0..3).map(|_| queue.next_file()).collect();
+
+        // Writes finish out of order
+        for (file, start_ms) in files.into_iter().zip([0, 1_000, 2_000]).rev() {
+            let spilled = runtime
+                .block_on(spill_chunk(file, chunk(AudioSource::Mic, start_ms, 500)))
+                .unwrap();
+            queue.push(spilled);
+        }
+        let mut unspilled = Vec::new();
+        while let Some(spilled) = queue.pop() {
+            let chunk = runtime.block_on(unspill_chunk(spilled)).unwrap();
+            assert_eq!(chunk.samples.len(), 8_000);
+            unspilled.push(chunk.start_ms);
+        }
+        assert_eq!(unspilled, [0, 1_000, 2_000]);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -1,8 +1,76 @@
 // Real-time transcription pipeline
 // Processes audio chunks and emits transcription results
 
+pub mod chunker;
+pub mod final_pass;
+pub mod language;
+pub mod load_shedding;
+pub mod prompt;
+pub mod stitch;
+pub mod translation;
+pub mod workers;
+
+pub use chunker::{SpeechChunk, SpeechChunker};
+
+use crate::audio::vad::VadConfig;
 use serde::Serialize;
 
+/// Which physical source the audio came from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AudioSource {
+    /// Microphone — the local user's voice.
+    Mic,
+    /// System audio via ScreenCaptureKit (macOS) or a PulseAudio/PipeWire monitor
+    /// source (Linux) — remote participants / any app audio.
+    #[cfg(any(target_os = "macos", target_os = "linux"))]
+    System,
+    /// One of several local inputs (Settings::audio_inputs), named by its label
+    Input(String),
+}
+
+impl AudioSource {
+    pub fn as_str(&self) -> &str {
+        match self {
+            AudioSource::Mic => "mic",
+            #[cfg(any(target_os = "macos", target_os = "linux"))]
+            AudioSource::System => "system",
+            AudioSource::Input(name) => name,
+        }
+    }
+
+    pub fn is_system(&self) -> bool {
+        match self {
+            #[cfg(any(target_os = "macos", target_os = "linux"))]
+            AudioSource::System => true,
+            _ => false,
+        }
+    }
+}
+
+/// A captured and resampled audio chunk ready for transcription.
+/// Passed from the audio producer to the transcription consumer via mpsc channel.
+#[derive(Debug)]
+pub struct AudioChunk {
+    /// 16kHz mono f32 PCM samples, already resampled.
+    pub samples: Vec<f32>,
+    /// Absolute start position in the recording timeline (milliseconds).
+    pub start_ms: i64,
+    /// Duration of this chunk in milliseconds.
+    pub duration_ms: i64,
+    /// Monotonic index of this chunk (producer-assigned, used for segment IDs).
+    pub chunk_index: u64,
+    /// Which capture source produced this chunk.
+    pub source: AudioSource,
+    /// Speech still in progress: transcribed for display only, replaced by the final chunk
+    /// that starts at the same position.
+    pub partial: bool,
+    /// When the chunk was queued, for measuring how far transcription is behind
+    pub queued_at: std::time::Instant,
+    /// When its last sample was captured. Timelines of different inputs drift apart (device
+    /// clocks, outages padded by estimate), so chunks of different inputs are compared by this.
+    pub ends_at: std::time::Instant,
+}
+
 /// Transcription segment emitted to frontend
 #[derive(Debug, Clone, Serialize)]
 pub struct TranscriptionEvent {
@@ -13,24 +81,122 @@ pub struct TranscriptionEvent {
     pub is_partial: bool,
     /// Audio source: "mic" (local user) or "system" (remote participants)
     pub source: String,
+    /// On a final segment: the partial result it replaces
+    pub partial_id: Option<String>,
+    /// Language the text was transcribed in, when known
+    pub language: Option<String>,
+    /// Detection probability of `language` (None = set for the source, not detected)
+    pub language_probability: Option<f32>,
+}
+
+/// Translation of a segment, emitted to the frontend after the segment itself
+#[derive(Debug, Clone, Serialize)]
+pub struct TranslationEvent {
+    pub segment_id: String,
+    pub text: String,
+    /// Language of the translation
+    pub language: String,
 }
 
 /// Configuration for the transcription pipeline
 pub struct TranscriptionConfig {
-    /// Duration of audio chunks to process (in seconds)
-    pub chunk_duration_secs: f32,
-    /// Overlap between chunks (in seconds) for better continuity
+    /// Chunks shorter than this keep growing across short pauses (seconds)
+    pub min_chunk_secs: f32,
+    /// Speech running longer than this is split at the quietest point (seconds)
+    pub max_chunk_secs: f32,
+    /// Audio before a split repeated at the start of the next chunk, so the words around it
+    /// can be stitched (seconds)
     pub overlap_secs: f32,
-    /// Minimum audio level to trigger transcription
-    pub silence_threshold: f32,
+    /// Silence that ends a chunk (milliseconds)
+    pub end_pause_ms: u32,
+    /// Audio kept before speech onset and after its end (milliseconds)
+    pub pre_roll_ms: u32,
+    /// Chunks with less detected speech than this are dropped (milliseconds)
+    pub min_speech_ms: u32,
+    /// Voice activity detector settings
+    pub vad: VadConfig,
 }
 
 impl Default for TranscriptionConfig {
     fn default() -> Self {
         Self {
-            chunk_duration_secs: 5.0, // Process every 5 seconds for faster feedback
-            overlap_secs: 0.5,
-            silence_threshold: 0.01,
+            min_chunk_secs: 2.0,
+            max_chunk_secs: 10.0, // Keep feedback reasonably fast during monologues
+            overlap_secs: 1.0,
+            end_pause_ms: 500,
+            pre_roll_ms: 200,
+            min_speech_ms: 250,
+            vad: VadConfig::default(),
         }
     }
 }
+
+/// The items around the one whose id is `id`: up to `before` items ahead of it, the item itself
+/// and up to `after` items following it. Items are found by id, not position, since segments
+/// can be removed from the transcript while it grows. Empty once the item itself is gone.
+pub fn window_around<'a, T>(
+    items: &'a [T],
+    item_id: impl Fn(&T) -> &str,
+    id: &str,
+    before: usize,
+    after: usize,
+) -> &'a [T] {
+    match items.iter().position(|item| item_id(item) == id) {
+        Some(idx) => &items[idx.saturating_sub(before)..(idx + after + 1).min(items.len())],
+        None => &[],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(window: &[(String, &str)]) -> Vec<String> {
+        window.iter().map(|(id, _)| id.clone()).collect()
+    }
+
+    #[test]
+    fn test_window_around_removed_segments() {
+        let mut transcript: Vec<(String, &str)> =
+            (1..=7).map(|i| (format!("m-seg-{}", i), "text")).collect();
+        // An echoed segment is dropped while the AI task runs
+        transcript.retain(|(id, _)| id != "m-seg-2");
+
+        // Enhancement batch ending at segment 5
+        let batch = window_around(&transcript, |s| &s.0, "m-seg-5", 4, 0);
+        assert_eq!(ids(batch), ["m-seg-1", "m-seg-3", "m-seg-4", "m-seg-5"]);
+
+        // Question context around segment 6
+        let context = window_around(&transcript, |s| &s.0, "m-seg-6", 5, 5);
+        assert_eq!(
+            ids(context),
+            ["m-seg-1", "m-seg-3", "m-seg-4", "m-seg-5", "m-seg-6", "m-seg-7"]
+        );
+
+        // The segment itself was removed
+        assert!(window_around(&transcript, |s| &s.0, "m-seg-2", 5, 5).is_empty());
+    }
+
+    #[test]
+    fn test_window_around_stitched_tails() {
+        let mut transcript: Vec<(String, &str)> = vec![
+            ("m-seg-1".to_string(), "we could ship"),
+            ("m-seg-2".to_string(), "on"),
+            ("m-seg-3".to_string(), "friday then"),
+            ("m-seg-4".to_string(), "does that work"),
+        ];
+        // The next chunk took over the tail: one is emptied and removed, one gets new text
+        transcript.retain(|(id, _)| id != "m-seg-2");
+        transcript[1].1 = "on friday then";
+
+        let context: Vec<&str> = window_around(&transcript, |s| &s.0, "m-seg-4", 5, 5)
+            .iter()
+            .map(|(_, text)| *text)
+            .collect();
+        assert_eq!(
+            context,
+            ["we could ship", "on friday then", "does that work"]
+        );
+        assert!(window_around(&transcript, |s| &s.0, "m-seg-2", 0, 0).is_empty());
+    }
+}
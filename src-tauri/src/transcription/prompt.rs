@@ -0,0 +1,171 @@
+// Decoder prompts
+// Whisper decodes each chunk on its own, so names, product terms and acronyms come out spelled
+// differently every time. An initial prompt with the team's vocabulary, the meeting title and
+// what was just said on the same source steers it toward consistent spellings.
+//
+// A prompt can also pull the decoder into a loop or make it copy the prompt back instead of
+// transcribing; `is_hallucination` spots both so the chunk can be decoded again without one.
+
+/// Whisper keeps the last 224 prompt tokens; about 4 characters each
+const MAX_PROMPT_CHARS: usize = 800;
+/// Vocabulary is capped so preceding speech always fits too
+const MAX_VOCABULARY_CHARS: usize = 400;
+/// Output copied from the prompt is only flagged from this many words (short phrases repeat)
+const MIN_COPIED_WORDS: usize = 4;
+/// A phrase repeated back to back this many times, covering at least `MIN_LOOP_WORDS`, is a loop
+const MIN_LOOP_REPEATS: usize = 3;
+const MIN_LOOP_WORDS: usize = 6;
+
+/// Split a user-entered vocabulary (comma, semicolon or newline separated) into terms
+pub fn parse_terms(text: &str) -> Vec<String> {
+    text.split([',', ';', '\n'])
+        .map(|term| term.trim())
+        .filter(|term| !term.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Build the prompt for the next chunk of a source: title, vocabulary, then the end of the
+/// preceding text (Whisper weighs the end of the prompt most).
+pub fn build_prompt(vocabulary: &[String], title: Option<&str>, previous: &str) -> String {
+    let mut prompt = String::new();
+    if let Some(title) = title.map(str::trim).filter(|t| !t.is_empty()) {
+        prompt.push_str(title);
+        prompt.push_str(". ");
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    let mut terms = String::new();
+    for term in vocabulary.iter().map(|t| t.trim()) {
+        if term.is_empty() || !seen.insert(term.to_lowercase()) {
+            continue;
+        }
+        if terms.len() + term.len() + 2 > MAX_VOCABULARY_CHARS {
+            break;
+        }
+        if !terms.is_empty() {
+            terms.push_str(", ");
+        }
+        terms.push_str(term);
+    }
+    if !terms.is_empty() {
+        prompt.push_str("Glossary: ");
+        prompt.push_str(&terms);
+        prompt.push_str(". ");
+    }
+
+    let budget = MAX_PROMPT_CHARS.saturating_sub(prompt.len());
+    prompt.push_str(tail(previous.trim(), budget));
+    // The decoder takes a C string
+    prompt.replace('\0', "").trim().to_string()
+}
+
+/// The last `max_chars` bytes of `text`, starting at a word
+fn tail(text: &str, max_chars: usize) -> &str {
+    if text.len() <= max_chars {
+        return text;
+    }
+    let mut start = text.len() - max_chars;
+    while !text.is_char_boundary(start) {
+        start += 1;
+    }
+    let cut = &text[start..];
+    match cut.find(char::is_whitespace) {
+        Some(space) => cut[space..].trim_start(),
+        None => cut,
+    }
+}
+
+fn normalized_words(text: &str) -> Vec<String> {
+    text.split_whitespace()
+        .map(|w| {
+            w.chars()
+                .filter(|c| c.is_alphanumeric())
+                .collect::<String>()
+                .to_lowercase()
+        })
+        .filter(|w| !w.is_empty())
+        .collect()
+}
+
+/// True if the decoder looped or copied its prompt instead of transcribing
+pub fn is_hallucination(text: &str, prompt: &str) -> bool {
+    let words = normalized_words(text);
+    if has_repetition_loop(&words) {
+        return true;
+    }
+    if words.len() < MIN_COPIED_WORDS {
+        return false;
+    }
+    let prompt_words = normalized_words(prompt);
+    prompt_words
+        .windows(words.len())
+        .any(|window| window == words.as_slice())
+}
+
+/// A phrase of 1-4 words repeated back to back
+fn has_repetition_loop(words: &[String]) -> bool {
+    for n in 1..=4 {
+        for start in 0..words.len() {
+            let phrase = &words[start..(start + n).min(words.len())];
+            if phrase.len() < n {
+                break;
+            }
+            let mut repeats = 1;
+            while words
+                .get(start + repeats * n..start + (repeats + 1) * n)
+                .is_some_and(|next| next == phrase)
+            {
+                repeats += 1;
+            }
+            if repeats >= MIN_LOOP_REPEATS && repeats * n >= MIN_LOOP_WORDS {
+                return true;
+            }
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_prompt_orders_and_limits_parts() {
+        let vocabulary = parse_terms("Kubernetes, PhantomEar;\n kubernetes , OKR");
+        assert_eq!(
+            vocabulary,
+            vec!["Kubernetes", "PhantomEar", "kubernetes", "OKR"]
+        );
+
+        let prompt = build_prompt(&vocabulary, Some("Q3 planning"), "we shipped the beta");
+        assert_eq!(
+            prompt,
+            "Q3 planning. Glossary: Kubernetes, PhantomEar, OKR. we shipped the beta"
+        );
+
+        let long = "word ".repeat(400);
+        let prompt = build_prompt(&vocabulary, None, &long);
+        assert!(prompt.len() <= MAX_PROMPT_CHARS);
+        assert!(prompt.starts_with("Glossary: "));
+        assert!(prompt.ends_with("word"));
+        assert_eq!(build_prompt(&[], None, "  "), "");
+    }
+
+    #[test]
+    fn test_detects_loops_and_copied_prompts() {
+        let prompt = "Glossary: Kubernetes, PhantomEar. so the rollout starts on monday";
+        assert!(!is_hallucination(
+            "Kubernetes upgrade is blocked on the PhantomEar release",
+            prompt
+        ));
+        assert!(is_hallucination("the rollout starts on Monday.", prompt));
+        assert!(is_hallucination(
+            "Thank you. Thank you. Thank you. Thank you.",
+            ""
+        ));
+        assert!(is_hallucination("no no no no no no", ""));
+        // Ordinary emphasis is not a loop
+        assert!(!is_hallucination("no, no, no, that's wrong", ""));
+    }
+}
@@ -0,0 +1,409 @@
+// Chunk stitching
+// Speech cut at the maximum chunk length is transcribed twice around the cut: the next chunk
+// starts `overlap_secs` before it. Aligning the words of that shared stretch tells where one
+// transcript should end and the next begin, so words at the boundary are neither repeated nor
+// left cut in half.
+//
+// Backends that don't time their words are aligned on text alone: the longest run of words
+// shared by the end of one transcript and the start of the next. Text is always cut where the
+// words are found in it, so its own spacing (none between CJK characters) is kept.
+
+use crate::asr::{TranscriptionSegment, WordTiming};
+
+/// Words this close outside the shared audio still count as part of it (timing jitter)
+const BOUNDARY_SLACK_MS: i64 = 200;
+/// A single matching word only anchors the alignment if it is at least this long
+const MIN_ANCHOR_CHARS: usize = 4;
+/// Words compared at either side of the cut when aligning on text alone
+const TEXT_OVERLAP_WORDS: usize = 16;
+
+/// Where two overlapping transcripts join
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stitch {
+    /// Words of the previous transcript to keep
+    pub keep_previous: usize,
+    /// Leading words of the next transcript already covered by the previous one
+    pub skip_current: usize,
+}
+
+fn normalize(word: &str) -> String {
+    word.chars()
+        .filter(|c| c.is_alphanumeric())
+        .collect::<String>()
+        .to_lowercase()
+}
+
+fn midpoint(word: &WordTiming) -> i64 {
+    (word.start_ms + word.end_ms) / 2
+}
+
+/// (start in `tail`, start in `head`, length) of the longest run of words both share
+fn longest_common_run(tail: &[String], head: &[String]) -> (usize, usize, usize) {
+    let mut best = (0, 0, 0);
+    for i in 0..tail.len() {
+        for j in 0..head.len() {
+            let len = tail[i..]
+                .iter()
+                .zip(&head[j..])
+                .take_while(|(a, b)| !a.is_empty() && a == b)
+                .count();
+            if len > best.2 {
+                best = (i, j, len);
+            }
+        }
+    }
+    best
+}
+
+/// Whether a shared run is long enough to join on
+fn is_anchor(tail: &[String], start: usize, len: usize) -> bool {
+    len >= 2 || (len == 1 && tail[start].chars().count() >= MIN_ANCHOR_CHARS)
+}
+
+fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{3040}'..='\u{30ff}'
+        | '\u{3400}'..='\u{9fff}'
+        | '\u{ac00}'..='\u{d7af}'
+        | '\u{f900}'..='\u{faff}')
+}
+
+/// Byte ranges of the words of a text: runs between whitespace, with each CJK character a
+/// word of its own
+pub fn text_words(text: &str) -> Vec<(usize, usize)> {
+    let mut words = Vec::new();
+    let mut start = None;
+    for (i, c) in text.char_indices() {
+        if c.is_whitespace() || is_cjk(c) {
+            if let Some(s) = start.take() {
+                words.push((s, i));
+            }
+            if is_cjk(c) {
+                words.push((i, i + c.len_utf8()));
+            }
+        } else if start.is_none() {
+            start = Some(i);
+        }
+    }
+    if let Some(s) = start {
+        words.push((s, text.len()));
+    }
+    words
+}
+
+/// Align the end of `previous` with the start of `current` by their text, for transcripts
+/// without word timings. Counts are of `text_words`; None without a convincing join.
+pub fn align_text(previous: &str, current: &str) -> Option<Stitch> {
+    let previous_words = text_words(previous);
+    let tail_from = previous_words.len().saturating_sub(TEXT_OVERLAP_WORDS);
+    let tail: Vec<String> = previous_words[tail_from..]
+        .iter()
+        .map(|&(s, e)| normalize(&previous[s..e]))
+        .collect();
+    let head: Vec<String> = text_words(current)
+        .into_iter()
+        .take(TEXT_OVERLAP_WORDS)
+        .map(|(s, e)| normalize(&current[s..e]))
+        .collect();
+
+    let (i, j, len) = longest_common_run(&tail, &head);
+    is_anchor(&tail, i, len).then_some(Stitch {
+        keep_previous: tail_from + i + len,
+        skip_current: j + len,
+    })
+}
+
+/// Byte ranges of a segment's timed words in its text, found in order; None if one isn't
+/// there (the text was rewritten, e.g. punctuation restored)
+fn word_spans(text: &str, words: &[WordTiming]) -> Option<Vec<(usize, usize)>> {
+    let mut spans = Vec::with_capacity(words.len());
+    let mut from = 0;
+    for word in words {
+        let start = from + text[from..].find(word.text.as_str())?;
+        from = start + word.text.len();
+        spans.push((start, from));
+    }
+    Some(spans)
+}
+
+/// Align the end of `previous` with the start of `current`, which share the audio from
+/// `overlap_start_ms` to `overlap_end_ms`. The longest run of words both heard in that stretch
+/// is the join; without one, the stretch is split at its midpoint.
+pub fn align(
+    previous: &[WordTiming],
+    current: &[WordTiming],
+    overlap_start_ms: i64,
+    overlap_end_ms: i64,
+) -> Stitch {
+    let tail_from = previous
+        .iter()
+        .position(|w| w.end_ms > overlap_start_ms - BOUNDARY_SLACK_MS)
+        .unwrap_or(previous.len());
+    let head_len = current
+        .iter()
+        .take_while(|w| w.start_ms < overlap_end_ms + BOUNDARY_SLACK_MS)
+        .count();
+
+    let tail: Vec<String> = previous[tail_from..]
+        .iter()
+        .map(|w| normalize(&w.text))
+        .collect();
+    let head: Vec<String> = current[..head_len]
+        .iter()
+        .map(|w| normalize(&w.text))
+        .collect();
+
+    let best = longest_common_run(&tail, &head);
+    let (i, j, len) = best;
+    if is_anchor(&tail, i, len) {
+        return Stitch {
+            keep_previous: tail_from + i + len,
+            skip_current: j + len,
+        };
+    }
+
+    let mid = (overlap_start_ms + overlap_end_ms) / 2;
+    Stitch {
+        keep_previous: tail_from
+            + previous[tail_from..]
+                .iter()
+                .take_while(|w| midpoint(w) < mid)
+                .count(),
+        skip_current: current[..head_len]
+            .iter()
+            .take_while(|w| midpoint(w) < mid)
+            .count(),
+    }
+}
+
+fn join_words(words: &[WordTiming]) -> String {
+    words
+        .iter()
+        .map(|w| w.text.as_str())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Keep the first `n` words of a segment, ending it at the last one kept
+pub fn truncate_words(segment: &mut TranscriptionSegment, n: usize) {
+    if n >= segment.words.len() {
+        return;
+    }
+    segment.text = match word_spans(&segment.text, &segment.words) {
+        Some(spans) => {
+            let end = n.checked_sub(1).map_or(0, |last| spans[last].1);
+            segment.text[..end].trim_end().to_string()
+        }
+        None => join_words(&segment.words[..n]),
+    };
+    segment.words.truncate(n);
+    if let Some(last) = segment.words.last() {
+        segment.end_ms = last.end_ms.max(segment.start_ms);
+    }
+}
+
+/// Keep the first `n` words (`text_words`) of a segment's text. Any word timings are dropped,
+/// as they no longer match.
+pub fn truncate_text(segment: &mut TranscriptionSegment, n: usize) {
+    let words = text_words(&segment.text);
+    if n >= words.len() {
+        return;
+    }
+    let end = n.checked_sub(1).map_or(0, |last| words[last].1);
+    segment.text = segment.text[..end].trim_end().to_string();
+    segment.words.clear();
+}
+
+/// Drop the first `n` words (`text_words`) across consecutive segments, removing segments
+/// left empty; the timings of the segment cut into are dropped
+pub fn drop_leading_text(segments: &mut Vec<TranscriptionSegment>, mut n: usize) {
+    while n > 0 {
+        let Some(first) = segments.first_mut() else {
+            return;
+        };
+        let words = text_words(&first.text);
+        if n >= words.len() {
+            n -= words.len();
+            segments.remove(0);
+            continue;
+        }
+        first.text = first.text[words[n].0..].to_string();
+        first.words.clear();
+        n = 0;
+    }
+}
+
+/// Drop the first `n` words across consecutive segments, removing segments left empty and
+/// starting the first remaining one at its first word
+pub fn drop_leading_words(segments: &mut Vec<TranscriptionSegment>, mut n: usize) {
+    while n > 0 {
+        let Some(first) = segments.first_mut() else {
+            return;
+        };
+        if n >= first.words.len() {
+            n -= first.words.len();
+            segments.remove(0);
+            continue;
+        }
+        first.text = match word_spans(&first.text, &first.words) {
+            Some(spans) => first.text[spans[n].0..].to_string(),
+            None => join_words(&first.words[n..]),
+        };
+        first.words.drain(..n);
+        first.start_ms = first.words[0].start_ms.min(first.end_ms);
+        n = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(spec: &[(&str, i64, i64)]) -> Vec<WordTiming> {
+        spec.iter()
+            .map(|&(text, start_ms, end_ms)| WordTiming {
+                text: text.to_string(),
+                start_ms,
+                end_ms,
+                probability: 0.9,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_align_joins_on_shared_words() {
+        // Cut at 10000 ms, next chunk from 9000 ms: "deploy" was cut in half in the first
+        let previous = words(&[
+            ("so", 8000, 8300),
+            ("we", 8300, 8600),
+            ("should", 9000, 9400),
+            ("de-", 9700, 10000),
+        ]);
+        let current = words(&[
+            ("should", 9050, 9400),
+            ("deploy", 9700, 10300),
+            ("it", 10300, 10500),
+            ("today.", 10500, 11000),
+        ]);
+        let stitch = align(&previous, &current, 9000, 10000);
+        assert_eq!(
+            stitch,
+            Stitch {
+                keep_previous: 3,
+                skip_current: 1
+            }
+        );
+    }
+
+    #[test]
+    fn test_align_splits_at_midpoint_without_shared_words() {
+        let previous = words(&[
+            ("okay", 8000, 8500),
+            ("um", 9100, 9300),
+            ("yes", 9600, 9900),
+        ]);
+        let current = words(&[
+            ("uh", 9100, 9300),
+            ("yeah", 9600, 9900),
+            ("right", 10100, 10400),
+        ]);
+        let stitch = align(&previous, &current, 9000, 10000);
+        assert_eq!(
+            stitch,
+            Stitch {
+                keep_previous: 2,
+                skip_current: 1
+            }
+        );
+    }
+
+    #[test]
+    fn test_trimming_rewrites_text_and_timing() {
+        let mut previous = TranscriptionSegment {
+            text: "we should de-".to_string(),
+            start_ms: 8300,
+            end_ms: 10000,
+            words: words(&[
+                ("we", 8300, 8600),
+                ("should", 9000, 9400),
+                ("de-", 9700, 10000),
+            ]),
+        };
+        truncate_words(&mut previous, 2);
+        assert_eq!(previous.text, "we should");
+        assert_eq!(previous.end_ms, 9400);
+
+        let mut current = vec![
+            TranscriptionSegment {
+                text: "should".to_string(),
+                start_ms: 9000,
+                end_ms: 9400,
+                words: words(&[("should", 9050, 9400)]),
+            },
+            TranscriptionSegment {
+                text: "deploy it today.".to_string(),
+                start_ms: 9400,
+                end_ms: 11000,
+                words: words(&[
+                    ("deploy", 9700, 10300),
+                    ("it", 10300, 10500),
+                    ("today.", 10500, 11000),
+                ]),
+            },
+        ];
+        drop_leading_words(&mut current, 2);
+        assert_eq!(current.len(), 1);
+        assert_eq!(current[0].text, "it today.");
+        assert_eq!(current[0].start_ms, 10300);
+    }
+
+    #[test]
+    fn test_trimming_keeps_text_spacing() {
+        // CJK text has no spaces between words
+        let mut segment = TranscriptionSegment {
+            text: "今日は会議です".to_string(),
+            start_ms: 0,
+            end_ms: 2000,
+            words: words(&[
+                ("今日は", 0, 800),
+                ("会議", 800, 1500),
+                ("です", 1500, 2000),
+            ]),
+        };
+        truncate_words(&mut segment, 2);
+        assert_eq!(segment.text, "今日は会議");
+        let mut segments = vec![segment];
+        drop_leading_words(&mut segments, 1);
+        assert_eq!(segments[0].text, "会議");
+    }
+
+    #[test]
+    fn test_align_text_without_timings() {
+        let stitch = align_text(
+            "we looked at the numbers and the launch moves to next",
+            "the launch moves to next Tuesday at noon",
+        )
+        .unwrap();
+        assert_eq!(
+            stitch,
+            Stitch {
+                keep_previous: 11,
+                skip_current: 5
+            }
+        );
+        let mut current = vec![TranscriptionSegment {
+            text: "the launch moves to next  Tuesday at noon".to_string(),
+            start_ms: 0,
+            end_ms: 0,
+            words: Vec::new(),
+        }];
+        drop_leading_text(&mut current, stitch.skip_current);
+        assert_eq!(current[0].text, "Tuesday at noon");
+        assert!(align_text("sounds good", "right, so next item").is_none());
+        // CJK characters count as words
+        assert_eq!(
+            align_text("来週の火曜日に", "火曜日に発表します").map(|s| s.skip_current),
+            Some(4)
+        );
+    }
+}
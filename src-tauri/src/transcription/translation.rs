@@ -0,0 +1,111 @@
+// Translation track
+// A second text per segment in another language, for meetings with colleagues who don't
+// share one. Whisper can decode speech straight into English (a second decoder pass over the
+// chunk); any other target language goes through the LLM, one segment at a time.
+//
+// Whisper's translation pass times its own segments, which don't line up with the
+// transcript's, so each translated segment is given to the transcript segment its middle
+// falls in.
+
+use crate::asr::TranscriptionSegment;
+
+/// How segments are translated, from `Settings::translation_mode`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TranslationMode {
+    Off,
+    /// Whisper's speech-to-English translation
+    Whisper,
+    /// The configured LLM, into any language
+    Llm,
+}
+
+impl TranslationMode {
+    /// Unknown values turn translation off
+    pub fn parse(mode: &str) -> Self {
+        match mode.trim().to_lowercase().as_str() {
+            "whisper" => TranslationMode::Whisper,
+            "llm" => TranslationMode::Llm,
+            _ => TranslationMode::Off,
+        }
+    }
+}
+
+/// Whether text in `language` needs translating into `target` (unknown languages do)
+pub fn needs_translation(language: Option<&str>, target: &str) -> bool {
+    language.is_none_or(|language| !language.eq_ignore_ascii_case(target.trim()))
+}
+
+/// Split a translation of a whole chunk among the chunk's transcript segments (start and end
+/// times). Translated text timed before the first segment covers speech that was stitched
+/// onto the previous chunk, and is dropped.
+pub fn assign_translations(
+    segments: &[(i64, i64)],
+    translated: &[TranscriptionSegment],
+) -> Vec<Option<String>> {
+    let mut texts: Vec<Vec<&str>> = vec![Vec::new(); segments.len()];
+    let Some(first_start) = segments.first().map(|s| s.0) else {
+        return Vec::new();
+    };
+    for piece in translated {
+        let text = piece.text.trim();
+        let middle = (piece.start_ms + piece.end_ms) / 2;
+        if text.is_empty() || middle < first_start {
+            continue;
+        }
+        // The last segment starting at or before the middle; segments are in time order
+        let index = segments
+            .iter()
+            .rposition(|&(start, _)| start <= middle)
+            .unwrap_or(0);
+        texts[index].push(text);
+    }
+    texts
+        .into_iter()
+        .map(|parts| (!parts.is_empty()).then(|| parts.join(" ")))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(text: &str, start_ms: i64, end_ms: i64) -> TranscriptionSegment {
+        TranscriptionSegment {
+            text: text.to_string(),
+            start_ms,
+            end_ms,
+            words: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_assign_translations() {
+        let segments = [(1000, 4000), (4000, 9000), (9000, 10000)];
+        let translated = [
+            // Stitched onto the previous chunk
+            segment("overlap", 0, 1200),
+            segment("Good morning.", 1000, 3500),
+            segment("Let's start", 3800, 6000),
+            segment("with the budget.", 6000, 8800),
+        ];
+        assert_eq!(
+            assign_translations(&segments, &translated),
+            vec![
+                Some("Good morning.".to_string()),
+                Some("Let's start with the budget.".to_string()),
+                None,
+            ]
+        );
+        assert!(assign_translations(&[], &translated).is_empty());
+    }
+
+    #[test]
+    fn test_needs_translation() {
+        assert!(needs_translation(Some("es"), "en"));
+        assert!(!needs_translation(Some("en"), "EN"));
+        assert!(needs_translation(None, "en"));
+        assert_eq!(TranslationMode::parse("Whisper"), TranslationMode::Whisper);
+        assert_eq!(TranslationMode::parse("off"), TranslationMode::Off);
+        assert_eq!(TranslationMode::parse("nonsense"), TranslationMode::Off);
+    }
+}
@@ -0,0 +1,514 @@
+// Transcription workers
+// One decoder falls behind when mic and system audio are both speaking, while most of the
+// machine's cores sit idle. Chunks are handed to a pool of workers instead, each decoding on a
+// Whisper state of its own with its share of the cores (backends that decode one chunk at a
+// time, like Parakeet, get a single worker). Workers finish out of order, so their results are
+// put back in queue order (which is timestamp order) before they are committed.
+
+use crate::asr::{self, PunctuationModel, TranscriptionEngine, WhisperModel};
+use crate::commands::{
+    decoder_prompt, restore_punctuation, source_language, timeline_segments, transcribe_prompted,
+    translation_target, whisper_translation, Settings,
+};
+use crate::storage::Database;
+use crate::transcription::load_shedding::{
+    self, LoadShedder, SheddingAction, SheddingDecision, SpillQueue,
+};
+use crate::transcription::translation::TranslationMode;
+use crate::transcription::AudioChunk;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::mpsc::{Receiver, UnboundedSender};
+use tokio::sync::{Mutex, RwLock};
+
+/// Most workers "auto" picks; more rarely pays off against fewer threads each
+const AUTO_MAX_WORKERS: usize = 4;
+/// Whisper gains little from more threads than this on one chunk
+const AUTO_MAX_THREADS: usize = 8;
+
+/// Workers and decoder threads per worker on `cpus` cores, from the settings (0 = auto)
+pub fn worker_plan(workers: usize, threads: usize, cpus: usize) -> (usize, usize) {
+    let cpus = cpus.max(1);
+    let workers = match workers {
+        0 => (cpus / 4).clamp(1, AUTO_MAX_WORKERS),
+        n => n,
+    };
+    let threads = match threads {
+        0 => (cpus / workers).clamp(1, AUTO_MAX_THREADS),
+        n => n,
+    };
+    (workers, threads)
+}
+
+/// Throughput of one worker over a recording
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct WorkerStats {
+    pub worker: usize,
+    pub threads: usize,
+    pub chunks: u64,
+    /// Audio transcribed
+    pub audio_ms: u64,
+    /// Time spent transcribing it
+    pub busy_ms: u64,
+    /// Transcription time per second of audio; above 1.0 the worker can't keep up alone
+    pub real_time_factor: f32,
+    /// The same for the worker's last chunk
+    pub last_real_time_factor: f32,
+}
+
+impl WorkerStats {
+    pub fn new(worker: usize, threads: usize) -> Self {
+        Self {
+            worker,
+            threads,
+            ..Default::default()
+        }
+    }
+
+    /// Count a chunk of `audio_ms` that took `busy_ms` to transcribe
+    pub fn record(&mut self, audio_ms: u64, busy_ms: u64) {
+        self.chunks += 1;
+        self.audio_ms += audio_ms;
+        self.busy_ms += busy_ms;
+        self.real_time_factor = ratio(self.busy_ms, self.audio_ms);
+        self.last_real_time_factor = ratio(busy_ms, audio_ms);
+    }
+}
+
+fn ratio(busy_ms: u64, audio_ms: u64) -> f32 {
+    if audio_ms == 0 {
+        0.0
+    } else {
+        busy_ms as f32 / audio_ms as f32
+    }
+}
+
+/// Puts results numbered 0, 1, 2, ... back in order as they arrive
+#[derive(Debug)]
+pub struct InOrder<T> {
+    next: u64,
+    waiting: BTreeMap<u64, T>,
+}
+
+impl<T> InOrder<T> {
+    pub fn new() -> Self {
+        Self {
+            next: 0,
+            waiting: BTreeMap::new(),
+        }
+    }
+
+    /// Add result `seq`, returning the results that are now next in line
+    pub fn push(&mut self, seq: u64, item: T) -> Vec<T> {
+        self.waiting.insert(seq, item);
+        let mut ready = Vec::new();
+        while let Some(item) = self.waiting.remove(&self.next) {
+            ready.push(item);
+            self.next += 1;
+        }
+        ready
+    }
+}
+
+impl<T> Default for InOrder<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A transcribed chunk on its way from a worker to the consumer
+pub struct TranscribedChunk {
+    pub chunk: AudioChunk,
+    pub result: anyhow::Result<ChunkTranscript>,
+    /// Spilled earlier and transcribed late, out of time order
+    pub catch_up: bool,
+    /// Dropped once the chunk is committed, releasing the worker for its next chunk
+    pub committed: tokio::sync::oneshot::Sender<()>,
+}
+
+/// What a worker made of a chunk, left for the consumer to stitch and commit
+pub struct ChunkTranscript {
+    /// Segments on the meeting timeline, punctuation restored
+    pub segments: Vec<asr::TranscriptionSegment>,
+    pub language: Option<String>,
+    pub language_probability: Option<f32>,
+    /// Whisper's English translation of the chunk (empty = none)
+    pub translation: Vec<asr::TranscriptionSegment>,
+}
+
+/// Results of the workers, by chunk number; None = the chunk was lost
+pub type WorkerResults<T = TranscribedChunk> = UnboundedSender<(u64, Option<T>)>;
+
+/// A chunk number taken by a worker. Dropped without a result (the worker panicked), it
+/// reports the chunk as lost, so the consumer doesn't wait for it forever.
+struct TakenChunk<T = TranscribedChunk> {
+    seq: u64,
+    results: Option<WorkerResults<T>>,
+}
+
+impl<T> TakenChunk<T> {
+    fn new(seq: u64, results: WorkerResults<T>) -> Self {
+        Self {
+            seq,
+            results: Some(results),
+        }
+    }
+
+    /// Hand the result to the consumer; false once the consumer is gone
+    fn send(mut self, transcribed: T) -> bool {
+        self.results
+            .take()
+            .is_some_and(|results| results.send((self.seq, Some(transcribed))).is_ok())
+    }
+}
+
+impl<T> Drop for TakenChunk<T> {
+    fn drop(&mut self) {
+        if let Some(results) = self.results.take() {
+            log::error!(
+                "Transcription worker stopped without finishing chunk {}",
+                self.seq
+            );
+            let _ = results.send((self.seq, None));
+        }
+    }
+}
+
+/// Chunks waiting for a worker
+pub struct ChunkQueue {
+    pub(crate) rx: Receiver<AudioChunk>,
+    /// Number of the next chunk taken, for committing in order
+    taken: u64,
+    /// Chunks read ahead while merging that didn't join the merged chunk, in arrival order
+    pub(crate) held: VecDeque<AudioChunk>,
+    /// Chunks spilled to disk while shedding load
+    pub(crate) spilled: SpillQueue,
+}
+
+impl ChunkQueue {
+    pub fn new(rx: Receiver<AudioChunk>, spill_dir: PathBuf) -> Self {
+        Self {
+            rx,
+            taken: 0,
+            held: VecDeque::new(),
+            spilled: SpillQueue::new(spill_dir),
+        }
+    }
+
+    pub(crate) fn number(&mut self) -> u64 {
+        self.taken += 1;
+        self.taken - 1
+    }
+}
+
+/// What the transcription workers of a recording share
+#[derive(Clone)]
+pub struct WorkerPool {
+    pub app: AppHandle,
+    pub engine: Arc<RwLock<Option<TranscriptionEngine>>>,
+    /// Smaller model, loaded the first time load shedding switches to it
+    pub fallback_engine: Arc<RwLock<Option<TranscriptionEngine>>>,
+    pub threads: usize,
+    pub db: Arc<Database>,
+    pub meeting_id: String,
+    pub meeting_title: String,
+    pub settings: Arc<Mutex<Settings>>,
+    pub punctuation_model: Arc<Mutex<Option<PunctuationModel>>>,
+    pub queue: Arc<Mutex<ChunkQueue>>,
+    pub pending_chunks: Arc<AtomicUsize>,
+    /// Chunks being transcribed
+    pub busy: Arc<AtomicUsize>,
+    /// Last final text of each source, carried into the next chunk's prompt
+    pub previous_text: Arc<std::sync::Mutex<HashMap<String, String>>>,
+    pub stats: Arc<std::sync::Mutex<Vec<WorkerStats>>>,
+    pub shedder: Arc<std::sync::Mutex<LoadShedder>>,
+    pub results: WorkerResults,
+}
+
+impl WorkerPool {
+    /// Count a chunk taken off the queue, returning the chunks still queued
+    pub(crate) fn take_pending(&self) -> usize {
+        // Atomically decrement so the producer can never observe a torn value
+        // between load and store. fetch_sub returns the old value, so subtract 1.
+        self.pending_chunks
+            .fetch_sub(1, Ordering::SeqCst)
+            .saturating_sub(1)
+    }
+
+    /// Feed the load shedder and report what it decides
+    pub(crate) fn observe_load(&self, latency_ms: u64) {
+        let queued = self.pending_chunks.load(Ordering::SeqCst);
+        let decision = self.shedder.lock().unwrap().observe(queued, latency_ms);
+        if let Some(decision) = decision {
+            self.report_shedding(decision, queued);
+        }
+    }
+
+    fn report_shedding(&self, decision: SheddingDecision, queued: usize) {
+        log::info!(
+            "Load shedding: {:?} {} ({})",
+            decision.action,
+            if decision.engaged {
+                "engaged"
+            } else {
+                "released"
+            },
+            decision.reason
+        );
+        let _ = self.app.emit(
+            "transcription-status",
+            serde_json::json!({
+                "status": "load_shedding",
+                "action": decision.action,
+                "engaged": decision.engaged,
+                "reason": decision.reason,
+                "pending_chunks": queued,
+            }),
+        );
+        if decision.engaged && decision.action == SheddingAction::SmallerModel {
+            tauri::async_runtime::spawn(load_fallback_engine(self.clone()));
+        }
+    }
+}
+
+/// Load the smaller model for load shedding, unless it already is
+async fn load_fallback_engine(pool: WorkerPool) {
+    if pool.fallback_engine.read().await.is_some() {
+        return;
+    }
+    let (model_name, language) = {
+        let settings = pool.settings.lock().await;
+        (
+            settings.load_shedding.fallback_model.clone(),
+            settings.language.clone(),
+        )
+    };
+    let threads = pool.threads;
+    let loaded = tokio::task::spawn_blocking(move || -> anyhow::Result<TranscriptionEngine> {
+        let model: WhisperModel = model_name.parse()?;
+        let mut engine = TranscriptionEngine::new();
+        engine.set_language(&language);
+        engine.set_threads(threads);
+        engine.load_model(&asr::get_model_path(model)?)?;
+        Ok(engine)
+    })
+    .await;
+    match loaded {
+        Ok(Ok(engine)) => {
+            log::info!("Loaded smaller model for load shedding");
+            *pool.fallback_engine.write().await = Some(engine);
+        }
+        Ok(Err(e)) => log::warn!("Smaller model for load shedding not loaded: {}", e),
+        Err(e) => log::error!("Loading smaller model panicked: {}", e),
+    }
+}
+
+/// Transcribe a chunk and get it ready to commit: on the meeting timeline, punctuation
+/// restored, and translated by Whisper in that translation mode
+async fn transcribe_chunk(
+    pool: &WorkerPool,
+    engine: &TranscriptionEngine,
+    chunk: &AudioChunk,
+    prompt: &str,
+    language: &asr::LanguageOptions,
+) -> anyhow::Result<ChunkTranscript> {
+    let result =
+        transcribe_prompted(engine, &chunk.samples, chunk.start_ms, prompt, language).await?;
+    let detected = result.language.clone();
+    let language_probability = result.language_probability;
+    let mut segments =
+        timeline_segments(result, chunk.start_ms, chunk.start_ms + chunk.duration_ms);
+    let mut translation = Vec::new();
+    // Partial results are only shown, as they come
+    if !chunk.partial {
+        let (restore, (mut mode, _)) = {
+            let settings = pool.settings.lock().await;
+            (settings.restore_punctuation, translation_target(&settings))
+        };
+        if restore {
+            if let Some(ref model) = *pool.punctuation_model.lock().await {
+                tokio::task::block_in_place(|| restore_punctuation(model, &mut segments));
+            }
+        }
+        if pool
+            .shedder
+            .lock()
+            .unwrap()
+            .is_engaged(SheddingAction::SkipAi)
+        {
+            mode = TranslationMode::Off;
+        }
+        translation = whisper_translation(
+            engine,
+            mode,
+            &chunk.samples,
+            chunk.start_ms,
+            language,
+            detected.as_deref(),
+            &segments,
+        )
+        .await;
+    }
+    Ok(ChunkTranscript {
+        segments,
+        language: detected,
+        language_probability,
+        translation,
+    })
+}
+
+/// Transcription worker: takes the next queued chunk and transcribes it. A worker waits for
+/// its chunk to be committed before taking another, so a single worker prompts every chunk
+/// with the text before it, and no more chunks than workers are ever in flight.
+pub async fn run_transcription_worker(pool: WorkerPool, worker: usize) {
+    while let Some((seq, chunk, catch_up)) = load_shedding::next_chunk(&pool).await {
+        let taken = TakenChunk::new(seq, pool.results.clone());
+        pool.busy.fetch_add(1, Ordering::SeqCst);
+
+        let _ = pool.app.emit(
+            "transcription-status",
+            serde_json::json!({
+                "status": "processing",
+                "pending_chunks": pool.pending_chunks.load(Ordering::SeqCst),
+            }),
+        );
+
+        let previous_text = pool
+            .previous_text
+            .lock()
+            .unwrap()
+            .get(chunk.source.as_str())
+            .cloned()
+            .unwrap_or_default();
+        let (prompt, language) = {
+            let settings = pool.settings.lock().await;
+            (
+                decoder_prompt(
+                    &pool.db,
+                    &pool.meeting_id,
+                    Some(&pool.meeting_title),
+                    &settings,
+                    &previous_text,
+                ),
+                source_language(&settings, Some(chunk.source.as_str())),
+            )
+        };
+
+        // Workers share the engine and decode in parallel (on blocking threads)
+        let started = std::time::Instant::now();
+        let smaller = pool
+            .shedder
+            .lock()
+            .unwrap()
+            .is_engaged(SheddingAction::SmallerModel);
+        let result = {
+            let fallback = pool.fallback_engine.read().await;
+            let engine = pool.engine.read().await;
+            match fallback.as_ref().filter(|_| smaller).or(engine.as_ref()) {
+                Some(eng) => transcribe_chunk(&pool, eng, &chunk, &prompt, &language).await,
+                None => Err(anyhow::anyhow!("No transcription engine")),
+            }
+        };
+        if let Some(stats) = pool.stats.lock().unwrap().get_mut(worker) {
+            stats.record(
+                chunk.duration_ms.max(0) as u64,
+                started.elapsed().as_millis() as u64,
+            );
+        }
+        pool.observe_load(chunk.queued_at.elapsed().as_millis() as u64);
+
+        let still_busy = pool.busy.fetch_sub(1, Ordering::SeqCst).saturating_sub(1);
+        let _ = pool.app.emit(
+            "transcription-status",
+            serde_json::json!({
+                "status": if still_busy == 0 { "idle" } else { "processing" },
+                "pending_chunks": pool.pending_chunks.load(Ordering::SeqCst),
+            }),
+        );
+
+        let (committed, was_committed) = tokio::sync::oneshot::channel();
+        let transcribed = TranscribedChunk {
+            chunk,
+            result,
+            catch_up,
+            committed,
+        };
+        if !taken.send(transcribed) {
+            break;
+        }
+        let _ = was_committed.await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_worker_plan() {
+        assert_eq!(worker_plan(0, 0, 8), (2, 4));
+        assert_eq!(worker_plan(0, 0, 2), (1, 2));
+        assert_eq!(worker_plan(0, 0, 32), (4, 8));
+        assert_eq!(worker_plan(3, 0, 8), (3, 2));
+        assert_eq!(worker_plan(2, 6, 8), (2, 6));
+        assert_eq!(worker_plan(0, 0, 0), (1, 1));
+    }
+
+    #[test]
+    fn test_worker_stats() {
+        let mut stats = WorkerStats::new(1, 4);
+        stats.record(10_000, 2_000);
+        stats.record(10_000, 6_000);
+        assert_eq!(stats.chunks, 2);
+        assert!((stats.real_time_factor - 0.4).abs() < 1e-6);
+        assert!((stats.last_real_time_factor - 0.6).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_in_order() {
+        let mut order = InOrder::new();
+        assert!(order.push(1, "b").is_empty());
+        assert!(order.push(2, "c").is_empty());
+        assert_eq!(order.push(0, "a"), vec!["a", "b", "c"]);
+        assert_eq!(order.push(3, "d"), vec!["d"]);
+    }
+
+    #[test]
+    fn test_lost_chunk_commits_in_order() {
+        let (_audio, rx) = tokio::sync::mpsc::channel(1);
+        let mut queue = ChunkQueue::new(rx, std::env::temp_dir());
+        let (results, mut received) = tokio::sync::mpsc::unbounded_channel();
+        let first = TakenChunk::new(queue.number(), results.clone());
+        let lost = TakenChunk::new(queue.number(), results.clone());
+        let last = TakenChunk::new(queue.number(), results);
+
+        assert!(last.send("c"));
+        // Its worker panicked before sending
+        drop(lost);
+        let mut order = InOrder::new();
+        let mut committed = Vec::new();
+        while let Ok((seq, item)) = received.try_recv() {
+            committed.extend(order.push(seq, item).into_iter().flatten());
+        }
+        assert!(committed.is_empty());
+
+        assert!(first.send("a"));
+        while let Ok((seq, item)) = received.try_recv() {
+            committed.extend(order.push(seq, item).into_iter().flatten());
+        }
+        assert_eq!(committed, ["a", "c"]);
+    }
+
+    #[test]
+    fn test_taken_chunk_without_consumer() {
+        let (results, received) = tokio::sync::mpsc::unbounded_channel();
+        drop(received);
+        assert!(!TakenChunk::new(0, results.clone()).send("a"));
+        // Dropped unsent, it can't report the chunk either
+        drop(TakenChunk::<&str>::new(1, results));
+    }
+}
@@ -27,6 +27,11 @@ function createMeetingsStore() {
     }
   }
 
+  /** Live transcript kept when a final pass replaced it (empty if it wasn't kept) */
+  async function getLiveTranscript(id: string): Promise<TranscriptSegment[]> {
+    return invoke<TranscriptSegment[]>('get_live_transcript', { meetingId: id });
+  }
+
   function setActive(id: string | null) {
     activeMeetingId = id;
     if (!id) {
@@ -47,6 +52,14 @@ function createMeetingsStore() {
     activeTranscript = [...activeTranscript, segment];
   }
 
+  function updateLocalSegment(segment: TranscriptSegment) {
+    activeTranscript = activeTranscript.map(s => (s.id === segment.id ? { ...s, ...segment } : s));
+  }
+
+  function removeLocalSegment(id: string) {
+    activeTranscript = activeTranscript.filter(s => s.id !== id);
+  }
+
   async function renameMeeting(id: string, newTitle: string) {
     const title = newTitle.trim();
     if (!title) return;
@@ -129,8 +142,9 @@ function createMeetingsStore() {
     }
   }
 
-  async function exportMeeting(id: string, format: string = 'markdown'): Promise<string> {
-    return invoke<string>('export_meeting', { id, format });
+  /** `track`: "original", "translation" or "both" */
+  async function exportMeeting(id: string, format: string = 'markdown', track: string = 'original'): Promise<string> {
+    return invoke<string>('export_meeting', { id, format, track });
   }
 
   function getPinnedMeetings(): MeetingListItem[] {
@@ -154,10 +168,13 @@ function createMeetingsStore() {
     get activeMeeting() { return getActiveMeeting(); },
     loadMeetings,
     selectMeeting,
+    getLiveTranscript,
     setActive,
     clearActive,
     setActiveTranscript,
     addLocalSegment,
+    updateLocalSegment,
+    removeLocalSegment,
     renameMeeting,
     updateMeetingTitle,
     updateMeetingTags,
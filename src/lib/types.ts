@@ -5,9 +5,17 @@ export interface TranscriptSegment {
   time: string;
   text: string;
   timestamp_ms: number;
+  /** End of the speech (older meetings: same as timestamp_ms) */
+  end_ms?: number;
   speaker_id?: string | null;
   /** "mic" = local user, "system" = remote participants */
   source?: string;
+  /** Live recording only: speech still being transcribed */
+  is_partial?: boolean;
+  /** Language the segment was transcribed in (older meetings: unknown) */
+  language?: string | null;
+  /** Second text in another language, when translation is on */
+  translation?: string | null;
 }
 
 export interface Meeting {
@@ -106,6 +114,48 @@ export interface TranscriptionEvent {
   end_ms: number;
   is_partial: boolean;
   source: string;
+  /** On a final segment: id of the partial result it replaces */
+  partial_id?: string | null;
+  /** Language the text was transcribed in, when known */
+  language?: string | null;
+  /** Detection probability of `language`; null when it was set for the source */
+  language_probability?: number | null;
+}
+
+export interface TranscriptionStatusEvent {
+  /** "queued", "processing", "idle" or "load_shedding" */
+  status: string;
+  pending_chunks: number;
+  /** load_shedding: the measure ("skip_ai", "merge_chunks", "smaller_model", "spill_to_disk") */
+  action?: string;
+  /** load_shedding: engaged, or released */
+  engaged?: boolean;
+  reason?: string;
+}
+
+/** "final-pass-progress": the recording is transcribed again with the final model */
+export interface FinalPassProgressEvent {
+  meeting_id: string;
+  /** "loading" | "transcribing" | "saving" | "embedding" | "done" | "failed" */
+  stage: string;
+  processed_ms: number;
+  total_ms: number | null;
+  segments: number;
+  error: string | null;
+}
+
+export interface FinalPassCompleteEvent {
+  meeting_id: string;
+  segments: number;
+  /** The live transcript was kept (see get_live_transcript) */
+  live_kept: boolean;
+}
+
+export interface TranslationEvent {
+  segment_id: string;
+  text: string;
+  /** Language of the translation */
+  language: string;
 }
 
 export interface Summary {